@@ -0,0 +1,34 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Embed the commit gitx is built from and the build profile for `gitx version`
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    
+    // Rebuild when HEAD moves, either to another branch or to a new commit on it
+    let head = Path::new(".git/HEAD");
+    if head.exists() {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        if let Ok(contents) = std::fs::read_to_string(head)
+            && let Some(branch_ref) = contents.trim().strip_prefix("ref: ")
+            && Path::new(".git").join(branch_ref).exists()
+        {
+            println!("cargo:rerun-if-changed=.git/{}", branch_ref);
+        }
+    }
+    
+    // Builds from a source tarball have no repository to ask
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short=10", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    
+    println!("cargo:rustc-env=GITX_GIT_HASH={}", git_hash);
+    println!("cargo:rustc-env=GITX_BUILD_PROFILE={}", std::env::var("PROFILE").unwrap_or_else(|_| "unknown".to_string()));
+    // Picks the matching binary when `gitx update` downloads a release
+    println!("cargo:rustc-env=GITX_TARGET={}", std::env::var("TARGET").unwrap_or_else(|_| "unknown".to_string()));
+}
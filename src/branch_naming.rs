@@ -1,27 +1,82 @@
 /// Branch naming utilities for transient PR branches
 
+use regex::Regex;
+
 /// Generate a transient PR branch name from a commit message
 /// Format: gitx/{username}/{sanitized-commit-title}
+/// If the commit references a ticket, the slug is prefixed with it: gitx/{username}/{ticket}-{title}
 pub fn generate_branch_name(username: &str, commit_message: &str) -> String {
-    let sanitized_title = sanitize_commit_title(commit_message);
+    let sanitized_title = match extract_ticket(commit_message) {
+        Some(ticket) => {
+            let title = commit_message.lines().next().unwrap_or("").replace(&ticket, "");
+            let ticket_slug = sanitize_commit_title(&ticket);
+            match sanitize_commit_title(&title).as_str() {
+                "untitled" => ticket_slug,
+                title_slug => sanitize_commit_title(&format!("{}-{}", ticket_slug, title_slug)),
+            }
+        }
+        None => sanitize_commit_title(commit_message),
+    };
     format!("gitx/{}/{}", username, sanitized_title)
 }
 
+/// Generate a transient PR branch name with a short commit hash suffix
+/// Format: gitx/{username}/{sanitized-commit-title}-{short-hash}
+pub fn generate_branch_name_with_hash(username: &str, commit_message: &str, commit_id: &str) -> String {
+    let short_hash = &commit_id[..commit_id.len().min(7)];
+    format!("{}-{}", generate_branch_name(username, commit_message), short_hash)
+}
+
+/// Make a user name safe to use as the branch namespace
+/// - Keep case, so a GitHub login like `LiXuanqi` is used as-is
+/// - Replace spaces and other characters git rejects in ref names with hyphens
+/// - Fall back to `unknown` if nothing usable is left
+pub fn sanitize_username(username: &str) -> String {
+    let mut sanitized = username
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '.' { c } else { '-' })
+        .collect::<String>();
+    
+    while sanitized.contains("--") {
+        sanitized = sanitized.replace("--", "-");
+    }
+    
+    let sanitized = sanitized.trim_matches(|c| c == '-' || c == '.').to_string();
+    if sanitized.is_empty() {
+        "unknown".to_string()
+    } else {
+        sanitized
+    }
+}
+
+/// Longest slug `sanitize_commit_title` produces
+const MAX_SLUG_LENGTH: usize = 50;
+
+/// Check whether the character at `index` has a letter or digit on both sides
+fn is_inside_word(chars: &[char], index: usize) -> bool {
+    let is_word = |c: Option<&char>| c.is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    index > 0 && is_word(chars.get(index - 1)) && is_word(chars.get(index + 1))
+}
+
 /// Sanitize commit title to be suitable for branch names
 /// - Convert to lowercase
 /// - Replace spaces and special chars with hyphens
-/// - Limit length to 50 characters
+/// - Keep `.` and `_` between letters or digits, so `OAuth2.0` and `v1.2.3` stay readable
+/// - Limit length to 50 characters, cutting at a word boundary where possible
 /// - Remove consecutive hyphens
 fn sanitize_commit_title(commit_message: &str) -> String {
     // Take first line only (commit title)
     let title = commit_message.lines().next().unwrap_or("").trim();
     
     // Convert to lowercase and replace problematic characters
-    let mut sanitized = title
-        .to_lowercase()
-        .chars()
-        .map(|c| match c {
+    let chars: Vec<char> = title.to_lowercase().chars().collect();
+    let mut sanitized = chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| match c {
             'a'..='z' | '0'..='9' => c,
+            '.' | '_' if is_inside_word(&chars, i) => c,
             _ => '-',
         })
         .collect::<String>();
@@ -34,10 +89,20 @@ fn sanitize_commit_title(commit_message: &str) -> String {
     // Trim hyphens from start and end
     sanitized = sanitized.trim_matches('-').to_string();
     
-    // Limit length to 50 characters
-    if sanitized.len() > 50 {
-        sanitized.truncate(50);
-        sanitized = sanitized.trim_matches('-').to_string();
+    // Limit length to 50 characters, cutting between words when there is more than one
+    if sanitized.chars().count() > MAX_SLUG_LENGTH {
+        let cut: String = sanitized.chars().take(MAX_SLUG_LENGTH).collect();
+        let ends_on_word = sanitized.chars().nth(MAX_SLUG_LENGTH) == Some('-');
+        sanitized = match cut.rfind('-') {
+            Some(index) if !ends_on_word && index > 0 => cut[..index].to_string(),
+            _ => cut,
+        };
+        sanitized = sanitized.trim_matches(|c| c == '-' || c == '.' || c == '_').to_string();
+    }
+    
+    // git refuses ref names ending in `.lock`
+    if let Some(stem) = sanitized.strip_suffix(".lock") {
+        sanitized = format!("{}-lock", stem);
     }
     
     // Ensure we have something
@@ -48,6 +113,21 @@ fn sanitize_commit_title(commit_message: &str) -> String {
     sanitized
 }
 
+/// Extract a ticket reference (e.g. `JIRA-123` or `#456`) from a commit message
+/// using the pattern configured in `gitx.branch.ticketPattern`
+pub fn extract_ticket(commit_message: &str) -> Option<String> {
+    extract_ticket_with_pattern(commit_message, &crate::config::get_ticket_pattern())
+}
+
+/// Extract a ticket reference using an explicit regex pattern
+/// The first capture group is used if present, otherwise the whole match
+pub fn extract_ticket_with_pattern(commit_message: &str, pattern: &str) -> Option<String> {
+    let regex = Regex::new(pattern).ok()?;
+    let captures = regex.captures(commit_message)?;
+    let ticket = captures.get(1).or_else(|| captures.get(0))?;
+    Some(ticket.as_str().to_string())
+}
+
 /// Check if a branch name follows our transient PR pattern
 pub fn is_transient_pr_branch(branch_name: &str) -> bool {
     branch_name.starts_with("gitx/") && branch_name.matches('/').count() == 2
@@ -96,12 +176,53 @@ mod tests {
         assert_eq!(sanitize_commit_title(""), "untitled");
         assert_eq!(sanitize_commit_title("---"), "untitled");
         
+        // Standalone punctuation still becomes a hyphen
+        assert_eq!(sanitize_commit_title("Fix crash . on exit"), "fix-crash-on-exit");
+        assert_eq!(sanitize_commit_title("Remove Cargo.lock"), "remove-cargo-lock");
+        
         // Test length limiting
         let long_title = "a".repeat(60);
         let sanitized = sanitize_commit_title(&long_title);
         assert!(sanitized.len() <= 50);
     }
 
+    #[test]
+    fn test_sanitize_commit_title_truncates_at_word_boundary() {
+        // A single word longer than the limit is cut mid-word
+        assert_eq!(sanitize_commit_title(&"a".repeat(60)), "a".repeat(50));
+        
+        // A hyphen right at position 50 ends the slug on the word before it
+        let title = format!("{} tail", "b".repeat(50));
+        assert_eq!(sanitize_commit_title(&title), "b".repeat(50));
+        
+        // Otherwise the word running past the limit is dropped
+        let slug = sanitize_commit_title("Refactor the payment reconciliation job to batch ledger writes");
+        assert_eq!(slug, "refactor-the-payment-reconciliation-job-to-batch");
+        assert!(!slug.ends_with('-'));
+        
+        let slug = sanitize_commit_title(&format!("{} {}", "c".repeat(45), "d".repeat(10)));
+        assert_eq!(slug, "c".repeat(45));
+    }
+
+    #[test]
+    fn test_sanitize_commit_title_keeps_version_dots() {
+        assert_eq!(sanitize_commit_title("Upgrade to OAuth2.0"), "upgrade-to-oauth2.0");
+        assert_eq!(sanitize_commit_title("Release v1.2.3"), "release-v1.2.3");
+        assert_eq!(sanitize_commit_title("Rename snake_case helpers"), "rename-snake_case-helpers");
+        // A dot ending the title isn't between two words
+        assert_eq!(sanitize_commit_title("Bump version to 2."), "bump-version-to-2");
+    }
+
+    #[test]
+    fn test_sanitize_username() {
+        // GitHub logins keep their case
+        assert_eq!(sanitize_username("LiXuanqi"), "LiXuanqi");
+        // git user.name values often contain spaces
+        assert_eq!(sanitize_username("Li Xuanqi"), "Li-Xuanqi");
+        assert_eq!(sanitize_username("  Jane  Q. Doe "), "Jane-Q.-Doe");
+        assert_eq!(sanitize_username("~:?"), "unknown");
+    }
+
     #[test]
     fn test_generate_branch_name() {
         assert_eq!(
@@ -114,10 +235,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generate_branch_name_with_hash() {
+        assert_eq!(
+            generate_branch_name_with_hash("alice", "Add auth", "1a2b3c4d5e6f7890"),
+            "gitx/alice/add-auth-1a2b3c4"
+        );
+        
+        // Two commits sharing a subject should produce distinct branches
+        let first = generate_branch_name_with_hash("alice", "Fix typo", "1111111aaaaaaa");
+        let second = generate_branch_name_with_hash("alice", "Fix typo", "2222222bbbbbbb");
+        assert_ne!(first, second);
+        assert!(is_transient_pr_branch(&first));
+        assert!(is_transient_pr_branch(&second));
+    }
+
+    #[test]
+    fn test_extract_ticket_jira_key() {
+        let pattern = crate::config::DEFAULT_TICKET_PATTERN;
+        assert_eq!(
+            extract_ticket_with_pattern("JIRA-123: Add user authentication", pattern),
+            Some("JIRA-123".to_string())
+        );
+        assert_eq!(
+            extract_ticket_with_pattern("Add login\n\nRefs: PROJ-42", pattern),
+            Some("PROJ-42".to_string())
+        );
+        assert_eq!(
+            extract_ticket_with_pattern("[ABC-7] Add UTF-8 support", pattern),
+            Some("ABC-7".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_ticket_github_issue() {
+        let pattern = crate::config::DEFAULT_TICKET_PATTERN;
+        assert_eq!(
+            extract_ticket_with_pattern("Fix crash on startup (#123)", pattern),
+            Some("#123".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_ticket_none() {
+        let pattern = crate::config::DEFAULT_TICKET_PATTERN;
+        assert_eq!(extract_ticket_with_pattern("Add user authentication", pattern), None);
+        assert_eq!(extract_ticket_with_pattern("Add UTF-8 support", pattern), None);
+        assert_eq!(extract_ticket_with_pattern("Switch checksums to SHA-256", pattern), None);
+        assert_eq!(extract_ticket_with_pattern("Enable HTTP-2 for uploads\n\nFalls back to HTTP-1 on old proxies", pattern), None);
+        assert_eq!(extract_ticket_with_pattern("Invalid pattern", "(["), None);
+    }
+
+    #[test]
+    fn test_extract_ticket_custom_pattern_with_capture_group() {
+        assert_eq!(
+            extract_ticket_with_pattern("Fix bug [ticket:789]", r"\[ticket:(\d+)\]"),
+            Some("789".to_string())
+        );
+    }
+
+    #[test]
+    fn test_generate_branch_name_with_ticket() {
+        assert_eq!(
+            generate_branch_name("alice", "JIRA-123: Add user authentication"),
+            "gitx/alice/jira-123-add-user-authentication"
+        );
+        assert_eq!(
+            generate_branch_name("bob", "Fix crash on startup (#456)"),
+            "gitx/bob/456-fix-crash-on-startup"
+        );
+    }
+
     #[test]
     fn test_is_transient_pr_branch() {
         assert!(is_transient_pr_branch("gitx/alice/add-user-auth"));
         assert!(is_transient_pr_branch("gitx/bob/fix-bug"));
+        assert!(is_transient_pr_branch("gitx/alice/add-auth-1a2b3c4"));
         assert!(!is_transient_pr_branch("main"));
         assert!(!is_transient_pr_branch("feature/new-ui"));
         assert!(!is_transient_pr_branch("gitx/alice")); // Missing feature name
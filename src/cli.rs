@@ -1,9 +1,24 @@
 use clap::{Parser, Subcommand};
+use crate::metadata::NotesMergeStrategy;
+use crate::status_display::{ColorChoice, SortKey, StatusFilter};
 
 #[derive(Parser)]
 #[command(name = "gitx")]
 #[command(about = "A Git extension tool")]
+#[command(version = crate::commands::version::SHORT_VERSION)]
 pub struct Cli {
+    /// Never contact GitHub; work only with local branches and metadata (also GITX_OFFLINE=1)
+    #[arg(long, global = true)]
+    pub offline: bool,
+    
+    /// Only print results and errors, without progress output
+    #[arg(long, short, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    
+    /// Also log every GitHub request and response
+    #[arg(long, short, global = true)]
+    pub verbose: bool,
+    
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -11,10 +26,23 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Branch operations
-    Branch,
-    /// Create a commit (passthrough to git commit)
+    Branch {
+        /// Switch even if it discards uncommitted changes
+        #[arg(long)]
+        force: bool,
+        /// Create a new branch and switch to it
+        #[arg(long, value_name = "NAME", conflicts_with = "delete")]
+        new: Option<String>,
+        /// Start point for --new (defaults to HEAD)
+        #[arg(long, value_name = "REF", requires = "new")]
+        from: Option<String>,
+        /// Delete a local branch
+        #[arg(long, value_name = "NAME")]
+        delete: Option<String>,
+    },
+    /// Create a commit (passthrough to git commit); `--amend` also updates the commit's PR
     Commit {
-        /// Arguments to pass to git commit
+        /// Arguments to pass to git commit, e.g. `-m <msg>`, `--no-verify` or `--amend`
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
@@ -23,27 +51,261 @@ pub enum Commands {
         /// Show all commits and let user choose interactively
         #[arg(long)]
         all: bool,
+        /// With --all, process every commit without prompting (automatic when stdin isn't a terminal)
+        #[arg(long, short, visible_alias = "all-commits")]
+        yes: bool,
+        /// Show complete commit SHAs when choosing commits with --all
+        #[arg(long)]
+        full_sha: bool,
+        /// Process only this commit (it must be reachable from HEAD)
+        #[arg(long, value_name = "SHA", conflicts_with = "all")]
+        commit: Option<String>,
+        /// Process every commit after this ref (e.g. origin/main) instead of only the latest
+        #[arg(long, value_name = "REF", conflicts_with = "commit")]
+        since: Option<String>,
+        /// Process exactly the commits whose shas are read from stdin, one per line
+        #[arg(long, conflicts_with_all = ["all", "commit", "since"])]
+        stdin: bool,
+        /// Commit the staged changes with this message first, then open the PR for that commit
+        #[arg(long, short = 'm', value_name = "MSG", conflicts_with_all = ["all", "commit", "since", "stdin", "dry_run"])]
+        message: Option<String>,
         /// Show what would be done without creating PRs
         #[arg(long)]
         dry_run: bool,
+        /// Create new PRs as drafts
+        #[arg(long)]
+        draft: bool,
+        /// Label to add to new PRs (repeatable)
+        #[arg(long = "label")]
+        labels: Vec<String>,
+        /// Reviewer to request on new PRs (repeatable, added to gitx.pr.reviewers)
+        #[arg(long = "reviewer")]
+        reviewers: Vec<String>,
+        /// User to assign to new PRs (repeatable)
+        #[arg(long = "assignee")]
+        assignees: Vec<String>,
+        /// Review each new PR's title and body in your git editor before creating it
+        #[arg(long)]
+        edit: bool,
+        /// Skip commits that conflict with their base branch instead of pushing them
+        #[arg(long)]
+        skip_conflicts: bool,
+        /// Open PRs against this branch instead of the auto-detected base
+        #[arg(long)]
+        base: Option<String>,
+        /// Overwrite PR branches that diverged on the remote (uses --force-with-lease)
+        #[arg(long)]
+        force: bool,
+        /// Skip merge commits, following first parents, instead of stopping at them
+        #[arg(long)]
+        allow_merges: bool,
+        /// Push the branch without opening a PR; a later `gitx diff` opens it
+        #[arg(long, conflicts_with_all = ["draft", "labels", "reviewers", "assignees", "edit"])]
+        push_only: bool,
+    },
+    /// Mark a draft PR as ready for review
+    Ready {
+        /// The PR branch to mark as ready
+        branch: String,
+    },
+    /// Remove a commit from the stack, close its PR and restack the commits above it
+    Drop {
+        /// Commit to drop
+        sha: String,
+        /// Also delete the dropped PR's branch locally and on the remote
+        #[arg(long)]
+        delete_branch: bool,
+    },
+    /// Commit the staged changes (or an empty commit) into the middle of the stack and update the PRs above it
+    Insert {
+        /// Commit to insert after (defaults to the commit below HEAD)
+        #[arg(long, value_name = "SHA")]
+        after: Option<String>,
+        /// Message for the new commit
+        #[arg(long, short = 'm', value_name = "MSG")]
+        message: String,
+    },
+    /// Change the order of the commits in the stack and update their PRs
+    Reorder {
+        /// Commits in their new order, bottom of the stack first (prompts when omitted)
+        commits: Vec<String>,
+    },
+    /// Change a commit's subject line and retitle its PR
+    Reword {
+        /// New subject line (the commit body is kept)
+        message: String,
+        /// Commit to reword (defaults to HEAD); commits above it are rewritten
+        #[arg(long)]
+        sha: Option<String>,
+    },
+    /// Close a PR without merging it
+    Close {
+        /// The PR branch to close
+        branch: String,
+        /// Also delete the PR branch locally and on the remote
+        #[arg(long)]
+        delete_branch: bool,
+    },
+    /// Start tracking a PR that was created outside gitx
+    Adopt {
+        /// Number of the PR to adopt
+        pr: u64,
+    },
+    /// Fetch a PR and check it out into a local branch
+    Checkout {
+        /// PR number (e.g. 123 or #123) or PR branch name
+        pr: String,
+        /// Switch even if it discards uncommitted changes
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show the commits on the current branch with the PRs tracking them
+    Log,
+    /// Remove gitx metadata for commits that no longer exist or whose PR branch is gone
+    Prune {
+        /// Show what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Push gitx metadata notes to origin
+    PushNotes,
+    /// Fetch gitx metadata notes from origin and merge them locally
+    FetchNotes {
+        /// How to resolve metadata that changed both locally and on the remote
+        #[arg(long, value_enum, default_value = "merge")]
+        strategy: NotesMergeStrategy,
     },
     /// Show status of current stacked PRs
-    Prs,
+    Prs {
+        /// When to colorize output (NO_COLOR is honored in auto mode)
+        #[arg(long, value_enum, default_value = "auto")]
+        color: ColorChoice,
+        /// Print one stable tab-separated line per PR for scripts
+        #[arg(long)]
+        porcelain: bool,
+        /// Print the tracked PRs as CSV for spreadsheets
+        #[arg(long, conflicts_with_all = ["porcelain", "remote"])]
+        csv: bool,
+        /// Order to list PRs in
+        #[arg(long, value_enum, default_value = "created")]
+        sort: SortKey,
+        /// Only show PRs in this state
+        #[arg(long, value_enum)]
+        filter: Option<StatusFilter>,
+        /// Fetch fresh PR statuses instead of using the cache
+        #[arg(long)]
+        refresh: bool,
+        /// Also list open PRs on GitHub that gitx isn't tracking
+        #[arg(long)]
+        remote: bool,
+        /// Only show PRs without updates for gitx.prs.staleDays (default 14) days
+        #[arg(long)]
+        stale: bool,
+    },
+    /// Summarize the tracked PRs: counts, updates per PR and time to merge
+    Stats {
+        /// Print the numbers as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Show git status (passthrough to git status)
     Status {
+        /// Also list the commits in the stack and the PRs tracking them (must come before git arguments)
+        #[arg(long)]
+        stack: bool,
         /// Arguments to pass to git status
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
     /// Initialize gitx configuration for this repository
-    Init,
+    Init {
+        /// GitHub personal access token
+        #[arg(long)]
+        token: Option<String>,
+        /// Default base branch for PRs
+        #[arg(long)]
+        base_branch: Option<String>,
+        /// Enable GitHub integration (pass false to disable)
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        enable_github: Option<bool>,
+        /// Clean up merged branches automatically (pass false to disable)
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        auto_cleanup: Option<bool>,
+        /// Add the gitx notes ref to remote.origin.fetch
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        share_notes: Option<bool>,
+        /// Install a post-commit hook that runs gitx diff (pass false to skip)
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        hooks: Option<bool>,
+        /// Don't install the post-commit hook (same as --hooks false)
+        #[arg(long, conflicts_with = "hooks")]
+        no_hooks: bool,
+        /// Store the token in the system keyring instead of git config
+        ///
+        /// On Linux this needs a Secret Service provider such as GNOME Keyring or KWallet;
+        /// without one, leave this off to keep the token in git config or use GITHUB_TOKEN.
+        #[arg(long)]
+        keyring: bool,
+        /// Accept defaults for anything not supplied instead of prompting
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Check that gitx is set up correctly for this repository
+    Doctor,
+    /// Get, set or list gitx settings
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
     /// Clean up merged PRs and sync with remote
     Land {
+        /// Only land this PR number instead of scanning every tracked PR
+        number: Option<u64>,
         /// Clean up all merged PRs
-        #[arg(long)]
+        #[arg(long, conflicts_with = "number")]
         all: bool,
         /// Show what would be cleaned up without making changes
         #[arg(long)]
         dry_run: bool,
+        /// Clean up the given PR even if it isn't merged
+        #[arg(long, requires = "number")]
+        force: bool,
+        /// Leave merged PR branches on the remote (default when gitx.branch.autoCleanup is false)
+        #[arg(long)]
+        keep_remote: bool,
+        /// Also clean up PRs that were closed without merging
+        #[arg(long, conflicts_with = "number")]
+        include_closed: bool,
+    },
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+    /// Install the latest gitx release, if it is newer than this one
+    Update {
+        /// Only report whether a newer release exists
+        #[arg(long)]
+        check_only: bool,
+    },
+    /// Print the gitx version, the commit it was built from and the libgit2 version
+    Version,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print the value of a gitx setting
+    Get {
+        /// Setting key, e.g. github.baseBranch (the gitx. prefix is optional)
+        key: String,
+    },
+    /// Set a gitx setting for this repository
+    Set {
+        /// Setting key, e.g. github.baseBranch (the gitx. prefix is optional)
+        key: String,
+        /// Value to store
+        value: String,
     },
+    /// List all gitx settings and their current values
+    List,
 }
\ No newline at end of file
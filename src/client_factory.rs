@@ -3,6 +3,10 @@ use crate::github::GitHubClientTrait;
 /// Factory function to create GitHub client - returns real client in production
 #[cfg(not(test))]
 pub async fn create_github_client() -> Result<Box<dyn GitHubClientTrait>, Box<dyn std::error::Error>> {
+    if crate::config::is_offline() {
+        return Err("Offline mode is on (--offline or GITX_OFFLINE); not contacting GitHub".into());
+    }
+    
     // Allow tests to force use of mock client via environment variable
     if std::env::var("GITX_USE_MOCK_GITHUB").is_ok() {
         let mock_client = crate::mock_github::MockGitHubClient::new();
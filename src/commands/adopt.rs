@@ -0,0 +1,18 @@
+use crate::git_ops;
+
+pub async fn handle_adopt(pr: u64) -> Result<(), Box<dyn std::error::Error>> {
+    match git_ops::adopt_pr(pr).await {
+        Ok((commit_id, metadata)) => {
+            println!(
+                "📥 Adopted PR #{} ({}) at commit {}",
+                pr,
+                metadata.pr_branch_name,
+                &commit_id.to_string()[..7]
+            );
+        }
+        Err(e) => {
+            eprintln!("Error adopting PR: {}", e);
+        }
+    }
+    Ok(())
+}
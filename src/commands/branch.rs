@@ -1,7 +1,40 @@
 use inquire::Select;
 use crate::git_ops;
 
-pub fn handle_branch() -> Result<(), Box<dyn std::error::Error>> {
+pub fn handle_branch(
+    force: bool,
+    new: Option<&str>,
+    from: Option<&str>,
+    delete: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    git_ops::ensure_on_branch()?;
+    
+    if let Some(branch_name) = delete {
+        match git_ops::delete_branch(branch_name) {
+            Ok(()) => println!("Deleted branch: {}", branch_name),
+            Err(e) => eprintln!("Error deleting branch '{}': {}", branch_name, e.message()),
+        }
+        return Ok(());
+    }
+    
+    if let Some(branch_name) = new {
+        if let Err(e) = git_ops::create_branch(branch_name, from) {
+            eprintln!("Error creating branch '{}': {}", branch_name, e.message());
+            return Ok(());
+        }
+        match git_ops::switch_branch(branch_name, force) {
+            Ok(()) => println!("Switched to a new branch: {}", branch_name),
+            Err(e) => {
+                // Don't leave the branch behind, or retrying would fail with "already exists"
+                if let Err(delete_error) = git_ops::delete_branch(branch_name) {
+                    eprintln!("Warning: Could not remove branch '{}': {}", branch_name, delete_error.message());
+                }
+                eprintln!("Error switching to new branch '{}': {}", branch_name, e.message());
+            }
+        }
+        return Ok(());
+    }
+    
     match git_ops::get_all_branches() {
         Ok(branches) => {
             if branches.is_empty() {
@@ -13,7 +46,7 @@ pub fn handle_branch() -> Result<(), Box<dyn std::error::Error>> {
             
             match selection {
                 Ok(chosen_branch) => {
-                    match git_ops::switch_branch(&chosen_branch) {
+                    match git_ops::switch_branch(&chosen_branch, force) {
                         Ok(()) => {
                             println!("Switched to branch: {}", chosen_branch);
                         }
@@ -0,0 +1,13 @@
+use crate::git_ops;
+
+pub async fn handle_checkout(pr: &str, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+    match git_ops::checkout_pr(pr, force).await {
+        Ok(branch) => {
+            println!("✅ Checked out {} into branch: {}", pr, branch);
+        }
+        Err(e) => {
+            eprintln!("Error checking out PR: {}", e);
+        }
+    }
+    Ok(())
+}
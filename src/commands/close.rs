@@ -0,0 +1,16 @@
+use crate::git_ops;
+
+pub async fn handle_close(branch: &str, delete_branch: bool) -> Result<(), Box<dyn std::error::Error>> {
+    match git_ops::close_pr(branch, delete_branch).await {
+        Ok(pr_number) => {
+            println!("🔴 Closed PR #{}", pr_number);
+            if delete_branch {
+                println!("🗑️  Deleted branch: {}", branch);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error closing PR: {}", e);
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
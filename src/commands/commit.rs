@@ -1,21 +1,75 @@
 use std::process::Command;
+use git2::Oid;
+use crate::commands::diff;
+use crate::config;
+use crate::git_ops;
+use crate::git_utils::open_repo;
+use crate::github::PRCreateOptions;
+use crate::metadata;
 
-pub fn handle_commit(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+/// Check whether the git commit arguments amend HEAD (arguments after `--` are paths)
+fn is_amend(args: &[String]) -> bool {
+    args.iter().take_while(|arg| *arg != "--").any(|arg| arg == "--amend")
+}
+
+fn head_commit_id() -> Result<Oid, Box<dyn std::error::Error>> {
+    Ok(open_repo()?.head()?.peel_to_commit()?.id())
+}
+
+/// Flags such as `--no-verify`, `-S` or `--fixup` are passed to `git commit` untouched
+pub async fn handle_commit(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    // Amending replaces HEAD, so remember the commit its PR metadata is stored on
+    let amended_from = if is_amend(args) {
+        // A detached HEAD's amendment would be left off every branch
+        git_ops::ensure_on_branch()?;
+        head_commit_id().ok()
+    } else {
+        None
+    };
+    
     // Passthrough to git commit with all provided arguments
     let mut cmd = Command::new("git");
     cmd.arg("commit");
     cmd.args(args);
+    if amended_from.is_some() {
+        // The post-commit hook would otherwise open a new PR for the amended commit
+        cmd.env("GITX_AMENDING", "1");
+    }
+    
+    let status = cmd.status().map_err(|e| format!("Error running git commit: {}", e))?;
+    if !status.success() {
+        return Err(format!("git commit failed ({})", status).into());
+    }
     
-    match cmd.status() {
-        Ok(status) => {
-            if !status.success() {
-                std::process::exit(status.code().unwrap_or(1));
-            }
-        }
-        Err(e) => {
-            eprintln!("Error running git commit: {}", e);
-            std::process::exit(1);
-        }
+    // Keep an amended commit attached to its PR and push the amendment to it
+    if let Some(old_head) = amended_from
+        && metadata::get_commit_metadata(&old_head)?.is_some()
+    {
+        let new_head = head_commit_id()?;
+        metadata::move_commit_metadata(&old_head, &new_head)?;
+        println!("🔗 Moved PR metadata to the amended commit {}", &new_head.to_string()[..8]);
+        diff::handle_diff(false, true, false, &["HEAD".to_string()], None, false, PRCreateOptions::default()).await?;
+        return Ok(());
+    }
+    
+    // With gitx.commit.autoDiff, open or update the PR for the commit just made
+    if config::is_commit_auto_diff_enabled() {
+        diff::handle_diff(false, true, false, &["HEAD".to_string()], None, false, PRCreateOptions::default()).await?;
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_amend() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        
+        assert!(is_amend(&args(&["--amend", "--no-edit"])));
+        assert!(is_amend(&args(&["--no-verify", "--amend", "-m", "Message"])));
+        assert!(!is_amend(&args(&["-m", "Message", "--", "--amend"])));
+        assert!(!is_amend(&args(&["--no-verify", "-m", "Message"])));
+    }
+}
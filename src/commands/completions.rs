@@ -0,0 +1,11 @@
+use clap::CommandFactory;
+use clap_complete::Shell;
+use crate::cli::Cli;
+
+pub fn handle_completions(shell: Shell) -> Result<(), Box<dyn std::error::Error>> {
+    // Generate from the derived command so completions always match the real CLI
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,34 @@
+use crate::cli::ConfigAction;
+use crate::config::{self, SettingKind};
+
+pub fn handle_config(action: &ConfigAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        ConfigAction::Get { key } => {
+            let setting = config::find_setting(key)?;
+            match config::get_git_config(setting.key)? {
+                Some(value) => println!("{}", value),
+                None => return Err(format!("{} is not set", setting.key).into()),
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let setting = config::find_setting(key)?;
+            config::validate_setting_value(setting, value)?;
+            
+            config::set_git_config(setting.key, value)?;
+            println!("✅ {} updated", setting.key);
+        }
+        ConfigAction::List => {
+            let current = config::current();
+            for setting in config::KNOWN_SETTINGS {
+                let value = match (current.get(setting.key), setting.kind) {
+                    (Some(value), SettingKind::Secret) => config::redact_secret(value),
+                    (Some(value), _) => value.to_string(),
+                    (None, _) => "(not set)".to_string(),
+                };
+                println!("{} = {}", setting.key, value);
+                println!("    {}", setting.description);
+            }
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -1,75 +1,200 @@
+use std::io::IsTerminal;
+use std::process::Command;
 use inquire::MultiSelect;
+use crate::config;
 use crate::git_ops;
+use crate::github::PRCreateOptions;
+use crate::github_utils::{self, GitHubRepo, PlannedRequest};
+use crate::progress::{self, Progress};
 
-/// Display commits and let user interactively select which ones to process
-fn select_commits_to_process(updates: &[git_ops::CommitUpdateType]) -> Result<Vec<git_ops::CommitUpdateType>, Box<dyn std::error::Error>> {
-    // Create display options for the user with indices
-    let options: Vec<(usize, String)> = updates.iter().enumerate().map(|(i, update)| {
-        let display = match update {
-            git_ops::CommitUpdateType::NewCommit(commit) => {
-                let short_id = &commit.id.to_string()[..8];
-                let title = commit.message.lines().next().unwrap_or("Untitled");
-                format!("🆕 {} {} (new commit)", short_id, title)
-            }
-            git_ops::CommitUpdateType::IncrementalUpdate { updated_oid, metadata, .. } => {
-                let short_id = &updated_oid.to_string()[..8];
-                let title = metadata.pr_branch_name.split('/').last().unwrap_or("unknown");
-                format!("🔄 {} {} (incremental update)", short_id, title)
-            }
-        };
-        (i, display)
-    }).collect();
+/// Build the selector line for one update, e.g. "🆕 1a2b3c4d Add login (new commit)"
+fn format_update_option(update: &git_ops::CommitUpdateType, full_sha: bool) -> String {
+    let display_id = |oid: &git2::Oid| {
+        let id = oid.to_string();
+        if full_sha { id } else { id[..8].to_string() }
+    };
     
-    // Extract just the display strings for the menu
-    let option_strings: Vec<String> = options.iter().map(|(_, display)| display.clone()).collect();
+    match update {
+        git_ops::CommitUpdateType::NewCommit(commit) => {
+            let title = commit.message.lines().next().unwrap_or("Untitled");
+            format!("🆕 {} {} (new commit)", display_id(&commit.id), title)
+        }
+        git_ops::CommitUpdateType::IncrementalUpdate { updated_oid, message, metadata, .. } => {
+            let title = message.lines().next().unwrap_or("Untitled");
+            let slug = metadata.pr_branch_name.rsplit('/').next().unwrap_or("unknown");
+            format!("🔄 {} {} (incremental update to {})", display_id(updated_oid), title, slug)
+        }
+    }
+}
+
+/// Display commits and let user interactively select which ones to process
+fn select_commits_to_process(updates: &[git_ops::CommitUpdateType], full_sha: bool) -> Result<Vec<git_ops::CommitUpdateType>, Box<dyn std::error::Error>> {
+    // One display string per update, in the same order
+    let options: Vec<String> = updates.iter().map(|update| format_update_option(update, full_sha)).collect();
     
-    // Show multi-select menu
-    let selected_options = MultiSelect::new("Select commits to process:", option_strings)
-        .with_help_message("Use space to select/deselect, arrow keys to navigate, enter to confirm")
-        .prompt()?;
+    // Show multi-select menu with everything checked, so processing all of it is a single Enter
+    let selected_options = MultiSelect::new("Select commits to process:", options)
+        .with_all_selected_by_default()
+        .with_help_message("All commits are selected; use space to deselect, arrow keys to navigate, enter to confirm")
+        .raw_prompt()?;
     
     if selected_options.is_empty() {
         return Err("No commits selected".into());
     }
     
-    // Map selected options back to indices, then to commits
+    // Each selected option carries its position in `updates`, even when two display strings match
     let selected_updates: Vec<git_ops::CommitUpdateType> = selected_options
         .into_iter()
-        .filter_map(|selected_option| {
-            // Find the index for this selected option
-            options.iter()
-                .find(|(_, display)| *display == selected_option)
-                .map(|(index, _)| updates[*index].clone())
-        })
+        .map(|selected_option| updates[selected_option.index].clone())
         .collect();
     
     Ok(selected_updates)
 }
 
-pub async fn handle_diff(all: bool, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
-    let updates = if all {
-        git_ops::get_commits_needing_processing()
+/// GitHub requests processing `update` would make, for `--dry-run --verbose`
+fn planned_requests(update: &git_ops::CommitUpdateType, repo: &GitHubRepo, pr_options: &PRCreateOptions) -> Result<Vec<PlannedRequest>, Box<dyn std::error::Error>> {
+    match update {
+        git_ops::CommitUpdateType::NewCommit(commit) => {
+            if pr_options.push_only {
+                return Ok(Vec::new());
+            }
+            // Title, draft state and base are worked out the way creating the PR would
+            let git_repo = crate::git_utils::open_repo()?;
+            let first_commit = git_repo.find_commit(commit.first_commit())?;
+            let subject = first_commit.message().unwrap_or("").lines().next().unwrap_or("Untitled commit");
+            let base = match &pr_options.base {
+                Some(base) => base.clone(),
+                None => git_ops::determine_base_branch_for_commit(&commit.first_commit())?,
+            };
+            let head = github_utils::pr_head_ref(&commit.potential_branch_name);
+            let draft = pr_options.draft || github_utils::is_wip_title(subject);
+            let mut requests = vec![PlannedRequest::create_pr(repo, &head, &base, &github_utils::pr_title_for_subject(subject), draft)];
+            for (field, values) in [("labels", &pr_options.labels), ("reviewers", &pr_options.reviewers), ("assignees", &pr_options.assignees)] {
+                if !values.is_empty() {
+                    requests.push(PlannedRequest::add_to_new_pr(repo, field, values));
+                }
+            }
+            Ok(requests)
+        }
+        git_ops::CommitUpdateType::IncrementalUpdate { metadata, .. } => Ok(metadata
+            .github_pr_number
+            .map(|pr_number| PlannedRequest::update_pr(repo, pr_number, vec![("body", "<regenerated>".to_string())]))
+            .into_iter()
+            .collect()),
+    }
+}
+
+/// Print the planned requests for `update` when `repo` is set
+fn print_planned_requests(update: &git_ops::CommitUpdateType, repo: Option<&GitHubRepo>, pr_options: &PRCreateOptions) {
+    let Some(repo) = repo else {
+        return;
+    };
+    match planned_requests(update, repo, pr_options) {
+        Ok(requests) => {
+            for request in requests {
+                println!("  {}", request);
+            }
+        }
+        Err(e) => eprintln!("Warning: Could not plan the GitHub requests: {}", e),
+    }
+}
+
+/// Commit shas for `--stdin`, one per line; blank lines are ignored
+pub fn read_commits_from_stdin() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut shas = Vec::new();
+    for line in std::io::stdin().lines() {
+        let line = line?;
+        let sha = line.trim();
+        if !sha.is_empty() {
+            shas.push(sha.to_string());
+        }
+    }
+    if shas.is_empty() {
+        return Err("No commits were given on stdin".into());
+    }
+    Ok(shas)
+}
+
+/// Commit the staged changes with `message`, then open or update the PR for that commit
+pub async fn handle_commit_and_diff(message: &str, pr_options: PRCreateOptions) -> Result<(), Box<dyn std::error::Error>> {
+    git_ops::ensure_on_branch()?;
+    if !git_ops::has_staged_changes()? {
+        return Err("Nothing is staged to commit; stage changes with 'git add' before using 'gitx diff -m'".into());
+    }
+    
+    // Run git itself so commit hooks and signing behave as they would for 'git commit'
+    let status = Command::new("git")
+        .args(["commit", "-m", message])
+        .status()
+        .map_err(|e| format!("Error running git commit: {}", e))?;
+    if !status.success() {
+        return Err(format!("git commit failed ({})", status).into());
+    }
+    
+    handle_diff(false, true, false, &["HEAD".to_string()], None, false, pr_options).await
+}
+
+pub async fn handle_diff(
+    all: bool,
+    yes: bool,
+    full_sha: bool,
+    commits: &[String],
+    since: Option<&str>,
+    dry_run: bool,
+    mut pr_options: PRCreateOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // The stack is read from the branch, so a detached HEAD would process the wrong commits
+    git_ops::ensure_on_branch()?;
+    
+    // Reviewers passed on the command line are added to the configured defaults
+    pr_options = pr_options.with_default_reviewers();
+    pr_options.force |= config::is_push_force_update_enabled();
+    let draft = pr_options.draft;
+    
+    // Name new branches after the GitHub login rather than git's user.name when we can
+    if !config::is_offline()
+        && crate::github::check_github_token()
+        && let Err(e) = crate::github_utils::get_github_login().await
+    {
+        eprintln!("Warning: Could not fetch GitHub login, using git user.name for branch names: {}", e);
+    }
+    
+    let allow_merges = pr_options.allow_merges;
+    let updates = if let [sha] = commits {
+        git_ops::get_commit_needing_processing(sha, allow_merges)
+    } else if !commits.is_empty() {
+        git_ops::get_commits_needing_processing_for(commits, allow_merges)
+    } else if let Some(since) = since {
+        git_ops::get_commits_needing_processing_since(since, allow_merges)
+    } else if all {
+        git_ops::get_commits_needing_processing(allow_merges)
     } else {
-        git_ops::get_latest_commit_needing_processing()
+        git_ops::get_latest_commit_needing_processing(allow_merges)
     };
     
     match updates {
         Ok(updates) => {
             if updates.is_empty() {
                 println!("No new commits or updates to process");
+                if git_ops::has_staged_changes().unwrap_or(false) {
+                    println!("You have staged changes; commit them first, or run 'gitx diff -m <message>' to commit and open a PR in one go");
+                }
                 return Ok(());
             }
             
-            // If --all flag is used, show interactive selection (if multiple commits)
+            // If --all flag is used, show interactive selection (if multiple commits and someone can answer)
             let selected_updates = if all {
-                if updates.len() > 1 {
-                    match select_commits_to_process(&updates) {
+                if updates.len() > 1 && !yes && std::io::stdin().is_terminal() {
+                    match select_commits_to_process(&updates, full_sha) {
                         Ok(selected) => selected,
                         Err(e) => {
                             eprintln!("Selection cancelled: {}", e);
                             return Ok(());
                         }
                     }
+                } else if updates.len() > 1 {
+                    println!("Processing all {} commits:", updates.len());
+                    updates
                 } else {
                     // Only one commit, process it directly
                     println!("Only one commit available, processing it:");
@@ -79,19 +204,44 @@ pub async fn handle_diff(all: bool, dry_run: bool) -> Result<(), Box<dyn std::er
                 updates
             };
             
+            // Offline: keep branches and notes local instead of pushing and opening PRs
+            let enable_github = !config::is_offline();
+            if !enable_github && pr_options.push_only {
+                return Err("--push-only pushes branches, so it can't be used offline".into());
+            }
+            if !enable_github {
+                println!("📴 Offline: managing local branches and metadata only, nothing is pushed");
+            }
+            
+            // At --verbose a dry run also lists the API calls it would make
+            let planned_repo = (dry_run && enable_github && config::verbosity() == config::Verbosity::Verbose)
+                .then(github_utils::planned_repo);
+            
             let mut new_branches = 0;
             let mut incremental_updates = 0;
+            // Dry runs make no network calls, so there is nothing to wait for
+            let progress = if dry_run {
+                Progress::with_enabled(selected_updates.len(), false)
+            } else {
+                Progress::new(selected_updates.len())
+            };
             
-            for update in &selected_updates {
+            for (step, update) in selected_updates.iter().enumerate() {
                 match update {
                     git_ops::CommitUpdateType::NewCommit(commit) => {
                         if dry_run {
-                            println!("Would create PR branch for: {}", commit.message.lines().next().unwrap_or(""));
+                            if pr_options.push_only {
+                                println!("Would push branch without a PR for: {}", commit.message.lines().next().unwrap_or(""));
+                            } else {
+                                println!("Would create {}PR branch for: {}", if draft { "draft " } else { "" }, commit.message.lines().next().unwrap_or(""));
+                            }
+                            print_planned_requests(update, planned_repo.as_ref(), &pr_options);
                             new_branches += 1;
                         } else {
-                            println!("Creating PR branch for: {}", commit.message.lines().next().unwrap_or(""));
+                            tracing::info!("Creating PR branch for: {}", commit.message.lines().next().unwrap_or(""));
+                            progress.start(step, "Creating PR", commit.message.lines().next().unwrap_or(""));
                             
-                            match git_ops::create_pr_branch_with_github(commit, true).await {
+                            match git_ops::create_pr_branch_with_github(commit, enable_github, &pr_options).await {
                                 Ok(Some(_pr_info)) => {
                                     new_branches += 1;
                                 }
@@ -99,44 +249,44 @@ pub async fn handle_diff(all: bool, dry_run: bool) -> Result<(), Box<dyn std::er
                                     new_branches += 1;
                                 }
                                 Err(e) => {
-                                    eprintln!("Error creating branch/PR '{}': {:#}", commit.potential_branch_name, e);
-                                    
                                     // Print the full error chain for debugging
-                                    let mut source = e.source();
-                                    while let Some(err) = source {
-                                        eprintln!("  Caused by: {}", err);
-                                        source = err.source();
-                                    }
+                                    progress::eprintln(format!(
+                                        "Error creating branch/PR '{}': {}",
+                                        commit.potential_branch_name,
+                                        config::format_error_chain(e.as_ref())
+                                    ));
                                 }
                             }
                         }
                     }
-                    git_ops::CommitUpdateType::IncrementalUpdate { original_oid, updated_oid, metadata } => {
+                    git_ops::CommitUpdateType::IncrementalUpdate { original_oid, updated_oid, metadata, commit_type, .. } => {
                         if dry_run {
                             println!("Would create incremental update for: {}", metadata.pr_branch_name);
+                            print_planned_requests(update, planned_repo.as_ref(), &pr_options);
                             incremental_updates += 1;
                         } else {
-                            println!("Creating incremental update for: {}", metadata.pr_branch_name);
+                            tracing::info!("Creating incremental update for: {}", metadata.pr_branch_name);
+                            progress.start(step, "Updating PR", &metadata.pr_branch_name);
                             
-                            match git_ops::create_incremental_commit_with_github(original_oid, updated_oid, metadata, true).await {
+                            match git_ops::create_incremental_commit_with_github(original_oid, updated_oid, metadata, *commit_type, pr_options.force, enable_github).await {
                                 Ok(()) => {
                                     incremental_updates += 1;
                                 }
                                 Err(e) => {
-                                    eprintln!("Error creating incremental commit/PR update for '{}': {:#}", metadata.pr_branch_name, e);
-                                    
                                     // Print the full error chain for debugging
-                                    let mut source = e.source();
-                                    while let Some(err) = source {
-                                        eprintln!("  Caused by: {}", err);
-                                        source = err.source();
-                                    }
+                                    progress::eprintln(format!(
+                                        "Error creating incremental commit/PR update for '{}': {}",
+                                        metadata.pr_branch_name,
+                                        config::format_error_chain(e.as_ref())
+                                    ));
                                 }
                             }
                         }
                     }
                 }
+                progress.inc();
             }
+            progress.finish();
             
             if new_branches > 0 || incremental_updates > 0 {
                 if dry_run {
@@ -146,9 +296,47 @@ pub async fn handle_diff(all: bool, dry_run: bool) -> Result<(), Box<dyn std::er
                 }
             }
         }
+        // Commits the user named explicitly must all be processed, so failing to is an error
+        Err(e) if !commits.is_empty() => return Err(e.into()),
         Err(e) => {
             eprintln!("Error analyzing commits: {}", e);
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::{CommitMetadata, IncrementalCommitType};
+
+    const OID: &str = "1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d";
+
+    #[test]
+    fn test_format_new_commit_option() {
+        let update = git_ops::CommitUpdateType::NewCommit(git_ops::CommitInfo {
+            id: git2::Oid::from_str(OID).unwrap(),
+            message: "Add login\n\nWith a form".to_string(),
+            potential_branch_name: "gitx/octo/add-login".to_string(),
+            grouped_commits: Vec::new(),
+        });
+        
+        assert_eq!(format_update_option(&update, false), "🆕 1a2b3c4d Add login (new commit)");
+        assert_eq!(format_update_option(&update, true), format!("🆕 {} Add login (new commit)", OID));
+    }
+
+    #[test]
+    fn test_format_incremental_update_option() {
+        let oid = git2::Oid::from_str(OID).unwrap();
+        let update = git_ops::CommitUpdateType::IncrementalUpdate {
+            original_oid: oid,
+            updated_oid: oid,
+            message: "Add login form\n\nReworked".to_string(),
+            metadata: CommitMetadata::new_branch_created("gitx/octo/add-login".to_string(), "abc123".to_string()),
+            commit_type: IncrementalCommitType::AmendedCommit,
+        };
+        
+        assert_eq!(format_update_option(&update, false), "🔄 1a2b3c4d Add login form (incremental update to add-login)");
+        assert_eq!(format_update_option(&update, true), format!("🔄 {} Add login form (incremental update to add-login)", OID));
+    }
+}
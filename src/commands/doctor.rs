@@ -0,0 +1,176 @@
+use git2::Repository;
+use crate::client_factory;
+use crate::config;
+use crate::git_utils::GitUtils;
+use crate::metadata;
+
+/// Outcome of a single diagnostic check
+struct Check {
+    name: String,
+    passed: bool,
+    critical: bool,
+    hint: &'static str,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, passed: bool, critical: bool, hint: &'static str) -> Self {
+        Self { name: name.into(), passed, critical, hint }
+    }
+    
+    fn print(&self) {
+        let icon = match (self.passed, self.critical) {
+            (true, _) => "✅",
+            (false, true) => "❌",
+            (false, false) => "⚠️ ",
+        };
+        println!("{} {}", icon, self.name);
+        if !self.passed {
+            println!("   💡 {}", self.hint);
+        }
+    }
+}
+
+pub async fn handle_doctor() -> Result<(), Box<dyn std::error::Error>> {
+    println!("🩺 Checking gitx setup\n");
+    
+    let checks = run_checks().await;
+    for check in &checks {
+        check.print();
+    }
+    
+    let critical_failures = checks.iter().filter(|c| c.critical && !c.passed).count();
+    if critical_failures > 0 {
+        return Err(format!(
+            "{} critical check{} failed",
+            critical_failures,
+            if critical_failures == 1 { "" } else { "s" }
+        ).into());
+    }
+    
+    println!("\n🎉 gitx is ready to use!");
+    Ok(())
+}
+
+async fn run_checks() -> Vec<Check> {
+    let mut checks = Vec::new();
+    
+    // 1. Inside a git repository (nothing else works without one)
+    let repo = match Repository::open(".") {
+        Ok(repo) => {
+            checks.push(Check::new("Inside a git repository", true, true, ""));
+            repo
+        }
+        Err(_) => {
+            checks.push(Check::new(
+                "Inside a git repository",
+                false,
+                true,
+                "Run gitx from the root of a git repository",
+            ));
+            return checks;
+        }
+    };
+    
+    // 2. Origin remote points at GitHub
+    let remote_check = match GitUtils::get_remote_url() {
+        Ok(url) if GitUtils::is_github_url(&url) => {
+            Check::new(format!("Origin remote is a GitHub repository ({})", url), true, true, "")
+        }
+        Ok(url) => {
+            // Name the mismatch rather than just the URL when origin is on another host
+            let detail = GitUtils::host_mismatch(&config::get_remote(), &url, &config::get_github_host()).unwrap_or(url);
+            Check::new(
+                format!("Origin remote is a GitHub repository ({})", detail),
+                false,
+                true,
+                "Point origin at GitHub, or set gitx.github.host for GitHub Enterprise Server",
+            )
+        }
+        Err(_) => Check::new(
+            "Origin remote is a GitHub repository",
+            false,
+            true,
+            "Add one with: git remote add origin git@github.com:<owner>/<repo>.git",
+        ),
+    };
+    let has_github_remote = remote_check.passed;
+    checks.push(remote_check);
+    
+    // 3. Token configured and accepted by GitHub
+    if config::get_github_token().is_none() {
+        checks.push(Check::new(
+            "GitHub token configured",
+            false,
+            true,
+            "Run 'gitx init [--keyring]', log in with 'gh auth login', or export GITHUB_TOKEN",
+        ));
+    } else {
+        let source = config::get_github_token_source().unwrap_or("unknown source");
+        checks.push(Check::new(format!("GitHub token configured (from {})", source), true, true, ""));
+        
+        if has_github_remote {
+            let login = match client_factory::create_github_client().await {
+                Ok(client) => client.get_current_user().await.ok(),
+                Err(_) => None,
+            };
+            checks.push(match login {
+                Some(login) => Check::new(format!("GitHub token is valid (authenticated as {})", login), true, true, ""),
+                None => Check::new(
+                    "GitHub token is valid",
+                    false,
+                    true,
+                    "Create a new token with 'repo' scope at https://github.com/settings/tokens",
+                ),
+            });
+        }
+    }
+    
+    // 4. Metadata notes ref exists locally and on the remote
+    let notes_ref = metadata::notes_ref();
+    let has_local_notes = repo.find_reference(&notes_ref).is_ok();
+    checks.push(Check::new(
+        format!("Metadata notes ref exists ({})", notes_ref),
+        has_local_notes,
+        false,
+        "Notes are created by the first 'gitx diff'",
+    ));
+    
+    if has_github_remote {
+        let remote = config::get_remote();
+        checks.push(Check::new(
+            format!("Metadata notes ref is fetchable from {}", remote),
+            remote_has_notes_ref(&remote, &notes_ref).await,
+            false,
+            "Push notes so they can be shared: gitx push-notes",
+        ));
+    }
+    
+    // 5. Default branch can be detected
+    let base_branch = config::get_base_branch();
+    let detected = [base_branch.as_str(), "main", "master"]
+        .iter()
+        .find(|name| repo.find_reference(&format!("refs/heads/{}", name)).is_ok())
+        .map(|name| name.to_string());
+    checks.push(match detected {
+        Some(name) => Check::new(format!("Default branch detected ({})", name), true, true, ""),
+        None => Check::new(
+            "Default branch detected",
+            false,
+            true,
+            "Create a main/master branch or set gitx.github.baseBranch",
+        ),
+    });
+    
+    checks
+}
+
+/// Check whether `remote` advertises the notes ref
+async fn remote_has_notes_ref(remote: &str, notes_ref: &str) -> bool {
+    let output = tokio::process::Command::new("git")
+        .args(["ls-remote", "--exit-code", remote, notes_ref])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await;
+    
+    matches!(output, Ok(output) if output.status.success())
+}
\ No newline at end of file
@@ -0,0 +1,37 @@
+use git2::Oid;
+use crate::commands::reorder;
+use crate::config;
+use crate::git_ops;
+use crate::metadata;
+
+/// Drop a commit from the stack, abandon its PR and restack the commits above it
+pub async fn handle_drop(sha: &str, delete_branch: bool) -> Result<(), Box<dyn std::error::Error>> {
+    git_ops::ensure_on_branch()?;
+    
+    let (dropped, rewritten) = git_ops::drop_commit(sha)?;
+    println!("🗑️  Dropped commit {}", &dropped.to_string()[..8]);
+    
+    if !rewritten.is_empty() {
+        println!("✅ Restacked {} commit{} onto its parent", rewritten.len(), if rewritten.len() == 1 { "" } else { "s" });
+    }
+    // Retarget the PRs stacked on the dropped one first: GitHub closes PRs whose base branch is deleted
+    let moved: Vec<Oid> = rewritten.iter().map(|(_, new)| *new).collect();
+    reorder::sync_moved_prs(&moved).await?;
+    
+    if let Some(pr_metadata) = metadata::get_commit_metadata(&dropped)? {
+        match pr_metadata.github_pr_number {
+            Some(pr_number) if !config::is_offline() && crate::github::check_github_token() => {
+                match git_ops::close_pr(&pr_metadata.pr_branch_name, delete_branch).await {
+                    Ok(_) => println!("🔴 Closed PR #{}", pr_number),
+                    Err(e) => eprintln!("Warning: Could not close PR #{}: {}; run 'gitx close {}' to retry", pr_number, e, pr_metadata.pr_branch_name),
+                }
+            }
+            Some(pr_number) => {
+                println!("PR #{} is still open; run 'gitx close {}' once GitHub is reachable", pr_number, pr_metadata.pr_branch_name);
+            }
+            None => metadata::update_commit_metadata(&dropped, &pr_metadata.mark_cancelled())?,
+        }
+    }
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,14 @@
+use crate::git_ops;
+use crate::metadata::NotesMergeStrategy;
+
+pub async fn handle_fetch_notes(strategy: NotesMergeStrategy) -> Result<(), Box<dyn std::error::Error>> {
+    match git_ops::fetch_metadata_notes(strategy).await {
+        Ok(()) => {
+            println!("✅ Fetched gitx metadata from {}", crate::config::get_remote());
+        }
+        Err(e) => {
+            eprintln!("Error fetching metadata notes: {}", e);
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
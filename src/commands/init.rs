@@ -1,7 +1,17 @@
+use std::io::IsTerminal;
 use crate::config;
 
-pub fn handle_init() -> Result<(), Box<dyn std::error::Error>> {
-    match config::interactive_init() {
+pub fn handle_init(options: &config::InitOptions, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    // Prompt only when run in a terminal with no settings supplied
+    let interactive = !yes && !options.has_any() && std::io::stdin().is_terminal();
+    
+    let result = if interactive {
+        config::interactive_init(options.keyring)
+    } else {
+        config::non_interactive_init(options)
+    };
+    
+    match result {
         Ok(()) => {
             // Initialization completed successfully
         }
@@ -0,0 +1,19 @@
+use git2::Oid;
+use crate::commands::{diff, reorder};
+use crate::git_ops;
+use crate::github::PRCreateOptions;
+
+/// Splice a new commit into the stack, open its PR and update the PRs above it
+pub async fn handle_insert(after: Option<&str>, message: &str) -> Result<(), Box<dyn std::error::Error>> {
+    git_ops::ensure_on_branch()?;
+    
+    let (inserted, rewritten) = git_ops::insert_commit(after, message)?;
+    let sha = inserted.to_string();
+    println!("✅ Inserted commit {} below {} commit{}", &sha[..8], rewritten.len(), if rewritten.len() == 1 { "" } else { "s" });
+    
+    // The new PR has to exist before the PRs above it can be retargeted onto it
+    diff::handle_diff(false, true, false, &[sha], None, false, PRCreateOptions::default()).await?;
+    
+    let moved: Vec<Oid> = rewritten.iter().map(|(_, new)| *new).collect();
+    reorder::sync_moved_prs(&moved).await
+}
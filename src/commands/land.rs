@@ -1,7 +1,23 @@
+use crate::config;
 use crate::git_ops;
 
-pub async fn handle_land(all: bool, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
-    match git_ops::land_merged_prs(all, dry_run).await {
+pub async fn handle_land(
+    number: Option<u64>,
+    all: bool,
+    dry_run: bool,
+    force: bool,
+    keep_remote: bool,
+    include_closed: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // gitx.branch.autoCleanup=false keeps merged branches on the remote by default
+    let keep_remote = keep_remote || !config::is_branch_auto_cleanup_enabled();
+    
+    let result = match number {
+        Some(pr_number) => git_ops::land_pr(pr_number, force, dry_run, keep_remote).await,
+        None => git_ops::land_merged_prs(all, dry_run, keep_remote, include_closed).await,
+    };
+    
+    match result {
         Ok(()) => {
             // Landing completed successfully
         }
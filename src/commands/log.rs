@@ -0,0 +1,32 @@
+use crate::git_ops::{self, StackCommit};
+use crate::status_display::get_status_display;
+
+pub fn handle_log() -> Result<(), Box<dyn std::error::Error>> {
+    let commits = git_ops::get_branch_commits()?;
+    
+    if commits.is_empty() {
+        println!("No commits ahead of the base branch");
+        return Ok(());
+    }
+    
+    for commit in &commits {
+        println!("{}", format_log_line(commit));
+    }
+    Ok(())
+}
+
+/// One log line: short sha and subject, then the PR tracking the commit if there is one
+pub fn format_log_line(commit: &StackCommit) -> String {
+    let short_sha = &commit.id.to_string()[..7];
+    match &commit.metadata {
+        Some(metadata) => {
+            let (icon, _) = get_status_display(&metadata.status, None, metadata.github_pr_number);
+            let pr = match metadata.github_pr_number {
+                Some(pr_number) => format!(" #{}", pr_number),
+                None => String::new(),
+            };
+            format!("{} {}  [{} {}{}]", short_sha, commit.summary, icon, metadata.pr_branch_name, pr)
+        }
+        None => format!("{} {}", short_sha, commit.summary),
+    }
+}
@@ -1,7 +1,25 @@
+pub mod adopt;
 pub mod branch;
+pub mod checkout;
+pub mod close;
 pub mod commit;
+pub mod completions;
+pub mod config;
 pub mod diff;
+pub mod doctor;
+pub mod drop;
+pub mod fetch_notes;
 pub mod init;
+pub mod insert;
 pub mod land;
+pub mod log;
 pub mod prs;
-pub mod status;
\ No newline at end of file
+pub mod prune;
+pub mod push_notes;
+pub mod ready;
+pub mod reorder;
+pub mod reword;
+pub mod stats;
+pub mod status;
+pub mod update;
+pub mod version;
\ No newline at end of file
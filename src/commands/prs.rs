@@ -1,13 +1,5 @@
-use crate::status_display;
+use crate::status_display::{self, StatusDisplayOptions};
 
-pub async fn handle_prs() -> Result<(), Box<dyn std::error::Error>> {
-    match status_display::display_status().await {
-        Ok(()) => {
-            // Status displayed successfully
-        }
-        Err(e) => {
-            eprintln!("Error displaying status: {}", e);
-        }
-    }
-    Ok(())
-}
\ No newline at end of file
+pub async fn handle_prs(options: &StatusDisplayOptions) -> Result<(), Box<dyn std::error::Error>> {
+    status_display::display_status(options).await
+}
@@ -0,0 +1,23 @@
+use crate::git_ops;
+
+pub async fn handle_prune(dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let stale = git_ops::prune_metadata(dry_run).await?;
+    
+    if stale.is_empty() {
+        println!("✅ No stale metadata found");
+        return Ok(());
+    }
+    
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    for entry in &stale {
+        println!(
+            "🗑️  {} metadata for {} ({}): {}",
+            verb,
+            &entry.commit_id.to_string()[..7],
+            entry.metadata.pr_branch_name,
+            entry.reason
+        );
+    }
+    println!("{} {} stale metadata entries", verb, stale.len());
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use crate::git_ops;
+
+pub async fn handle_push_notes() -> Result<(), Box<dyn std::error::Error>> {
+    match git_ops::push_metadata_notes().await {
+        Ok(()) => {
+            println!("✅ Pushed gitx metadata to {}", crate::config::get_remote());
+        }
+        Err(e) => {
+            eprintln!("Error pushing metadata notes: {}", e);
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
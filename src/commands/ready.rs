@@ -0,0 +1,13 @@
+use crate::git_ops;
+
+pub async fn handle_ready(branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+    match git_ops::mark_pr_ready(branch).await {
+        Ok(pr_number) => {
+            println!("✅ PR #{} is ready for review", pr_number);
+        }
+        Err(e) => {
+            eprintln!("Error marking PR ready for review: {}", e);
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,94 @@
+use std::io::IsTerminal;
+use git2::Oid;
+use inquire::Select;
+use crate::client_factory;
+use crate::commands::diff;
+use crate::config;
+use crate::git_ops::{self, StackCommit};
+use crate::git_utils::open_repo;
+use crate::github::PRCreateOptions;
+use crate::metadata;
+
+/// Label shown for a commit in the ordering prompt
+fn commit_label(stack_commit: &StackCommit) -> String {
+    let pr = stack_commit
+        .metadata
+        .as_ref()
+        .and_then(|m| m.github_pr_number)
+        .map(|number| format!(" (PR #{})", number))
+        .unwrap_or_default();
+    format!("{} {}{}", &stack_commit.id.to_string()[..8], stack_commit.summary, pr)
+}
+
+/// Ask for the new order one commit at a time, bottom of the stack first
+fn prompt_for_order(mut remaining: Vec<StackCommit>) -> Result<Vec<Oid>, Box<dyn std::error::Error>> {
+    // Offer the commits bottom first, the order they'd be picked in if nothing moves
+    remaining.reverse();
+    let mut order = Vec::new();
+    while remaining.len() > 1 {
+        let labels: Vec<String> = remaining.iter().map(commit_label).collect();
+        let prompt = format!("Commit {} of the stack (bottom first):", order.len() + 1);
+        let choice = Select::new(&prompt, labels).raw_prompt()?;
+        order.push(remaining.remove(choice.index).id);
+    }
+    order.extend(remaining.into_iter().map(|stack_commit| stack_commit.id));
+    Ok(order)
+}
+
+/// Reorder the stack, then push the rewritten commits to their PRs
+///
+/// `commits` gives the new order bottom first; without it the order is asked for interactively.
+pub async fn handle_reorder(commits: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    git_ops::ensure_on_branch()?;
+    
+    let order = if commits.is_empty() {
+        if !std::io::stdin().is_terminal() {
+            return Err("Pass the commits in their new order, bottom of the stack first, when not running interactively".into());
+        }
+        let stack = git_ops::get_reorderable_commits()?;
+        if stack.len() < 2 {
+            println!("Nothing to reorder: the stack has fewer than two commits");
+            return Ok(());
+        }
+        prompt_for_order(stack)?
+    } else {
+        let repo = open_repo()?;
+        commits
+            .iter()
+            .map(|sha| Ok(repo.revparse_single(sha)?.peel_to_commit()?.id()))
+            .collect::<Result<Vec<_>, git2::Error>>()?
+    };
+    
+    let rewritten = git_ops::reorder_commits(&order)?;
+    if rewritten.is_empty() {
+        println!("Stack order unchanged");
+        return Ok(());
+    }
+    println!("✅ Reordered the stack, rewriting {} commit{}", rewritten.len(), if rewritten.len() == 1 { "" } else { "s" });
+    let moved: Vec<Oid> = rewritten.iter().map(|(_, new)| *new).collect();
+    sync_moved_prs(&moved).await
+}
+
+/// Retarget and push the PRs of commits that were rewritten to a new place in the stack
+pub(crate) async fn sync_moved_prs(moved: &[Oid]) -> Result<(), Box<dyn std::error::Error>> {
+    // Point the PRs at their new parents first so the stack links written below are right
+    if !config::is_offline() && crate::github::check_github_token() {
+        let github_client = client_factory::create_github_client().await?;
+        for pr_number in git_ops::retarget_moved_prs_with_github_client(moved, &*github_client).await? {
+            println!("🎯 Retargeted PR #{} onto its new parent", pr_number);
+        }
+    }
+    
+    // Every moved commit with a PR gets an incremental update carrying its new position
+    for oid in moved {
+        if metadata::get_commit_metadata(oid)?.is_none() {
+            continue;
+        }
+        let sha = oid.to_string();
+        if let Err(e) = diff::handle_diff(false, true, false, std::slice::from_ref(&sha), None, false, PRCreateOptions::default()).await {
+            eprintln!("Warning: Could not update the PR for {}: {}; run 'gitx diff --commit {}' to retry", &sha[..8], e, &sha[..8]);
+        }
+    }
+    
+    Ok(())
+}
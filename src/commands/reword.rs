@@ -0,0 +1,16 @@
+use crate::git_ops;
+
+pub async fn handle_reword(sha: Option<&str>, message: &str) -> Result<(), Box<dyn std::error::Error>> {
+    match git_ops::reword_commit_and_pr(sha, message).await {
+        Ok((new_oid, retitled)) => {
+            println!("✅ Reworded commit, now {}", &new_oid.to_string()[..8]);
+            if let Some(pr_number) = retitled {
+                println!("✅ Retitled PR #{}", pr_number);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error rewording commit: {}", e);
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,33 @@
+use crate::{github, metadata, stats, status_display};
+
+pub async fn handle_stats(json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let pr_statuses = metadata::get_all_pr_status()?;
+    
+    if pr_statuses.is_empty() && !json {
+        println!("No stacked PRs found.");
+        println!("Run 'gitx diff' to create PR branches from your commits.");
+        return Ok(());
+    }
+    
+    // GitHub knows about merges gitx hasn't landed yet, and when they happened
+    let github_statuses = if github::check_github_token() {
+        match status_display::get_github_statuses(&pr_statuses, false).await {
+            Ok(statuses) => Some(statuses),
+            Err(e) => {
+                eprintln!("Warning: Could not fetch GitHub PR statuses: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    
+    let stats = stats::compute_stats(&pr_statuses, github_statuses.as_ref());
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        println!("📊 Stack Stats\n");
+        println!("{}", stats::format_stats(&stats));
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use std::process::Command;
+use crate::git_ops;
 
-pub fn handle_status(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+pub fn handle_status(stack: bool, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     // Passthrough to git status with all provided arguments
     let mut cmd = Command::new("git");
     cmd.arg("status");
@@ -17,5 +18,39 @@ pub fn handle_status(args: &[String]) -> Result<(), Box<dyn std::error::Error>>
             std::process::exit(1);
         }
     }
+    
+    if stack {
+        print_stack();
+    }
     Ok(())
+}
+
+/// Print each commit in the current stack with the PR tracking it
+fn print_stack() {
+    let commits = match git_ops::get_stack_commits() {
+        Ok(commits) => commits,
+        Err(e) => {
+            eprintln!("Error reading stack: {}", e);
+            return;
+        }
+    };
+    
+    println!();
+    if commits.is_empty() {
+        println!("Stack: no commits ahead of the base branch");
+        return;
+    }
+    
+    println!("Stack:");
+    for commit in &commits {
+        let short_sha = &commit.id.to_string()[..7];
+        let tracking = match &commit.metadata {
+            Some(metadata) => match metadata.github_pr_number {
+                Some(pr_number) => format!("{} (PR #{})", metadata.pr_branch_name, pr_number),
+                None => format!("{} (no PR)", metadata.pr_branch_name),
+            },
+            None => "no PR".to_string(),
+        };
+        println!("  {} {}  [{}]", short_sha, commit.summary, tracking);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,28 @@
+use crate::config;
+use crate::self_update;
+
+pub async fn handle_update(check_only: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if config::is_offline() {
+        return Err("Offline mode is on; can't check for gitx updates.".into());
+    }
+
+    let current = env!("CARGO_PKG_VERSION");
+    let octocrab = self_update::release_client()?;
+    let Some(release) = self_update::check_for_update_with_client(&octocrab, current).await? else {
+        println!("✅ gitx {} is up to date", current);
+        return Ok(());
+    };
+
+    if check_only {
+        println!("⬆️  gitx {} is available (you have {}): {}", release.version(), current, release.html_url);
+        println!("Run 'gitx update' to install it.");
+        return Ok(());
+    }
+
+    println!("⬇️  Downloading gitx {}...", release.version());
+    let bytes = self_update::download_verified_binary_with_client(&octocrab, &release).await?;
+    self_update::replace_current_exe(&bytes)?;
+    println!("✅ Updated gitx {} → {}", current, release.version());
+
+    Ok(())
+}
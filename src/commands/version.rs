@@ -0,0 +1,20 @@
+/// Version shown by `gitx --version`: the crate version and the commit it was built from
+pub const SHORT_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("GITX_GIT_HASH"), ")");
+
+/// Build details worth pasting into a bug report
+pub fn version_info() -> String {
+    let (major, minor, rev) = git2::Version::get().libgit2_version();
+    
+    [
+        format!("gitx {}", env!("CARGO_PKG_VERSION")),
+        format!("commit:  {}", env!("GITX_GIT_HASH")),
+        format!("profile: {}", env!("GITX_BUILD_PROFILE")),
+        format!("libgit2: {}.{}.{}", major, minor, rev),
+    ]
+    .join("\n")
+}
+
+pub fn handle_version() -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", version_info());
+    Ok(())
+}
@@ -1,8 +1,169 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use inquire::{Text, Confirm, Select};
 
+/// Default pattern for ticket references: JIRA-style keys or GitHub issue numbers
+/// leading a line, following a `Refs:`-style trailer key, or in brackets, so `UTF-8` or `SHA-256` never match
+pub const DEFAULT_TICKET_PATTERN: &str = r"(?m)(?:^\[?|^[A-Za-z-]+:[ \t]*|[(\[])([A-Z][A-Z0-9]+-\d+|#\d+)(?:[:\])\s]|$)";
+
+/// Default number of seconds fetched PR statuses are reused by `gitx prs`
+pub const DEFAULT_STATUS_CACHE_SECONDS: u64 = 60;
+
+/// Default PR title template: the commit subject as is
+pub const DEFAULT_PR_TITLE_FORMAT: &str = "{subject}";
+
+/// Default longest PR title before it is shortened at a word boundary
+pub const DEFAULT_PR_TITLE_MAX_LENGTH: usize = 72;
+
+/// Default number of days without updates before `gitx prs` calls a PR stale
+pub const DEFAULT_PRS_STALE_DAYS: u64 = 14;
+
+/// Default number of commits `gitx diff` looks at
+pub const DEFAULT_DIFF_LIMIT: usize = 10;
+
+/// Default commit message markers that keep `gitx diff` from turning a commit into a PR
+pub const DEFAULT_SKIP_MARKERS: &str = "[skip gitx],[no-pr]";
+
+/// Default number of attempts for a rate-limited GitHub request
+pub const DEFAULT_GITHUB_MAX_ATTEMPTS: u32 = 3;
+
+/// Kind of value a gitx setting accepts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingKind {
+    Text,
+    Bool,
+    Secret,
+}
+
+/// A gitx setting that can be managed with `gitx config`
+#[derive(Debug)]
+pub struct KnownSetting {
+    pub key: &'static str,
+    pub kind: SettingKind,
+    pub description: &'static str,
+}
+
+/// All settings gitx understands
+pub const KNOWN_SETTINGS: &[KnownSetting] = &[
+    KnownSetting { key: "gitx.github.token", kind: SettingKind::Secret, description: "GitHub personal access token" },
+    KnownSetting { key: "gitx.github.enabled", kind: SettingKind::Bool, description: "Create GitHub PRs from 'gitx diff'" },
+    KnownSetting { key: "gitx.github.baseBranch", kind: SettingKind::Text, description: "Default base branch for PRs" },
+    KnownSetting { key: "gitx.github.maxAttempts", kind: SettingKind::Text, description: "Attempts made when GitHub rate-limits a request (default 3)" },
+    KnownSetting { key: "gitx.github.host", kind: SettingKind::Text, description: "GitHub host (for GitHub Enterprise Server)" },
+    KnownSetting { key: "gitx.github.caBundle", kind: SettingKind::Text, description: "PEM file of extra root certificates trusted for GitHub (e.g. a corporate proxy's CA)" },
+    KnownSetting { key: "gitx.github.fork", kind: SettingKind::Text, description: "Owner of the fork branches are pushed to, for PRs opened from a fork" },
+    KnownSetting { key: "gitx.github.upstream", kind: SettingKind::Text, description: "Remote of the repository PRs are opened against (default gitx.remote)" },
+    KnownSetting { key: "gitx.remote", kind: SettingKind::Text, description: "Remote PR branches are pushed to (default origin)" },
+    KnownSetting { key: "gitx.branch.autoCleanup", kind: SettingKind::Bool, description: "Delete merged PR branches from the remote in 'gitx land' (default true)" },
+    KnownSetting { key: "gitx.branch.includeHash", kind: SettingKind::Bool, description: "Append a short commit hash to branch names" },
+    KnownSetting { key: "gitx.branch.ticketPattern", kind: SettingKind::Text, description: "Regex matching ticket references in commits" },
+    KnownSetting { key: "gitx.push.forceUpdate", kind: SettingKind::Bool, description: "Overwrite PR branches that diverged on the remote (--force-with-lease)" },
+    KnownSetting { key: "gitx.commit.autoDiff", kind: SettingKind::Bool, description: "Run 'gitx diff' for the new commit after 'gitx commit'" },
+    KnownSetting { key: "gitx.land.rebase", kind: SettingKind::Bool, description: "Fast-forward the base branch after 'gitx land' instead of pulling (default true)" },
+    KnownSetting { key: "gitx.diff.limit", kind: SettingKind::Text, description: "Most commits 'gitx diff' processes, 0 for no limit (default 10)" },
+    KnownSetting { key: "gitx.diff.skipMarker", kind: SettingKind::Text, description: "Comma-separated commit message markers 'gitx diff' skips (default [skip gitx],[no-pr])" },
+    KnownSetting { key: "gitx.notesRef", kind: SettingKind::Text, description: "Git notes ref where gitx stores stack metadata" },
+    KnownSetting { key: "gitx.status.cacheSeconds", kind: SettingKind::Text, description: "How long 'gitx prs' reuses fetched PR statuses (default 60)" },
+    KnownSetting { key: "gitx.prs.staleDays", kind: SettingKind::Text, description: "Days without updates before 'gitx prs' marks a PR stale (default 14)" },
+    KnownSetting { key: "gitx.pr.includeDiffstat", kind: SettingKind::Bool, description: "Include a diffstat in generated PR bodies (default true)" },
+    KnownSetting { key: "gitx.pr.template", kind: SettingKind::Text, description: "PR template path relative to the repo root (default: GitHub's locations)" },
+    KnownSetting { key: "gitx.pr.templateMode", kind: SettingKind::Text, description: "How the PR template is used: prepend or replace-description" },
+    KnownSetting { key: "gitx.pr.reviewers", kind: SettingKind::Text, description: "Comma-separated reviewers requested on new PRs" },
+    KnownSetting { key: "gitx.pr.codeowners", kind: SettingKind::Bool, description: "Request reviews from the CODEOWNERS of the files a PR changes" },
+    KnownSetting { key: "gitx.pr.autoLabel", kind: SettingKind::Bool, description: "Label new PRs by their conventional commit type (feat, fix, ...)" },
+    KnownSetting { key: "gitx.pr.titleFormat", kind: SettingKind::Text, description: "PR title template using {subject} and {ticket}, e.g. '[{ticket}] {subject}' (default {subject})" },
+    KnownSetting { key: "gitx.pr.titleMaxLength", kind: SettingKind::Text, description: "Longest PR title before it is shortened with an ellipsis, 0 for no limit (default 72)" },
+    KnownSetting { key: "gitx.pr.labelMap", kind: SettingKind::Text, description: "Comma-separated type=label pairs for autoLabel (default feat=enhancement,fix=bug)" },
+];
+
+/// Look up a known setting; the `gitx.` prefix is optional and matching is case-insensitive
+pub fn find_setting(key: &str) -> Result<&'static KnownSetting, String> {
+    let full_key = if key.to_lowercase().starts_with("gitx.") {
+        key.to_string()
+    } else {
+        format!("gitx.{}", key)
+    };
+    
+    KNOWN_SETTINGS
+        .iter()
+        .find(|setting| setting.key.eq_ignore_ascii_case(&full_key))
+        .ok_or_else(|| {
+            let known: Vec<&str> = KNOWN_SETTINGS.iter().map(|s| s.key).collect();
+            format!("Unknown gitx setting '{}'. Known settings:\n  {}", key, known.join("\n  "))
+        })
+}
+
+/// Validate a value for a known setting
+pub fn validate_setting_value(setting: &KnownSetting, value: &str) -> Result<(), String> {
+    match setting.kind {
+        SettingKind::Bool if value != "true" && value != "false" => {
+            Err(format!("{} must be 'true' or 'false', got '{}'", setting.key, value))
+        }
+        SettingKind::Text if setting.key == "gitx.branch.ticketPattern" => regex::Regex::new(value)
+            .map(|_| ())
+            .map_err(|e| format!("{} is not a valid regex: {}", setting.key, e)),
+        SettingKind::Text if setting.key == "gitx.status.cacheSeconds" && value.parse::<u64>().is_err() => {
+            Err(format!("{} must be a whole number of seconds, got '{}'", setting.key, value))
+        }
+        SettingKind::Text if setting.key == "gitx.prs.staleDays" && value.parse::<u64>().is_err() => {
+            Err(format!("{} must be a whole number of days, got '{}'", setting.key, value))
+        }
+        SettingKind::Text if setting.key == "gitx.pr.titleMaxLength" && value.parse::<usize>().is_err() => {
+            Err(format!("{} must be a whole number (0 for no limit), got '{}'", setting.key, value))
+        }
+        SettingKind::Text if setting.key == "gitx.github.maxAttempts" && !matches!(value.parse::<u32>(), Ok(n) if n > 0) => {
+            Err(format!("{} must be a positive whole number, got '{}'", setting.key, value))
+        }
+        SettingKind::Text if setting.key == "gitx.diff.limit" && value.parse::<usize>().is_err() => {
+            Err(format!("{} must be a whole number (0 for no limit), got '{}'", setting.key, value))
+        }
+        SettingKind::Text if setting.key == "gitx.pr.templateMode" && crate::github_utils::PRTemplateMode::parse(value).is_none() => {
+            Err(format!("{} must be 'prepend' or 'replace-description', got '{}'", setting.key, value))
+        }
+        SettingKind::Text if setting.key == "gitx.notesRef" && !value.starts_with("refs/notes/") => {
+            Err(format!("{} must start with 'refs/notes/', got '{}'", setting.key, value))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Redact a secret value for display, keeping a short prefix to help identify it
+pub fn redact_secret(value: &str) -> String {
+    let prefix: String = value.chars().take(4).collect();
+    if value.chars().count() > 8 {
+        format!("{}****", prefix)
+    } else {
+        "****".to_string()
+    }
+}
+
+/// Mask anything that looks like a GitHub token (`ghp_…`, `gho_…`, `github_pat_…`) with `***`
+///
+/// Applied to error output, since HTTP errors can echo the Authorization header.
+pub fn redact(s: &str) -> String {
+    static TOKEN_PATTERN: OnceLock<regex::Regex> = OnceLock::new();
+    let pattern = TOKEN_PATTERN.get_or_init(|| {
+        regex::Regex::new(r"github_pat_[A-Za-z0-9_]+|gh[pousr]_[A-Za-z0-9]+").expect("valid token pattern")
+    });
+    pattern.replace_all(s, "***").into_owned()
+}
+
+/// Format an error and each of its sources on separate `Caused by:` lines, with tokens redacted
+pub fn format_error_chain(error: &dyn std::error::Error) -> String {
+    let mut formatted = redact(&error.to_string());
+    let mut source = error.source();
+    while let Some(err) = source {
+        formatted.push_str(&format!("\n  Caused by: {}", redact(&err.to_string())));
+        source = err.source();
+    }
+    formatted
+}
+
 /// Initialize gitx configuration interactively
-pub fn interactive_init() -> Result<(), Box<dyn std::error::Error>> {
+pub fn interactive_init(use_keyring: bool) -> Result<(), Box<dyn std::error::Error>> {
     println!("🚀 Welcome to gitx! Let's set up your configuration.\n");
     
     // Ask for GitHub token
@@ -12,8 +173,7 @@ pub fn interactive_init() -> Result<(), Box<dyn std::error::Error>> {
         .prompt()?;
     
     if !github_token.trim().is_empty() {
-        set_git_config("gitx.github.token", &github_token)?;
-        println!("✅ GitHub token configured");
+        save_github_token(github_token.trim(), use_keyring)?;
     }
     
     // Ask if they want GitHub integration enabled by default
@@ -51,6 +211,27 @@ pub fn interactive_init() -> Result<(), Box<dyn std::error::Error>> {
     set_git_config("gitx.branch.autoCleanup", &auto_cleanup.to_string())?;
     println!("✅ Auto cleanup: {}", if auto_cleanup { "enabled" } else { "disabled" });
     
+    // Ask about sharing metadata
+    let share_notes = Confirm::new("Fetch shared gitx metadata from origin on every git fetch?")
+        .with_default(false)
+        .with_help_message("Adds the gitx notes ref to remote.origin.fetch so teammates' PR state is visible")
+        .prompt()?;
+    
+    if share_notes {
+        configure_notes_fetch_refspec()?;
+        println!("✅ Metadata notes will be fetched from origin");
+    }
+    
+    // Ask about keeping PRs in sync automatically
+    let install_hooks = Confirm::new("Install a post-commit hook that runs 'gitx diff' for each new commit?")
+        .with_default(false)
+        .with_help_message("Any existing post-commit hook is kept and still runs first")
+        .prompt()?;
+    
+    if install_hooks {
+        install_commit_hook()?;
+    }
+    
     println!("\n🎉 gitx configuration complete!");
     println!("\nYour settings have been saved to this repository's git config.");
     println!("You can view them with: git config --list | grep gitx");
@@ -64,91 +245,984 @@ pub fn interactive_init() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// Set a git config value for the current repository
-fn set_git_config(key: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// Settings supplied on the command line for `gitx init`
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    pub token: Option<String>,
+    pub base_branch: Option<String>,
+    pub enable_github: Option<bool>,
+    pub auto_cleanup: Option<bool>,
+    pub share_notes: Option<bool>,
+    /// Install the post-commit hook that runs `gitx diff`
+    pub hooks: Option<bool>,
+    /// Store the token in the OS keyring instead of git config
+    pub keyring: bool,
+}
+
+impl InitOptions {
+    /// Check if any setting was supplied
+    pub fn has_any(&self) -> bool {
+        self.token.is_some()
+            || self.base_branch.is_some()
+            || self.enable_github.is_some()
+            || self.auto_cleanup.is_some()
+            || self.share_notes.is_some()
+            || self.hooks.is_some()
+    }
+}
+
+/// Initialize gitx configuration without prompting, using defaults for anything not supplied or already set
+pub fn non_interactive_init(options: &InitOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(token) = options.token.as_deref().filter(|t| !t.trim().is_empty()) {
+        save_github_token(token.trim(), options.keyring)?;
+    }
+    
+    // Flags always win; defaults only fill in settings the repository doesn't have yet
+    if let Some(enable_github) = options.enable_github.or_else(|| is_unset("gitx.github.enabled").then_some(true)) {
+        set_git_config("gitx.github.enabled", &enable_github.to_string())?;
+        println!("✅ GitHub integration: {}", if enable_github { "enabled" } else { "disabled" });
+    }
+    
+    if let Some(base_branch) = options.base_branch.as_deref().or_else(|| is_unset("gitx.github.baseBranch").then_some("main")) {
+        set_git_config("gitx.github.baseBranch", base_branch)?;
+        println!("✅ Base branch set to: {}", base_branch);
+    }
+    
+    if let Some(auto_cleanup) = options.auto_cleanup.or_else(|| is_unset("gitx.branch.autoCleanup").then_some(true)) {
+        set_git_config("gitx.branch.autoCleanup", &auto_cleanup.to_string())?;
+        println!("✅ Auto cleanup: {}", if auto_cleanup { "enabled" } else { "disabled" });
+    }
+    
+    if options.share_notes.unwrap_or(false) {
+        configure_notes_fetch_refspec()?;
+        println!("✅ Metadata notes will be fetched from origin");
+    }
+    
+    if options.hooks.unwrap_or(false) {
+        install_commit_hook()?;
+    }
+    
+    println!("\n🎉 gitx configuration complete!");
+    
+    Ok(())
+}
+
+/// Check if a setting has no value in git config yet
+fn is_unset(key: &str) -> bool {
+    current().get(key).is_none()
+}
+
+/// Save the GitHub token to the OS keyring or to git config
+fn save_github_token(token: &str, use_keyring: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if use_keyring {
+        store_token_in_keyring(&get_github_host(), token)
+            .map_err(|e| format!("Could not store the token in the system keyring: {}; run 'gitx init' without --keyring to keep it in git config instead", e))?;
+        println!("✅ GitHub token stored in the system keyring");
+    } else {
+        set_git_config("gitx.github.token", token)?;
+        println!("✅ GitHub token configured");
+    }
+    
+    Ok(())
+}
+
+/// Install the post-commit hook and report what happened to any existing hook
+fn install_commit_hook() -> Result<(), Box<dyn std::error::Error>> {
+    let hooks_dir = crate::hooks::hooks_dir()?;
+    match crate::hooks::install_post_commit_hook(&hooks_dir)? {
+        crate::hooks::HookInstall::Chained => println!(
+            "✅ post-commit hook installed; your existing hook was kept as {} and runs first",
+            crate::hooks::CHAINED_HOOK_NAME
+        ),
+        _ => println!("✅ post-commit hook installed: new commits run 'gitx diff'"),
+    }
+    Ok(())
+}
+
+/// Configure origin to fetch the gitx metadata notes alongside branches
+pub fn configure_notes_fetch_refspec() -> Result<(), Box<dyn std::error::Error>> {
+    let notes_ref = get_notes_ref();
+    let refspec = format!("+{}:{}", notes_ref, crate::git_ops::remote_notes_ref(&notes_ref));
+    
+    // remote.origin.fetch is multi-valued, so check every entry rather than the cached value
     let output = Command::new("git")
-        .args(&["config", key, value])
+        .args(["config", "--get-all", "remote.origin.fetch"])
+        .output()?;
+    if String::from_utf8_lossy(&output.stdout).lines().any(|line| line.trim() == refspec) {
+        return Ok(());
+    }
+    
+    let output = Command::new("git")
+        .args(["config", "--add", "remote.origin.fetch", &refspec])
         .output()?;
     
     if !output.status.success() {
         let error = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to set git config {}: {}", key, error).into());
+        return Err(format!("Failed to add notes refspec to remote.origin.fetch: {}", error).into());
     }
     
+    invalidate_cache();
+    
     Ok(())
 }
 
-/// Get a git config value
-pub fn get_git_config(key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+/// Set a git config value for the current repository
+pub fn set_git_config(key: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
     let output = Command::new("git")
-        .args(&["config", key])
+        .args(&["config", key, value])
         .output()?;
     
-    if output.status.success() {
-        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if value.is_empty() {
-            Ok(None)
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to set git config {}: {}", key, error).into());
+    }
+    
+    // The cached snapshot no longer reflects the repository config
+    invalidate_cache();
+    
+    Ok(())
+}
+
+/// Runs `git config` invocations; abstracted so lookups can be tested without spawning git
+pub trait GitConfigRunner {
+    /// Run `git config` with the given arguments, returning stdout if the command succeeded
+    fn run(&self, args: &[&str]) -> Result<Option<String>, Box<dyn std::error::Error>>;
+}
+
+/// Runner that shells out to the `git` binary
+pub struct GitCommandRunner;
+
+impl GitConfigRunner for GitCommandRunner {
+    fn run(&self, args: &[&str]) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let output = Command::new("git")
+            .arg("config")
+            .args(args)
+            .output()?;
+        
+        if output.status.success() {
+            Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
         } else {
-            Ok(Some(value))
+            Ok(None)
         }
-    } else {
-        Ok(None)
     }
 }
 
-/// Check if gitx is initialized in the current repo
-#[allow(dead_code)]
-pub fn is_initialized() -> bool {
-    get_git_config("gitx.github.token").unwrap_or(None).is_some()
+/// Keyring service name gitx stores tokens under (the account is the GitHub host)
+pub const KEYRING_SERVICE: &str = "gitx";
+
+/// A place a GitHub token can come from; abstracted so the lookup order can be tested
+pub trait TokenSource {
+    /// Short description of the source, shown by `gitx doctor`
+    fn name(&self) -> &'static str;
+    
+    /// The token this source holds, if any
+    fn token(&self) -> Option<String>;
 }
 
-/// Get the configured GitHub token (from repo config or environment)
-pub fn get_github_token() -> Option<String> {
-    // First try repo-specific config
-    if let Ok(Some(token)) = get_git_config("gitx.github.token") {
-        return Some(token);
+/// Token stored in the OS keyring by `gitx init --keyring`
+pub struct KeyringTokenSource {
+    pub host: String,
+}
+
+impl TokenSource for KeyringTokenSource {
+    fn name(&self) -> &'static str {
+        "system keyring"
     }
     
-    // Fall back to global config
-    if let Ok(Some(token)) = get_git_config_global("gitx.github.token") {
-        return Some(token);
+    fn token(&self) -> Option<String> {
+        keyring::Entry::new(KEYRING_SERVICE, &self.host).ok()?.get_password().ok()
+    }
+}
+
+/// Token the GitHub CLI is logged in with (`gh auth token`)
+pub struct GhCliTokenSource {
+    pub host: String,
+}
+
+impl TokenSource for GhCliTokenSource {
+    fn name(&self) -> &'static str {
+        "gh auth token"
     }
     
-    // Fall back to environment variable
-    std::env::var("GITHUB_TOKEN").ok()
+    fn token(&self) -> Option<String> {
+        let output = Command::new("gh")
+            .args(["auth", "token", "--hostname", &self.host])
+            .output()
+            .ok()?;
+        
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
 }
 
-/// Get a global git config value
-fn get_git_config_global(key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
-    let output = Command::new("git")
-        .args(&["config", "--global", key])
-        .output()?;
+/// Token stored in git config (`gitx.github.token`)
+pub struct GitConfigTokenSource<'a> {
+    pub config: &'a Config,
+}
+
+impl TokenSource for GitConfigTokenSource<'_> {
+    fn name(&self) -> &'static str {
+        "git config gitx.github.token"
+    }
     
-    if output.status.success() {
-        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if value.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(value))
+    fn token(&self) -> Option<String> {
+        self.config.get("gitx.github.token").map(str::to_string)
+    }
+}
+
+/// Token exported as `GITHUB_TOKEN`
+pub struct EnvTokenSource;
+
+impl TokenSource for EnvTokenSource {
+    fn name(&self) -> &'static str {
+        "GITHUB_TOKEN"
+    }
+    
+    fn token(&self) -> Option<String> {
+        std::env::var("GITHUB_TOKEN").ok()
+    }
+}
+
+/// Return the first non-empty token from `sources`, with the name of the source it came from
+pub fn resolve_github_token(sources: &[&dyn TokenSource]) -> Option<(&'static str, String)> {
+    sources.iter().find_map(|source| {
+        source
+            .token()
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .map(|token| (source.name(), token))
+    })
+}
+
+/// Store a GitHub token for `host` in the OS keyring
+pub fn store_token_in_keyring(host: &str, token: &str) -> Result<(), Box<dyn std::error::Error>> {
+    keyring::Entry::new(KEYRING_SERVICE, host)?.set_password(token)?;
+    Ok(())
+}
+
+/// Snapshot of the git config visible from the current directory
+///
+/// Loaded with a single `git config --list --null` call. Git lists system, global and
+/// local values in that order, so later entries win and repo settings override global ones.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    values: HashMap<String, String>,
+    /// Resolved lazily since the keyring and `gh` lookups are comparatively slow
+    github_token: OnceLock<Option<(&'static str, String)>>,
+}
+
+impl Config {
+    /// Load the config by running `git config --list --null`
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::load_with_runner(&GitCommandRunner)
+    }
+    
+    /// Load the config using the given runner
+    pub fn load_with_runner(runner: &dyn GitConfigRunner) -> Result<Self, Box<dyn std::error::Error>> {
+        let output = runner.run(&["--list", "--null"])?.unwrap_or_default();
+        Ok(Self::parse(&output))
+    }
+    
+    /// Parse `git config --list --null` output (`key\nvalue\0` records)
+    pub fn parse(output: &str) -> Self {
+        let mut values = HashMap::new();
+        
+        for record in output.split('\0').filter(|r| !r.is_empty()) {
+            // Keys without a value (e.g. `[section] flag`) have no newline
+            let (key, value) = record.split_once('\n').unwrap_or((record, "true"));
+            values.insert(normalize_key(key), value.to_string());
         }
-    } else {
-        Ok(None)
+        
+        Self { values, github_token: OnceLock::new() }
+    }
+    
+    /// Get a config value, treating empty values as unset
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .get(&normalize_key(key))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+    
+    /// Get a boolean config value
+    fn get_bool(&self, key: &str) -> bool {
+        self.get_bool_or(key, false)
+    }
+    
+    /// Get a boolean config value the way git reads one, falling back to `default` when unset or unrecognized
+    fn get_bool_or(&self, key: &str, default: bool) -> bool {
+        match self.get(key).map(str::to_ascii_lowercase).as_deref() {
+            Some("true" | "yes" | "on" | "1") => true,
+            Some("false" | "no" | "off" | "0") => false,
+            _ => default,
+        }
+    }
+    
+    /// Check if gitx is initialized
+    pub fn is_initialized(&self) -> bool {
+        self.get("gitx.github.token").is_some()
+    }
+    
+    /// Get the GitHub token, trying in order: the OS keyring, `gh auth token`,
+    /// `gitx.github.token` in git config, then the `GITHUB_TOKEN` environment variable
+    pub fn github_token(&self) -> Option<String> {
+        self.resolved_github_token().map(|(_, token)| token)
+    }
+    
+    /// Get where the GitHub token was found (see `github_token` for the order)
+    pub fn github_token_source(&self) -> Option<&'static str> {
+        self.resolved_github_token().map(|(source, _)| source)
+    }
+    
+    fn resolved_github_token(&self) -> Option<(&'static str, String)> {
+        self.github_token
+            .get_or_init(|| {
+                let host = self.github_host();
+                resolve_github_token(&[
+                    &KeyringTokenSource { host: host.clone() },
+                    &GhCliTokenSource { host },
+                    &GitConfigTokenSource { config: self },
+                    &EnvTokenSource,
+                ])
+            })
+            .clone()
+    }
+    
+    /// Check if GitHub integration is enabled
+    pub fn is_github_enabled(&self) -> bool {
+        self.get_bool("gitx.github.enabled")
+    }
+    
+    /// Get the GitHub host (`github.com` unless using GitHub Enterprise Server)
+    pub fn github_host(&self) -> String {
+        self.get("gitx.github.host").unwrap_or("github.com").to_string()
+    }
+    
+    /// Get the path of a PEM bundle of extra root certificates to trust for GitHub
+    pub fn github_ca_bundle(&self) -> Option<String> {
+        self.get("gitx.github.caBundle").map(str::to_string)
+    }
+    
+    /// Get the owner of the fork PR branches are pushed to, if working from a fork
+    pub fn github_fork(&self) -> Option<String> {
+        self.get("gitx.github.fork").map(str::to_string)
+    }
+    
+    /// Get the remote of the repository PRs are opened against
+    pub fn github_upstream_remote(&self) -> String {
+        self.get("gitx.github.upstream").map(str::to_string).unwrap_or_else(|| self.remote())
+    }
+    
+    /// Get git's `user.name`, if set
+    pub fn git_username(&self) -> Option<String> {
+        self.get("user.name").map(str::to_string)
+    }
+    
+    /// Get the remote PR branches are pushed to
+    pub fn remote(&self) -> String {
+        self.get("gitx.remote").unwrap_or("origin").to_string()
+    }
+    
+    /// Get the base branch
+    pub fn base_branch(&self) -> String {
+        self.get("gitx.github.baseBranch").unwrap_or("main").to_string()
+    }
+    
+    /// Check if diverged PR branches are force-updated on the remote
+    pub fn is_push_force_update_enabled(&self) -> bool {
+        self.get_bool("gitx.push.forceUpdate")
+    }
+    
+    /// Check if `gitx land` deletes merged PR branches from the remote (on unless set to false)
+    pub fn is_branch_auto_cleanup_enabled(&self) -> bool {
+        self.get_bool_or("gitx.branch.autoCleanup", true)
+    }
+    
+    /// Check if `gitx land` fast-forwards the base branch rather than pulling (on unless set to false)
+    pub fn is_land_rebase_enabled(&self) -> bool {
+        self.get_bool_or("gitx.land.rebase", true)
+    }
+    
+    /// Check if `gitx commit` should run `gitx diff` for the new commit
+    pub fn is_commit_auto_diff_enabled(&self) -> bool {
+        self.get_bool("gitx.commit.autoDiff")
+    }
+    
+    /// Check if branch names should include a short commit hash suffix
+    pub fn is_branch_hash_enabled(&self) -> bool {
+        self.get_bool("gitx.branch.includeHash")
+    }
+    
+    /// Get the ticket reference pattern
+    pub fn ticket_pattern(&self) -> String {
+        self.get("gitx.branch.ticketPattern").unwrap_or(DEFAULT_TICKET_PATTERN).to_string()
+    }
+    
+    /// Get the git notes ref gitx metadata is stored under
+    pub fn notes_ref(&self) -> String {
+        self.get("gitx.notesRef").unwrap_or(crate::metadata::GITX_NOTES_REF).to_string()
+    }
+    
+    /// Get how many seconds fetched PR statuses stay cached
+    pub fn status_cache_seconds(&self) -> u64 {
+        self.get("gitx.status.cacheSeconds")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_STATUS_CACHE_SECONDS)
+    }
+    
+    /// Get how many days a PR can go without updates before it counts as stale
+    pub fn prs_stale_days(&self) -> u64 {
+        self.get("gitx.prs.staleDays")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PRS_STALE_DAYS)
+    }
+    
+    /// Get the most commits `gitx diff` processes (0 means no limit)
+    pub fn diff_limit(&self) -> usize {
+        self.get("gitx.diff.limit")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_DIFF_LIMIT)
+    }
+    
+    /// Get the commit message markers that keep a commit out of `gitx diff` (comma-separated `gitx.diff.skipMarker`)
+    pub fn skip_markers(&self) -> Vec<String> {
+        self.get("gitx.diff.skipMarker")
+            .unwrap_or(DEFAULT_SKIP_MARKERS)
+            .split(',')
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect()
+    }
+    
+    /// Check if generated PR bodies include a diffstat (on unless set to false)
+    pub fn is_diffstat_enabled(&self) -> bool {
+        self.get("gitx.pr.includeDiffstat").is_none_or(|v| v == "true")
+    }
+    
+    /// Get the custom PR template path, if one is configured
+    pub fn pr_template(&self) -> Option<String> {
+        self.get("gitx.pr.template").map(str::to_string)
+    }
+    
+    /// Get how the PR template is combined with the generated body
+    pub fn pr_template_mode(&self) -> crate::github_utils::PRTemplateMode {
+        self.get("gitx.pr.templateMode")
+            .and_then(crate::github_utils::PRTemplateMode::parse)
+            .unwrap_or(crate::github_utils::PRTemplateMode::Prepend)
+    }
+    
+    /// Get how many times a rate-limited GitHub request is attempted
+    pub fn github_max_attempts(&self) -> u32 {
+        self.get("gitx.github.maxAttempts")
+            .and_then(|v| v.parse().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_GITHUB_MAX_ATTEMPTS)
+    }
+    
+    /// Get the default reviewers to request on new PRs (comma-separated `gitx.pr.reviewers`)
+    pub fn default_reviewers(&self) -> Vec<String> {
+        self.get("gitx.pr.reviewers")
+            .map(|v| {
+                v.split(',')
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    
+    /// Check if new PRs request reviews from CODEOWNERS
+    pub fn is_pr_codeowners_enabled(&self) -> bool {
+        self.get_bool("gitx.pr.codeowners")
+    }
+    
+    /// Check if new PRs are labeled by their conventional commit type
+    pub fn is_pr_auto_label_enabled(&self) -> bool {
+        self.get_bool("gitx.pr.autoLabel")
+    }
+    
+    /// Get the template PR titles are rendered from (`gitx.pr.titleFormat`)
+    pub fn pr_title_format(&self) -> String {
+        self.get("gitx.pr.titleFormat").unwrap_or(DEFAULT_PR_TITLE_FORMAT).to_string()
+    }
+    
+    /// Get the longest PR title allowed before it is shortened (0 means no limit)
+    pub fn pr_title_max_length(&self) -> usize {
+        self.get("gitx.pr.titleMaxLength")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PR_TITLE_MAX_LENGTH)
+    }
+    
+    /// Get the conventional commit type to label mapping: the defaults, overridden by `gitx.pr.labelMap`
+    pub fn pr_label_map(&self) -> Vec<(String, String)> {
+        let mut label_map: Vec<(String, String)> = crate::github_utils::DEFAULT_LABEL_MAP
+            .iter()
+            .map(|(commit_type, label)| (commit_type.to_string(), label.to_string()))
+            .collect();
+        
+        let overrides = self.get("gitx.pr.labelMap").unwrap_or("");
+        for (commit_type, label) in overrides.split(',').filter_map(|pair| pair.split_once('=')) {
+            let (commit_type, label) = (commit_type.trim(), label.trim());
+            if commit_type.is_empty() || label.is_empty() {
+                continue;
+            }
+            label_map.retain(|(existing, _)| !existing.eq_ignore_ascii_case(commit_type));
+            label_map.push((commit_type.to_string(), label.to_string()));
+        }
+        
+        label_map
     }
 }
 
+/// Normalize a config key the way git does: section and variable names are
+/// case-insensitive, subsections are case-sensitive
+fn normalize_key(key: &str) -> String {
+    match (key.find('.'), key.rfind('.')) {
+        (Some(first), Some(last)) => format!(
+            "{}{}{}",
+            key[..first].to_lowercase(),
+            &key[first..last],
+            key[last..].to_lowercase()
+        ),
+        _ => key.to_lowercase(),
+    }
+}
+
+/// Config snapshot cached for the directory it was loaded from
+static CONFIG_CACHE: Mutex<Option<(PathBuf, Arc<Config>)>> = Mutex::new(None);
+
+/// Get the config for the current directory, loading it on first use
+pub fn current() -> Arc<Config> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let mut cache = CONFIG_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    
+    if let Some((dir, config)) = cache.as_ref()
+        && *dir == cwd
+    {
+        return config.clone();
+    }
+    
+    let config = Arc::new(Config::load().unwrap_or_default());
+    *cache = Some((cwd, config.clone()));
+    config
+}
+
+/// Drop the cached config so the next lookup reloads it
+pub fn invalidate_cache() {
+    *CONFIG_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Offline mode requested on the command line with `--offline`
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Turn offline mode on or off for the rest of the process
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Check whether gitx must not contact GitHub (`--offline` or `GITX_OFFLINE=1`)
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+        || std::env::var("GITX_OFFLINE").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+/// How much gitx prints beyond results and errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only results and errors (`--quiet`)
+    Quiet,
+    /// Progress lines such as "Pushing branch..." as well
+    Normal,
+    /// Also log every GitHub request and response (`--verbose`)
+    Verbose,
+}
+
+/// Verbosity chosen on the command line, stored as the enum's discriminant
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Set the verbosity for the rest of the process
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Get the verbosity chosen with `--quiet` or `--verbose`
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        2 => Verbosity::Verbose,
+        _ => Verbosity::Normal,
+    }
+}
+
+/// Check whether progress output is turned off (`--quiet`)
+pub fn is_quiet() -> bool {
+    verbosity() == Verbosity::Quiet
+}
+
+/// Get a git config value
+pub fn get_git_config(key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    Ok(current().get(key).map(str::to_string))
+}
+
+/// Check if gitx is initialized in the current repo
+#[allow(dead_code)]
+pub fn is_initialized() -> bool {
+    current().is_initialized()
+}
+
+/// Get the GitHub token from the keyring, `gh`, git config or environment (in that order)
+pub fn get_github_token() -> Option<String> {
+    current().github_token()
+}
+
+/// Get where the GitHub token was found, if one was
+pub fn get_github_token_source() -> Option<&'static str> {
+    current().github_token_source()
+}
+
 /// Check if GitHub integration is enabled
 #[allow(dead_code)]
 pub fn is_github_enabled() -> bool {
-    get_git_config("gitx.github.enabled")
-        .unwrap_or(None)
-        .map(|v| v == "true")
-        .unwrap_or(false)
+    current().is_github_enabled()
+}
+
+/// Get the configured GitHub host (`github.com` unless using GitHub Enterprise Server)
+pub fn get_github_host() -> String {
+    current().github_host()
+}
+
+/// Get the path of a PEM bundle of extra root certificates to trust for GitHub (`gitx.github.caBundle`)
+pub fn get_github_ca_bundle() -> Option<String> {
+    current().github_ca_bundle()
+}
+
+/// Get the owner of the fork PR branches are pushed to, if working from a fork
+pub fn get_github_fork() -> Option<String> {
+    current().github_fork()
+}
+
+/// Get the remote of the repository PRs are opened against (`gitx.github.upstream`, default `gitx.remote`)
+pub fn get_github_upstream_remote() -> String {
+    current().github_upstream_remote()
+}
+
+/// Get git's `user.name`, read once per directory along with the rest of the config
+pub fn get_git_username() -> Option<String> {
+    current().git_username()
+}
+
+/// Get the remote PR branches are pushed to (`gitx.remote`, default origin)
+pub fn get_remote() -> String {
+    current().remote()
 }
 
 /// Get the configured base branch
-#[allow(dead_code)]
 pub fn get_base_branch() -> String {
-    get_git_config("gitx.github.baseBranch")
-        .unwrap_or(None)
-        .unwrap_or_else(|| "main".to_string())
-}
\ No newline at end of file
+    current().base_branch()
+}
+
+/// Check if diverged PR branches are force-updated on the remote (`gitx.push.forceUpdate`)
+pub fn is_push_force_update_enabled() -> bool {
+    current().is_push_force_update_enabled()
+}
+
+/// Check if `gitx land` deletes merged PR branches from the remote (`gitx.branch.autoCleanup`)
+pub fn is_branch_auto_cleanup_enabled() -> bool {
+    current().is_branch_auto_cleanup_enabled()
+}
+
+/// Check if `gitx land` keeps the base branch linear (`gitx.land.rebase`)
+pub fn is_land_rebase_enabled() -> bool {
+    current().is_land_rebase_enabled()
+}
+
+/// Check if `gitx commit` chains into `gitx diff` (`gitx.commit.autoDiff`)
+pub fn is_commit_auto_diff_enabled() -> bool {
+    current().is_commit_auto_diff_enabled()
+}
+
+/// Check if branch names should include a short commit hash suffix
+pub fn is_branch_hash_enabled() -> bool {
+    current().is_branch_hash_enabled()
+}
+
+/// Get the configured ticket reference pattern
+pub fn get_ticket_pattern() -> String {
+    current().ticket_pattern()
+}
+
+/// Get the configured git notes ref for gitx metadata
+pub fn get_notes_ref() -> String {
+    current().notes_ref()
+}
+
+/// Get how many seconds fetched PR statuses stay cached
+pub fn get_status_cache_seconds() -> u64 {
+    current().status_cache_seconds()
+}
+
+/// Get how many days a PR can go without updates before it counts as stale
+pub fn get_prs_stale_days() -> u64 {
+    current().prs_stale_days()
+}
+
+/// Get the most commits `gitx diff` processes (0 means no limit)
+pub fn get_diff_limit() -> usize {
+    current().diff_limit()
+}
+
+/// Get the commit message markers that keep a commit out of `gitx diff`
+pub fn get_skip_markers() -> Vec<String> {
+    current().skip_markers()
+}
+
+/// Check if generated PR bodies include a diffstat
+pub fn is_diffstat_enabled() -> bool {
+    current().is_diffstat_enabled()
+}
+
+/// Get the custom PR template path, if one is configured
+pub fn get_pr_template() -> Option<String> {
+    current().pr_template()
+}
+
+/// Get how the PR template is combined with the generated body
+pub fn get_pr_template_mode() -> crate::github_utils::PRTemplateMode {
+    current().pr_template_mode()
+}
+
+/// Get how many times a rate-limited GitHub request is attempted
+pub fn get_github_max_attempts() -> u32 {
+    current().github_max_attempts()
+}
+
+/// Get the default reviewers to request on new PRs (comma-separated `gitx.pr.reviewers`)
+pub fn get_default_reviewers() -> Vec<String> {
+    current().default_reviewers()
+}
+
+/// Check if new PRs request reviews from CODEOWNERS
+pub fn is_pr_codeowners_enabled() -> bool {
+    current().is_pr_codeowners_enabled()
+}
+
+/// Check if new PRs are labeled by their conventional commit type
+pub fn is_pr_auto_label_enabled() -> bool {
+    current().is_pr_auto_label_enabled()
+}
+
+/// Get the conventional commit type to label mapping used by `gitx.pr.autoLabel`
+pub fn get_pr_label_map() -> Vec<(String, String)> {
+    current().pr_label_map()
+}
+
+/// Get the template PR titles are rendered from
+pub fn get_pr_title_format() -> String {
+    current().pr_title_format()
+}
+
+/// Get the longest PR title allowed before it is shortened (0 means no limit)
+pub fn get_pr_title_max_length() -> usize {
+    current().pr_title_max_length()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Runner that returns canned output and counts how often git would be spawned
+    struct CountingRunner {
+        output: String,
+        calls: Cell<usize>,
+    }
+
+    impl GitConfigRunner for CountingRunner {
+        fn run(&self, _args: &[&str]) -> Result<Option<String>, Box<dyn std::error::Error>> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(Some(self.output.clone()))
+        }
+    }
+
+    #[test]
+    fn test_config_single_subprocess_for_many_lookups() {
+        let runner = CountingRunner {
+            output: "gitx.github.token\nghp_test\0gitx.github.enabled\ntrue\0".to_string(),
+            calls: Cell::new(0),
+        };
+        
+        let config = Config::load_with_runner(&runner).unwrap();
+        for _ in 0..100 {
+            assert!(config.is_initialized());
+            assert!(config.is_github_enabled());
+            assert_eq!(config.base_branch(), "main");
+        }
+        
+        assert_eq!(runner.calls.get(), 1);
+    }
+
+    #[test]
+    fn test_config_parse_later_values_override() {
+        // Global value listed first, repo-local value last
+        let config = Config::parse("gitx.github.basebranch\nmain\0gitx.github.basebranch\ndevelop\0");
+        assert_eq!(config.base_branch(), "develop");
+    }
+
+    #[test]
+    fn test_branch_auto_cleanup_defaults_on() {
+        assert!(Config::parse("").is_branch_auto_cleanup_enabled());
+        assert!(Config::parse("gitx.branch.autocleanup\ntrue\0").is_branch_auto_cleanup_enabled());
+        assert!(!Config::parse("gitx.branch.autocleanup\nfalse\0").is_branch_auto_cleanup_enabled());
+        assert!(!Config::parse("gitx.branch.autocleanup\nno\0").is_branch_auto_cleanup_enabled());
+        assert!(!Config::parse("gitx.branch.autocleanup\nOff\0").is_branch_auto_cleanup_enabled());
+        assert!(!Config::parse("gitx.branch.autocleanup\n0\0").is_branch_auto_cleanup_enabled());
+    }
+
+    #[test]
+    fn test_land_rebase_defaults_on() {
+        assert!(Config::parse("").is_land_rebase_enabled());
+        assert!(!Config::parse("gitx.land.rebase\nfalse\0").is_land_rebase_enabled());
+        assert!(!Config::parse("gitx.land.rebase\nno\0").is_land_rebase_enabled());
+        assert!(!Config::parse("gitx.land.rebase\noff\0").is_land_rebase_enabled());
+        assert!(Config::parse("gitx.land.rebase\nyes\0").is_land_rebase_enabled());
+    }
+
+    #[test]
+    fn test_git_username() {
+        assert_eq!(Config::parse("user.name\nTest User\0").git_username(), Some("Test User".to_string()));
+        // A repo can blank out a global name
+        assert_eq!(Config::parse("user.name\nTest User\0user.name\n\0").git_username(), None);
+    }
+
+    #[test]
+    fn test_config_key_normalization() {
+        // git lowercases section and variable names in --list output
+        let config = Config::parse("gitx.branch.includehash\ntrue\0remote.MyFork.url\nhttps://example.com\0");
+        assert!(config.is_branch_hash_enabled());
+        assert_eq!(config.get("gitx.branch.includeHash"), Some("true"));
+        assert_eq!(config.get("remote.MyFork.URL"), Some("https://example.com"));
+        assert_eq!(config.get("remote.myfork.url"), None);
+    }
+
+    #[test]
+    fn test_find_setting() {
+        assert_eq!(find_setting("gitx.github.token").unwrap().key, "gitx.github.token");
+        assert_eq!(find_setting("github.baseBranch").unwrap().key, "gitx.github.baseBranch");
+        assert_eq!(find_setting("GITX.GITHUB.BASEBRANCH").unwrap().key, "gitx.github.baseBranch");
+        
+        let err = find_setting("github.nope").unwrap_err();
+        assert!(err.contains("Unknown gitx setting 'github.nope'"));
+        assert!(err.contains("gitx.github.enabled"));
+    }
+
+    #[test]
+    fn test_validate_setting_value() {
+        let enabled = find_setting("github.enabled").unwrap();
+        assert!(validate_setting_value(enabled, "true").is_ok());
+        assert!(validate_setting_value(enabled, "yes").is_err());
+        
+        let pattern = find_setting("branch.ticketPattern").unwrap();
+        assert!(validate_setting_value(pattern, r"JIRA-\d+").is_ok());
+        assert!(validate_setting_value(pattern, "([").is_err());
+        
+        let attempts = find_setting("github.maxAttempts").unwrap();
+        assert!(validate_setting_value(attempts, "5").is_ok());
+        assert!(validate_setting_value(attempts, "0").is_err());
+    }
+
+    struct FakeTokenSource(&'static str, Option<&'static str>);
+    
+    impl TokenSource for FakeTokenSource {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+        
+        fn token(&self) -> Option<String> {
+            self.1.map(str::to_string)
+        }
+    }
+
+    #[test]
+    fn test_resolve_github_token_priority() {
+        let keyring = FakeTokenSource("keyring", Some("from-keyring"));
+        let gh = FakeTokenSource("gh", Some("from-gh\n"));
+        let config = FakeTokenSource("config", Some("from-config"));
+        let missing = FakeTokenSource("missing", None);
+        let blank = FakeTokenSource("blank", Some("  "));
+        
+        assert_eq!(resolve_github_token(&[&keyring, &gh, &config]), Some(("keyring", "from-keyring".to_string())));
+        assert_eq!(resolve_github_token(&[&missing, &blank, &gh, &config]), Some(("gh", "from-gh".to_string())));
+        assert_eq!(resolve_github_token(&[&missing, &config]), Some(("config", "from-config".to_string())));
+        assert_eq!(resolve_github_token(&[&missing, &blank]), None);
+    }
+
+    #[test]
+    fn test_git_config_token_source() {
+        let config = Config::parse("gitx.github.token\nghp_secret\0");
+        assert_eq!(GitConfigTokenSource { config: &config }.token(), Some("ghp_secret".to_string()));
+        
+        let empty = Config::parse("");
+        assert_eq!(GitConfigTokenSource { config: &empty }.token(), None);
+    }
+
+    #[derive(Debug)]
+    struct ChainedError(&'static str, Option<Box<ChainedError>>);
+    
+    impl std::fmt::Display for ChainedError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+    
+    impl std::error::Error for ChainedError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            self.1.as_deref().map(|e| e as &(dyn std::error::Error + 'static))
+        }
+    }
+
+    #[test]
+    fn test_format_error_chain_redacts_tokens() {
+        let error = ChainedError(
+            "request failed",
+            Some(Box::new(ChainedError("bad header: Authorization: token ghp_abc123XYZ", Some(Box::new(ChainedError("github_pat_11AB_cd34 rejected", None)))))),
+        );
+        
+        let formatted = format_error_chain(&error);
+        
+        assert_eq!(
+            formatted,
+            "request failed\n  Caused by: bad header: Authorization: token ***\n  Caused by: *** rejected"
+        );
+        assert_eq!(redact("gho_xyz and ghp_ in prose"), "*** and ghp_ in prose");
+    }
+
+    #[test]
+    fn test_redact_secret() {
+        assert_eq!(redact_secret("ghp_abcdefghijklmnop"), "ghp_****");
+        assert_eq!(redact_secret("short"), "****");
+    }
+
+    #[test]
+    fn test_config_defaults_and_empty_values() {
+        let config = Config::parse("gitx.github.host\n\0gitx.pr.reviewers\nalice, bob,\0");
+        assert_eq!(config.github_host(), "github.com");
+        assert_eq!(config.ticket_pattern(), DEFAULT_TICKET_PATTERN);
+        assert_eq!(config.default_reviewers(), vec!["alice", "bob"]);
+        assert_eq!(config.skip_markers(), vec!["[skip gitx]", "[no-pr]"]);
+        assert!(!config.is_github_enabled());
+    }
+
+    #[test]
+    fn test_pr_label_map_overrides_defaults() {
+        assert_eq!(Config::parse("").pr_label_map(), vec![
+            ("feat".to_string(), "enhancement".to_string()),
+            ("fix".to_string(), "bug".to_string()),
+        ]);
+        
+        let config = Config::parse("gitx.pr.labelMap\nfix=defect, docs = documentation,bogus\0");
+        assert_eq!(config.pr_label_map(), vec![
+            ("feat".to_string(), "enhancement".to_string()),
+            ("fix".to_string(), "defect".to_string()),
+            ("docs".to_string(), "documentation".to_string()),
+        ]);
+    }
+}
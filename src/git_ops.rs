@@ -2,12 +2,14 @@ use git2::{Repository, BranchType, Oid};
 use crate::branch_naming;
 use crate::metadata;
 use crate::github::{self, GitHubClientTrait};
-use crate::github_utils::generate_pr_body;
-use crate::git_utils::GitUtils;
+use crate::github_utils::{format_commits_section, format_stack_section, generate_pr_body, insert_before_footer, is_wip_title, replace_stack_section, pr_title_for_subject, split_trailers};
+use crate::git_utils::{open_repo, GitUtils};
 use crate::client_factory;
+use crate::config;
+use crate::progress;
 
 pub fn get_all_branches() -> Result<Vec<String>, git2::Error> {
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
     let mut branches = Vec::new();
     
     let branch_iter = repo.branches(Some(BranchType::Local))?;
@@ -22,15 +24,17 @@ pub fn get_all_branches() -> Result<Vec<String>, git2::Error> {
     Ok(branches)
 }
 
-pub fn switch_branch(branch_name: &str) -> Result<(), git2::Error> {
-    let repo = Repository::open(".")?;
+/// Switch to a local branch, refusing to touch uncommitted changes unless `force` is set
+pub fn switch_branch(branch_name: &str, force: bool) -> Result<(), git2::Error> {
+    let repo = open_repo()?;
     
     // Get the branch reference
     let branch_ref = format!("refs/heads/{}", branch_name);
     let obj = repo.revparse_single(&branch_ref)?;
     
     // Checkout the branch
-    repo.checkout_tree(&obj, None)?;
+    let mut checkout = guarded_checkout(&repo, force)?;
+    repo.checkout_tree(&obj, Some(&mut checkout))?;
     
     // Set HEAD to point to the branch
     repo.set_head(&branch_ref)?;
@@ -38,17 +42,117 @@ pub fn switch_branch(branch_name: &str) -> Result<(), git2::Error> {
     Ok(())
 }
 
+/// Create a local branch at `start_point` (HEAD when `None`)
+pub fn create_branch(branch_name: &str, start_point: Option<&str>) -> Result<(), git2::Error> {
+    let repo = open_repo()?;
+    
+    if !git2::Branch::name_is_valid(branch_name)? {
+        return Err(git2::Error::from_str(&format!("'{}' is not a valid branch name", branch_name)));
+    }
+    if repo.find_branch(branch_name, BranchType::Local).is_ok() {
+        return Err(git2::Error::from_str(&format!("Branch '{}' already exists", branch_name)));
+    }
+    
+    let target = repo.revparse_single(start_point.unwrap_or("HEAD"))?.peel_to_commit()?;
+    repo.branch(branch_name, &target, false)?;
+    
+    Ok(())
+}
+
+/// Error unless HEAD points at a branch
+pub fn ensure_on_branch() -> Result<(), git2::Error> {
+    let repo = open_repo()?;
+    
+    // An unborn branch has no commits yet but is still a branch
+    if repo.head_detached()? {
+        return Err(git2::Error::from_str("gitx requires being on a branch (HEAD is detached)"));
+    }
+    Ok(())
+}
+
+/// Check whether the index holds changes that aren't committed yet
+pub fn has_staged_changes() -> Result<bool, git2::Error> {
+    let repo = open_repo()?;
+    
+    // Before the first commit everything in the index is staged
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e),
+    };
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+    Ok(diff.deltas().len() > 0)
+}
+
+/// Delete a local branch
+pub fn delete_branch(branch_name: &str) -> Result<(), git2::Error> {
+    let repo = open_repo()?;
+    let mut branch = repo.find_branch(branch_name, BranchType::Local)?;
+    
+    if branch.is_head() {
+        return Err(git2::Error::from_str(&format!("Cannot delete the current branch '{}'", branch_name)));
+    }
+    branch.delete()
+}
+
+/// Checkout options that refuse to clobber uncommitted changes unless `force` is set
+fn guarded_checkout(repo: &Repository, force: bool) -> Result<git2::build::CheckoutBuilder<'static>, git2::Error> {
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    if force {
+        checkout.force();
+    } else if has_uncommitted_changes(repo)? {
+        return Err(git2::Error::from_str(
+            "You have uncommitted changes. Commit or stash them first, or use --force to discard them",
+        ));
+    }
+    Ok(checkout)
+}
+
+/// Checkout options for a rewrite that commits whatever is staged, so only unstaged changes are refused
+fn guarded_checkout_keeping_staged(repo: &Repository) -> Result<git2::build::CheckoutBuilder<'static>, git2::Error> {
+    if has_unstaged_changes(repo)? {
+        return Err(git2::Error::from_str("You have unstaged changes. Stage, commit or stash them first"));
+    }
+    Ok(git2::build::CheckoutBuilder::new())
+}
+
+/// Check for staged or unstaged modifications to tracked files
+fn has_uncommitted_changes(repo: &Repository) -> Result<bool, git2::Error> {
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    
+    Ok(!repo.statuses(Some(&mut options))?.is_empty())
+}
+
+/// Check for modifications to tracked files that haven't been staged
+fn has_unstaged_changes(repo: &Repository) -> Result<bool, git2::Error> {
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    
+    Ok(repo.statuses(Some(&mut options))?.iter().any(|entry| entry.status().intersects(
+        git2::Status::WT_MODIFIED | git2::Status::WT_DELETED | git2::Status::WT_TYPECHANGE | git2::Status::WT_RENAMED,
+    )))
+}
+
 /// Get the current git user name from config
+///
+/// Served from the cached config snapshot, so repeated calls don't reopen the repository.
 pub fn get_git_username() -> Result<String, git2::Error> {
-    let repo = Repository::open(".")?;
-    let config = repo.config()?;
-    
-    config.get_string("user.name")
+    config::get_git_username().ok_or_else(|| git2::Error::from_str("user.name is not set"))
+}
+
+/// The user part of new branch names (`gitx/{namespace}/...`)
+///
+/// Prefers the GitHub login once `github_utils::get_github_login` has fetched it,
+/// falling back to git's `user.name`. Either way it is sanitized for use in a ref.
+pub fn get_branch_namespace() -> String {
+    let username = crate::github_utils::cached_github_login().or_else(|| get_git_username().ok());
+    branch_naming::sanitize_username(username.as_deref().unwrap_or("unknown"))
 }
 
 /// Determine the appropriate base branch for a commit by looking at its parent
 pub fn determine_base_branch_for_commit(commit_oid: &Oid) -> Result<String, git2::Error> {
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
     let commit = repo.find_commit(*commit_oid)?;
     
     // If the commit has parents, look at the first parent
@@ -82,6 +186,53 @@ pub fn determine_base_branch_for_commit(commit_oid: &Oid) -> Result<String, git2
     }
 }
 
+/// Whether a branch exists locally or as a remote-tracking branch on the configured remote
+pub fn branch_exists_locally_or_remotely(repo: &Repository, branch: &str) -> bool {
+    repo.find_branch(branch, BranchType::Local).is_ok()
+        || repo.find_branch(&format!("{}/{}", config::get_remote(), branch), BranchType::Remote).is_ok()
+}
+
+/// List the files that would conflict when merging a commit into its base branch
+///
+/// The base is looked up on the configured remote first, then locally. Returns an empty list when
+/// the base can't be found, since there is nothing to check against.
+pub fn find_merge_conflicts(repo: &Repository, commit_oid: &Oid, base_branch: &str) -> Result<Vec<String>, git2::Error> {
+    let base_ref = repo.find_reference(&format!("refs/remotes/{}/{}", config::get_remote(), base_branch))
+        .or_else(|_| repo.find_reference(&format!("refs/heads/{}", base_branch)));
+    let base_commit = match base_ref {
+        Ok(base_ref) => base_ref.peel_to_commit()?,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let commit = repo.find_commit(*commit_oid)?;
+    
+    let index = repo.merge_commits(&base_commit, &commit, None)?;
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
+    }
+    
+    let mut files = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        if let Some(entry) = entry {
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Describe the files that conflict with a PR's base branch
+pub fn format_conflict_warning(base_branch: &str, files: &[String]) -> String {
+    let mut warning = format!("This commit conflicts with '{}' in {} file(s):", base_branch, files.len());
+    for file in files {
+        warning.push_str(&format!("\n  - {}", file));
+    }
+    warning
+}
+
 /// Information about updates needed for commits
 #[derive(Debug, Clone)]
 pub enum CommitUpdateType {
@@ -89,23 +240,49 @@ pub enum CommitUpdateType {
     IncrementalUpdate {
         original_oid: Oid,
         updated_oid: Oid,
+        /// Full message of the updated commit
+        message: String,
         metadata: metadata::CommitMetadata,
+        commit_type: metadata::IncrementalCommitType,
     },
 }
 
+/// Check whether a commit should be skipped as a merge, erroring unless `allow_merges`
+/// (`gitx diff --allow-merges`) asked to step over them
+fn skip_merge_commit(commit: &git2::Commit, allow_merges: bool) -> Result<bool, git2::Error> {
+    if commit.parent_count() <= 1 {
+        return Ok(false);
+    }
+    if allow_merges {
+        return Ok(true);
+    }
+    
+    let short_id = commit.id().to_string()[..7].to_string();
+    Err(git2::Error::from_str(&format!(
+        "gitx expects a linear stack; found a merge commit {} ({}). Rebase it away or pass --allow-merges to skip it",
+        short_id,
+        commit.summary().unwrap_or(""),
+    )))
+}
+
 /// Get commits on main branch that need processing (new commits or incremental updates)
-pub fn get_commits_needing_processing() -> Result<Vec<CommitUpdateType>, git2::Error> {
-    get_commits_needing_processing_impl(false)
+pub fn get_commits_needing_processing(allow_merges: bool) -> Result<Vec<CommitUpdateType>, git2::Error> {
+    get_commits_needing_processing_impl(false, None, allow_merges)
 }
 
 /// Get only the latest commit that needs processing
-pub fn get_latest_commit_needing_processing() -> Result<Vec<CommitUpdateType>, git2::Error> {
-    get_commits_needing_processing_impl(true)
+pub fn get_latest_commit_needing_processing(allow_merges: bool) -> Result<Vec<CommitUpdateType>, git2::Error> {
+    get_commits_needing_processing_impl(true, None, allow_merges)
+}
+
+/// Get commits after `since` that need processing, instead of stopping at origin's merge base
+pub fn get_commits_needing_processing_since(since: &str, allow_merges: bool) -> Result<Vec<CommitUpdateType>, git2::Error> {
+    get_commits_needing_processing_impl(false, Some(since), allow_merges)
 }
 
 /// Internal implementation for getting commits needing processing
-fn get_commits_needing_processing_impl(latest_only: bool) -> Result<Vec<CommitUpdateType>, git2::Error> {
-    let repo = Repository::open(".")?;
+fn get_commits_needing_processing_impl(latest_only: bool, since: Option<&str>, allow_merges: bool) -> Result<Vec<CommitUpdateType>, git2::Error> {
+    let repo = open_repo()?;
     let mut updates = Vec::new();
     
     // Get main branch head
@@ -116,57 +293,302 @@ fn get_commits_needing_processing_impl(latest_only: bool) -> Result<Vec<CommitUp
     // Walk commits from HEAD
     let mut revwalk = repo.revwalk()?;
     revwalk.push(main_commit.id())?;
+    // Commits brought in by a skipped merge aren't part of the stack
+    if allow_merges {
+        revwalk.simplify_first_parent()?;
+    }
+    
+    // An explicit `--since` ref bounds the walk; otherwise commits already on the remote's
+    // copy of the branch don't need PRs, so stop at the merge base
+    if let Some(since) = since {
+        let since_commit = repo.revparse_single(since)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| git2::Error::from_str(&format!("'{}' doesn't resolve to a commit", since)))?;
+        revwalk.hide(since_commit.id())?;
+    } else {
+        let remote_ref = format!("refs/remotes/{}/{}", config::get_remote(), main_ref.shorthand().unwrap_or("main"));
+        if let Ok(remote_oid) = repo.refname_to_id(&remote_ref)
+            && let Ok(merge_base) = repo.merge_base(remote_oid, main_commit.id())
+        {
+            revwalk.hide(merge_base)?;
+        }
+    }
+    
+    let username = get_branch_namespace();
+    let include_hash = config::is_branch_hash_enabled();
     
-    let username = get_git_username().unwrap_or_else(|_| "unknown".to_string());
+    // gitx.diff.limit caps the walk on top of the merge base (0 = no cap); latest_only is always 1
+    let commit_limit = if latest_only {
+        1
+    } else {
+        match config::get_diff_limit() {
+            0 => usize::MAX,
+            limit => limit,
+        }
+    };
     
-    let commit_limit = if latest_only { 1 } else { 10 }; // Only 1 commit if latest_only
+    let skip_markers = config::get_skip_markers();
+    let mut walked = 0;
+    // Lower commits of grouped PRs, which are handled together with the PR's top commit
+    let mut grouped = Vec::new();
+    // Group of the most recent new commit, while the walk is still inside it
+    let mut current_group: Option<String> = None;
     
-    for oid in revwalk.take(commit_limit) {
+    for oid in revwalk {
+        // Past the limit the walk only goes on to finish the group it's in
+        if walked >= commit_limit && current_group.is_none() {
+            break;
+        }
+        
         let oid = oid?;
         let commit = repo.find_commit(oid)?;
         let message = commit.message().unwrap_or("").to_string();
         
-        // Check if this position in history has existing metadata stored elsewhere
-        // (This handles the case where commits are amended/rebased)
-        let current_commit_id = oid.to_string();
-        let mut found_metadata_for_position = false;
-        
-        // Check if we have metadata for this commit
-        if let Some(existing_metadata) = metadata::get_commit_metadata(&oid).map_err(|e| git2::Error::from_str(&e.to_string()))? {
-            // Check if the stored original commit ID matches current commit
-            if existing_metadata.is_commit_changed(&current_commit_id) {
-                // This means the commit was amended - we need an incremental update
-                updates.push(CommitUpdateType::IncrementalUpdate {
-                    original_oid: oid,
-                    updated_oid: oid,
-                    metadata: existing_metadata,
-                });
-                found_metadata_for_position = true;
-            } else {
-                // Commit unchanged, skip
-                found_metadata_for_position = true;
-            }
+        // Local-only commits marked with e.g. `[skip gitx]` never become PRs or count toward the limit
+        if skip_markers.iter().any(|marker| message.contains(marker.as_str())) {
+            continue;
+        }
+        if grouped.contains(&oid.to_string()) || skip_merge_commit(&commit, allow_merges)? {
+            continue;
         }
         
-        if !found_metadata_for_position {
-            // No metadata found - this is a new commit
-            let potential_branch = branch_naming::generate_branch_name(&username, &message);
-            
-            updates.push(CommitUpdateType::NewCommit(CommitInfo {
+        let group = split_trailers(&message).1.group;
+        let update = classify_commit(&commit, &username, include_hash)?;
+        match update {
+            Some(CommitUpdateType::NewCommit(commit_info)) => {
+                // Fold this commit into the PR above it when both carry the same `Gitx-Group:`
+                if let (Some(group), Some(CommitUpdateType::NewCommit(above))) = (&group, updates.last_mut())
+                    && current_group.as_ref() == Some(group)
+                    && repo.find_commit(above.grouped_commits.first().copied().unwrap_or(above.id))?.parent_id(0).ok() == Some(oid)
+                {
+                    // The group's PR is named after its first commit
+                    above.grouped_commits.insert(0, commit_info.id);
+                    above.message = commit_info.message;
+                    above.potential_branch_name = commit_info.potential_branch_name;
+                    continue;
+                }
+                // Folded commits don't count toward the limit; anything else starts a new PR
+                if walked >= commit_limit {
+                    break;
+                }
+                walked += 1;
+                current_group = group;
+                updates.push(CommitUpdateType::NewCommit(commit_info));
+            }
+            Some(update) => {
+                if walked >= commit_limit {
+                    break;
+                }
+                walked += 1;
+                current_group = None;
+                if let CommitUpdateType::IncrementalUpdate { metadata, .. } = &update {
+                    grouped.extend(metadata.commits.iter().cloned());
+                }
+                updates.push(update);
+            }
+            None => {
+                if walked >= commit_limit {
+                    break;
+                }
+                walked += 1;
+                current_group = None;
+                if let Some(existing) = metadata::get_commit_metadata(&oid).map_err(|e| git2::Error::from_str(&e.to_string()))? {
+                    grouped.extend(existing.commits);
+                }
+            }
+        }
+    }
+    
+    Ok(updates)
+}
+
+/// Work out whether a commit needs a new PR, an incremental update, or nothing
+fn classify_commit(commit: &git2::Commit, username: &str, include_hash: bool) -> Result<Option<CommitUpdateType>, git2::Error> {
+    let oid = commit.id();
+    let message = commit.message().unwrap_or("").to_string();
+    
+    // Check if this position in history has existing metadata stored elsewhere
+    // (This handles the case where commits are amended/rebased)
+    let current_commit_id = oid.to_string();
+    
+    // Check if we have metadata for this commit
+    if let Some(existing_metadata) = metadata::get_commit_metadata(&oid).map_err(|e| git2::Error::from_str(&e.to_string()))? {
+        // A branch pushed without a PR (`--push-only`) gets its PR on the next online run
+        if existing_metadata.github_pr_number.is_none()
+            && existing_metadata.status == metadata::PRStatus::BranchCreated
+            && !config::is_offline()
+        {
+            return Ok(Some(CommitUpdateType::NewCommit(CommitInfo {
                 id: oid,
-                message: message.clone(),
-                potential_branch_name: potential_branch,
+                message,
+                potential_branch_name: existing_metadata.pr_branch_name,
+                grouped_commits: Vec::new(),
+            })));
+        }
+        
+        // Check if the stored original commit ID matches current commit
+        if existing_metadata.is_commit_changed(&current_commit_id) {
+            // The commit changed since it was last pushed - we need an incremental update
+            return Ok(Some(CommitUpdateType::IncrementalUpdate {
+                original_oid: oid,
+                updated_oid: oid,
+                message,
+                commit_type: incremental_commit_type(commit, &existing_metadata),
+                metadata: existing_metadata,
+            }));
+        }
+        // Commit unchanged, skip
+        return Ok(None);
+    }
+    
+    // A fixup on top of a PR's last pushed commit is added to that PR rather than opening its own
+    if let Some((pr_oid, pr_metadata)) = pr_extended_by(commit)? {
+        if pr_metadata.incremental_commits.iter().any(|incremental| incremental.commit_id == current_commit_id) {
+            return Ok(None);
+        }
+        if incremental_commit_type(commit, &pr_metadata) == metadata::IncrementalCommitType::AdditionalCommit {
+            return Ok(Some(CommitUpdateType::IncrementalUpdate {
+                original_oid: pr_oid,
+                updated_oid: oid,
+                message,
+                commit_type: metadata::IncrementalCommitType::AdditionalCommit,
+                metadata: pr_metadata,
             }));
         }
     }
     
+    // No metadata found - this is a new commit
+    let potential_branch = if include_hash {
+        branch_naming::generate_branch_name_with_hash(username, &message, &current_commit_id)
+    } else {
+        branch_naming::generate_branch_name(username, &message)
+    };
+    
+    Ok(Some(CommitUpdateType::NewCommit(CommitInfo {
+        id: oid,
+        message,
+        potential_branch_name: potential_branch,
+        grouped_commits: Vec::new(),
+    })))
+}
+
+/// Whether a changed commit rewrote the last pushed one or was added on top of it
+///
+/// A commit whose parent is the last pushed commit extends the PR (`AdditionalCommit`);
+/// anything else took its place in history (`AmendedCommit`).
+fn incremental_commit_type(commit: &git2::Commit, pr_metadata: &metadata::CommitMetadata) -> metadata::IncrementalCommitType {
+    let last_pushed = pr_metadata
+        .incremental_commits
+        .last()
+        .map(|incremental| incremental.commit_id.as_str())
+        .unwrap_or(&pr_metadata.original_commit_id);
+    
+    match commit.parent_id(0) {
+        Ok(parent) if parent.to_string() == last_pushed => metadata::IncrementalCommitType::AdditionalCommit,
+        _ => metadata::IncrementalCommitType::AmendedCommit,
+    }
+}
+
+/// The PR a `fixup!`/`squash!` commit without metadata builds on, with the commit carrying its metadata
+///
+/// Follows first parents down through fixups already added to that PR, so a series of them
+/// all land on the same PR. Any other commit on top of a PR starts a PR of its own.
+fn pr_extended_by(commit: &git2::Commit) -> Result<Option<(Oid, metadata::CommitMetadata)>, git2::Error> {
+    let summary = commit.summary().unwrap_or("");
+    if !summary.starts_with("fixup! ") && !summary.starts_with("squash! ") {
+        return Ok(None);
+    }
+    
+    let mut skipped = Vec::new();
+    let mut below = commit.parent(0).ok();
+    while let Some(candidate) = below {
+        let candidate_metadata = metadata::get_commit_metadata(&candidate.id())
+            .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+        if let Some(pr_metadata) = candidate_metadata {
+            let recorded = |id: &String| pr_metadata.incremental_commits.iter().any(|incremental| &incremental.commit_id == id);
+            return Ok(skipped.iter().all(recorded).then_some((candidate.id(), pr_metadata)));
+        }
+        skipped.push(candidate.id().to_string());
+        below = candidate.parent(0).ok();
+    }
+    Ok(None)
+}
+
+/// Get the update needed for one specific commit, bypassing the history walk
+///
+/// The commit must be HEAD or one of its ancestors. Returns an empty list when the
+/// commit's PR is already up to date.
+pub fn get_commit_needing_processing(sha: &str, allow_merges: bool) -> Result<Vec<CommitUpdateType>, git2::Error> {
+    let repo = open_repo()?;
+    let commit = repo.revparse_single(sha)?.peel_to_commit()?;
+    let head_oid = repo.head()?.peel_to_commit()?.id();
+    
+    if commit.id() != head_oid && !repo.graph_descendant_of(head_oid, commit.id())? {
+        return Err(git2::Error::from_str(&format!("Commit {} is not reachable from HEAD", sha)));
+    }
+    if skip_merge_commit(&commit, allow_merges)? {
+        return Ok(Vec::new());
+    }
+    
+    let username = get_branch_namespace();
+    Ok(classify_commit(&commit, &username, config::is_branch_hash_enabled())?.into_iter().collect())
+}
+
+/// Get the updates for exactly the commits in `shas`, e.g. as piped to `gitx diff --stdin`
+///
+/// Each one must resolve to a commit reachable from HEAD; any that don't are reported together.
+pub fn get_commits_needing_processing_for(shas: &[String], allow_merges: bool) -> Result<Vec<CommitUpdateType>, git2::Error> {
+    let repo = open_repo()?;
+    let head_oid = repo.head()?.peel_to_commit()?.id();
+    
+    let mut commits: Vec<git2::Commit> = Vec::new();
+    let mut unknown = Vec::new();
+    for sha in shas {
+        match repo.revparse_single(sha).and_then(|object| object.peel_to_commit()) {
+            Ok(commit) if commit.id() == head_oid || repo.graph_descendant_of(head_oid, commit.id())? => {
+                if !commits.iter().any(|seen| seen.id() == commit.id()) {
+                    commits.push(commit);
+                }
+            }
+            _ => unknown.push(sha.as_str()),
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(git2::Error::from_str(&format!("Unknown commits or not reachable from HEAD: {}", unknown.join(", "))));
+    }
+    
+    // Process the bottom of the stack first, whatever order the shas were given in
+    let mut walk = repo.revwalk()?;
+    walk.push(head_oid)?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+    let mut ordered = Vec::with_capacity(commits.len());
+    for oid in walk {
+        let oid = oid?;
+        if let Some(index) = commits.iter().position(|commit| commit.id() == oid) {
+            ordered.push(commits.swap_remove(index));
+            if commits.is_empty() {
+                break;
+            }
+        }
+    }
+    ordered.reverse();
+    
+    let username = get_branch_namespace();
+    let include_hash = config::is_branch_hash_enabled();
+    let mut updates = Vec::new();
+    for commit in ordered {
+        if skip_merge_commit(&commit, allow_merges)? {
+            continue;
+        }
+        updates.extend(classify_commit(&commit, &username, include_hash)?);
+    }
     Ok(updates)
 }
 
 /// Legacy function for backward compatibility
 #[allow(dead_code)]
 pub fn get_unpushed_commits() -> Result<Vec<CommitInfo>, git2::Error> {
-    let updates = get_commits_needing_processing()?;
+    let updates = get_commits_needing_processing(false)?;
     let mut commits = Vec::new();
     
     for update in updates {
@@ -178,57 +600,160 @@ pub fn get_unpushed_commits() -> Result<Vec<CommitInfo>, git2::Error> {
     Ok(commits)
 }
 
+/// A commit in the current stack and the gitx metadata tracking it, if any
+#[derive(Debug, Clone)]
+pub struct StackCommit {
+    pub id: Oid,
+    pub summary: String,
+    pub metadata: Option<metadata::CommitMetadata>,
+}
+
+/// Get the commits unique to the current branch, newest first
+/// Commits reachable from the branch's upstream (or the configured base branch)
+/// are excluded; without either, the most recent commits are returned
+pub fn get_stack_commits() -> Result<Vec<StackCommit>, git2::Error> {
+    get_stack_commits_impl(Some(10))
+}
+
+/// Get every commit unique to the current branch, newest first
+/// Without an upstream or base branch to stop at, this walks the whole history
+pub fn get_branch_commits() -> Result<Vec<StackCommit>, git2::Error> {
+    get_stack_commits_impl(None)
+}
+
+fn get_stack_commits_impl(limit: Option<usize>) -> Result<Vec<StackCommit>, git2::Error> {
+    let repo = open_repo()?;
+    let head = repo.head()?;
+    
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.peel_to_commit()?.id())?;
+    
+    // Find what the stack sits on top of
+    let upstream = head
+        .shorthand()
+        .and_then(|name| repo.find_branch(name, BranchType::Local).ok())
+        .and_then(|branch| branch.upstream().ok())
+        .and_then(|upstream| upstream.get().target());
+    let base_branch = config::get_base_branch();
+    let base = upstream
+        .or_else(|| repo.refname_to_id(&format!("refs/remotes/{}/{}", config::get_remote(), base_branch)).ok())
+        .or_else(|| {
+            if head.shorthand() == Some(base_branch.as_str()) {
+                None
+            } else {
+                repo.refname_to_id(&format!("refs/heads/{}", base_branch)).ok()
+            }
+        });
+    if let Some(base) = base {
+        revwalk.hide(base)?;
+    }
+    
+    let mut commits = Vec::new();
+    for oid in revwalk.take(limit.unwrap_or(usize::MAX)) {
+        let oid = oid?;
+        let commit = repo.find_commit(oid)?;
+        let metadata = metadata::get_commit_metadata(&oid).ok().flatten();
+        
+        commits.push(StackCommit {
+            id: oid,
+            summary: commit.summary().unwrap_or("").to_string(),
+            metadata,
+        });
+    }
+    
+    Ok(commits)
+}
+
 /// Information about a commit that could become a PR
 #[derive(Debug, Clone)]
 pub struct CommitInfo {
     pub id: Oid,
     pub message: String,
     pub potential_branch_name: String,
+    /// Commits below `id` that go into the same PR, oldest first (empty unless grouped)
+    pub grouped_commits: Vec<Oid>,
+}
+
+impl CommitInfo {
+    /// The commit that names the PR and decides its base: the first one of a group
+    pub fn first_commit(&self) -> Oid {
+        self.grouped_commits.first().copied().unwrap_or(self.id)
+    }
+    
+    /// Every commit in a grouped PR, oldest first, as stored in its metadata (empty unless grouped)
+    pub fn pr_commit_ids(&self) -> Vec<String> {
+        if self.grouped_commits.is_empty() {
+            return Vec::new();
+        }
+        self.grouped_commits.iter().chain(std::iter::once(&self.id)).map(Oid::to_string).collect()
+    }
 }
 
 /// Create a transient PR branch for a specific commit
 pub fn create_pr_branch(commit_info: &CommitInfo) -> Result<(), git2::Error> {
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
     
     // Get the commit object
     let commit = repo.find_commit(commit_info.id)?;
     
-    // Try to create the branch at this commit
-    let branch_created = match repo.branch(&commit_info.potential_branch_name, &commit, false) {
-        Ok(_) => {
-            println!("Created branch: {}", commit_info.potential_branch_name);
-            true
-        }
-        Err(e) if e.code() == git2::ErrorCode::Exists => {
-            println!("Branch already exists: {}", commit_info.potential_branch_name);
-            true // Branch exists, that's still success for our purposes
-        }
-        Err(e) => return Err(e), // Real error, propagate it
-    };
+    // Create the branch at this commit (or reuse the one already there)
+    create_or_reuse_pr_branch(&repo, &commit_info.potential_branch_name, &commit)?;
     
-    if branch_created {
-        // Store metadata for this commit (only if we don't already have it)
-        if !metadata::has_pr_metadata(&commit_info.id) {
-            let commit_metadata = metadata::CommitMetadata::new_branch_created(
-                commit_info.potential_branch_name.clone(),
-                commit_info.id.to_string()
-            );
-            
-            metadata::store_commit_metadata(&commit_info.id, &commit_metadata)
-                .map_err(|e| git2::Error::from_str(&format!("Failed to store metadata: {}", e)))?;
-        }
+    // Store metadata for this commit (only if we don't already have it)
+    if !metadata::has_pr_metadata(&commit_info.id) {
+        let commit_metadata = metadata::CommitMetadata::new_branch_created(
+            commit_info.potential_branch_name.clone(),
+            commit_info.id.to_string()
+        ).with_commits(commit_info.pr_commit_ids());
+        
+        metadata::store_commit_metadata(&commit_info.id, &commit_metadata)
+            .map_err(|e| git2::Error::from_str(&format!("Failed to store metadata: {}", e)))?;
     }
     
     Ok(())
 }
 
+/// Create a local PR branch at `commit`, reusing an existing gitx branch of the same name
+///
+/// A gitx branch left at another commit is moved, since it would hide the new commit from
+/// the PR; a branch gitx doesn't manage is never touched.
+fn create_or_reuse_pr_branch<'repo>(
+    repo: &'repo Repository,
+    branch_name: &str,
+    commit: &git2::Commit,
+) -> Result<git2::Branch<'repo>, git2::Error> {
+    match repo.branch(branch_name, commit, false) {
+        Ok(branch) => {
+            tracing::info!("Created branch: {}", branch_name);
+            Ok(branch)
+        }
+        Err(e) if e.code() == git2::ErrorCode::Exists => {
+            let existing = repo.find_branch(branch_name, BranchType::Local)?;
+            if existing.get().target() == Some(commit.id()) {
+                tracing::info!("Branch already exists: {}", branch_name);
+                Ok(existing)
+            } else if branch_naming::is_transient_pr_branch(branch_name) {
+                tracing::info!("Updated branch: {}", branch_name);
+                repo.branch(branch_name, commit, true)
+            } else {
+                Err(git2::Error::from_str(&format!(
+                    "Branch '{}' already exists at a different commit and isn't managed by gitx; rename or delete it first",
+                    branch_name
+                )))
+            }
+        }
+        Err(e) => Err(e), // Real error, propagate it
+    }
+}
+
 /// Create an incremental commit on an existing PR branch
 pub fn create_incremental_commit(
     original_commit_oid: &Oid,
     updated_commit_oid: &Oid,
     pr_metadata: &metadata::CommitMetadata,
+    commit_type: metadata::IncrementalCommitType,
 ) -> Result<(), git2::Error> {
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
     
     // Get the PR branch
     let pr_branch = repo.find_branch(&pr_metadata.pr_branch_name, BranchType::Local)?;
@@ -258,13 +783,13 @@ pub fn create_incremental_commit(
         &[&pr_branch_commit],
     )?;
     
-    println!("Added incremental commit to: {}", pr_metadata.pr_branch_name);
+    progress::println(format!("Added incremental commit to: {}", pr_metadata.pr_branch_name));
     
     // Update metadata to track this incremental commit
     let updated_metadata = pr_metadata.clone().add_incremental_commit(
         updated_commit_oid.to_string(),
         updated_commit.message().unwrap_or("").to_string(),
-        metadata::IncrementalCommitType::AmendedCommit,
+        commit_type,
     );
     
     metadata::update_commit_metadata(original_commit_oid, &updated_metadata)
@@ -277,6 +802,7 @@ pub fn create_incremental_commit(
 pub async fn create_pr_branch_with_github_client(
     commit_info: &CommitInfo,
     enable_github: bool,
+    pr_options: &github::PRCreateOptions,
     github_client: Option<&dyn GitHubClientTrait>,
 ) -> Result<Option<github::PRInfo>, Box<dyn std::error::Error>> {
     if !enable_github {
@@ -287,11 +813,11 @@ pub async fn create_pr_branch_with_github_client(
     
     // GitHub mode: create transient branch, push, create PR, then delete local branch
     if let Some(client) = github_client {
-        create_transient_pr_branch_with_github_client(commit_info, client).await
+        create_transient_pr_branch_with_github_client(commit_info, pr_options, client).await
     } else {
         // Create a GitHub client using factory (real in production, mock in tests)
         let github_client = client_factory::create_github_client().await?;
-        create_transient_pr_branch_with_github_client(commit_info, &*github_client).await
+        create_transient_pr_branch_with_github_client(commit_info, pr_options, &*github_client).await
     }
 }
 
@@ -299,85 +825,234 @@ pub async fn create_pr_branch_with_github_client(
 pub async fn create_pr_branch_with_github(
     commit_info: &CommitInfo,
     enable_github: bool,
+    pr_options: &github::PRCreateOptions,
 ) -> Result<Option<github::PRInfo>, Box<dyn std::error::Error>> {
-    create_pr_branch_with_github_client(commit_info, enable_github, None).await
+    create_pr_branch_with_github_client(commit_info, enable_github, pr_options, None).await
+}
+
+/// Local branch that only lives while a PR is being pushed
+///
+/// Deleted when dropped, so a step failing part-way leaves nothing behind for a retry to trip over.
+struct TransientBranch<'repo> {
+    branch: Option<git2::Branch<'repo>>,
+}
+
+impl<'repo> TransientBranch<'repo> {
+    fn new(branch: git2::Branch<'repo>) -> Self {
+        Self { branch: Some(branch) }
+    }
+    
+    /// Delete the branch now, reporting any failure
+    fn delete(mut self) -> Result<(), git2::Error> {
+        match self.branch.take() {
+            Some(mut branch) => branch.delete(),
+            None => Ok(()),
+        }
+    }
+    
+    /// Keep the branch after all
+    fn keep(mut self) {
+        self.branch.take();
+    }
 }
 
+impl Drop for TransientBranch<'_> {
+    fn drop(&mut self) {
+        if let Some(mut branch) = self.branch.take() {
+            let _ = branch.delete();
+        }
+    }
+}
 
 /// Create a transient PR branch with dependency injection for GitHub client
 pub async fn create_transient_pr_branch_with_github_client(
     commit_info: &CommitInfo,
+    pr_options: &github::PRCreateOptions,
     github_client: &dyn GitHubClientTrait,
 ) -> Result<Option<github::PRInfo>, Box<dyn std::error::Error>> {
-    let repo = Repository::open(".").map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+    let repo = open_repo().map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
     
-    // 1. Create temporary local branch
     let commit = repo.find_commit(commit_info.id).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-    let mut temp_branch = repo.branch(&commit_info.potential_branch_name, &commit, false)
-        .map_err(|e| e)?;
-    
-    // 2. Push branch to remote
-    GitUtils::push_branch(&commit_info.potential_branch_name).await?;
-    
-    // 3. Create metadata (before deleting local branch)
-    let commit_message = commit.message().unwrap_or("");
+    // A grouped PR takes its title and description from the group's first commit
+    let first_commit = repo.find_commit(commit_info.first_commit())?;
+    let commit_message = first_commit.message().unwrap_or("");
     let commit_metadata = metadata::CommitMetadata::new_branch_created(
         commit_info.potential_branch_name.clone(),
         commit_info.id.to_string()
-    );
-    metadata::store_commit_metadata(&commit_info.id, &commit_metadata)
-        .map_err(|e| e)?;
+    ).with_commits(commit_info.pr_commit_ids());
+    
+    // Work out the PR title and body first, so `--edit` can abort before anything is pushed
+    // (WIP commits become drafts with the marker stripped from the title)
+    let subject = commit_message.lines().next().unwrap_or("Untitled commit");
+    let draft = pr_options.draft || is_wip_title(subject);
+    let mut pr_title = pr_title_for_subject(subject);
+    let mut pr_body = if commit_info.grouped_commits.is_empty() {
+        generate_pr_body(&commit_metadata, commit_message, Some((&repo, commit_info.id)))
+    } else {
+        let mut commits = commit_info.grouped_commits.clone();
+        commits.push(commit_info.id);
+        let body = generate_pr_body(&commit_metadata, commit_message, None);
+        insert_before_footer(&body, &format_commits_section(&repo, &commits))
+    };
+    if pr_options.edit && !pr_options.push_only {
+        (pr_title, pr_body) = crate::pr_editor::edit_pr_message(&pr_title, &pr_body)?;
+    }
+    
+    // Use the requested base branch, or determine the appropriate one for this commit
+    let base_branch = match &pr_options.base {
+        Some(base) => {
+            if !branch_exists_locally_or_remotely(&repo, base) {
+                return Err(format!("Base branch '{}' doesn't exist locally or on {}", base, config::get_remote()).into());
+            }
+            base.clone()
+        }
+        None => determine_base_branch_for_commit(&commit_info.first_commit())
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?,
+    };
+    
+    // Check the commit still merges into its base before pushing anything
+    let conflicts = find_merge_conflicts(&repo, &commit_info.id, &base_branch)?;
+    if !conflicts.is_empty() {
+        let warning = format_conflict_warning(&base_branch, &conflicts);
+        if pr_options.skip_conflicts {
+            return Err(format!("{}\nSkipped because of --skip-conflicts", warning).into());
+        }
+        progress::eprintln(format!("Warning: {}", warning));
+    }
+    
+    // 1. Create temporary local branch (deleted again if any later step fails)
+    let temp_branch = TransientBranch::new(create_or_reuse_pr_branch(&repo, &commit_info.potential_branch_name, &commit)?);
     
-    // 4. Create the PR
-    let pr_title = commit_message.lines().next().unwrap_or("Untitled commit").to_string();
-    let pr_body = generate_pr_body(&commit_metadata, commit_message);
+    // 2. Push branch to remote
+    GitUtils::push_branch(&commit_info.potential_branch_name, pr_options.force).await?;
     
-    // Determine the appropriate base branch for this commit
-    let base_branch = determine_base_branch_for_commit(&commit_info.id)
-        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+    // With --push-only the branch stays put, and a later `gitx diff` opens its PR
+    if pr_options.push_only {
+        metadata::update_commit_metadata(&commit_info.id, &commit_metadata.with_base_branch(base_branch))?;
+        temp_branch.keep();
+        progress::println(format!("Pushed branch {} without opening a PR (run 'gitx diff' again to open one)", commit_info.potential_branch_name));
+        return Ok(None);
+    }
     
+    // 3. Create the PR (from the fork's branch when working from a fork)
     let pr_info = github_client.create_pr(
-        &commit_info.potential_branch_name,
+        &crate::github_utils::pr_head_ref(&commit_info.potential_branch_name),
         &pr_title,
         &pr_body,
         &base_branch,
+        draft,
     ).await?;
     
-    // 5. Update metadata with PR number
-    let updated_metadata = commit_metadata.with_pr_number(pr_info.number);
+    // Apply labels, reviewers and assignees (the PR already exists, so failures are only warnings)
+    let mut labels = pr_options.labels.clone();
+    if config::is_pr_auto_label_enabled()
+        && let Some(label) = crate::github_utils::conventional_label(commit.summary().unwrap_or(""), &config::get_pr_label_map())
+        && !labels.contains(&label)
+    {
+        labels.push(label);
+    }
+    if !labels.is_empty()
+        && let Err(e) = github_client.add_labels(pr_info.number, &labels).await
+    {
+        progress::eprintln(format!("Warning: Failed to add labels to PR #{}: {}", pr_info.number, e));
+    }
+    let mut reviewers = pr_options.reviewers.clone();
+    let mut team_reviewers = Vec::new();
+    if config::is_pr_codeowners_enabled() {
+        // The PR already exists, so a failed lookup only costs the automatic reviewers
+        match changed_paths(&repo, commit_info) {
+            Ok(paths) => {
+                let code_owners = crate::github_utils::codeowners_for(&paths);
+                // GitHub refuses to request a review from the PR's own author
+                let author = github_client.get_current_user().await.ok();
+                for user in code_owners.users {
+                    if author.as_ref() != Some(&user) && !reviewers.contains(&user) {
+                        reviewers.push(user);
+                    }
+                }
+                team_reviewers = code_owners.teams;
+            }
+            Err(e) => progress::eprintln(format!("Warning: Could not look up code owners for PR #{}: {}", pr_info.number, e)),
+        }
+    }
+    if !reviewers.is_empty()
+        && let Err(e) = github_client.request_reviewers(pr_info.number, &reviewers).await
+    {
+        progress::eprintln(format!("Warning: Failed to request reviewers on PR #{}: {}", pr_info.number, e));
+    }
+    if !team_reviewers.is_empty()
+        && let Err(e) = github_client.request_team_reviewers(pr_info.number, &team_reviewers).await
+    {
+        progress::eprintln(format!("Warning: Failed to request team reviewers on PR #{}: {}", pr_info.number, e));
+    }
+    if !pr_options.assignees.is_empty()
+        && let Err(e) = github_client.add_assignees(pr_info.number, &pr_options.assignees).await
+    {
+        progress::eprintln(format!("Warning: Failed to add assignees to PR #{}: {}", pr_info.number, e));
+    }
+    
+    // 4. Store metadata with PR number and the branch it targets, only once the PR exists
+    // (a failed attempt leaves no note behind, so a retry starts clean)
+    let updated_metadata = commit_metadata
+        .with_pr_number(pr_info.number)
+        .with_base_branch(base_branch);
     metadata::update_commit_metadata(&commit_info.id, &updated_metadata)
         .map_err(|e| e)?;
     
-    // 6. Delete the local branch (keep only on GitHub)
-    temp_branch.delete().map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+    // 5. Delete the local branch (keep only on GitHub)
+    temp_branch.delete()?;
     
-    println!("Created GitHub PR #{}: {} (transient branch deleted locally)", pr_info.number, pr_info.url);
+    progress::println(format!("Created GitHub PR #{}: {} (transient branch deleted locally)", pr_info.number, pr_info.url));
+    
+    // 6. Link the PRs in this stack to each other now that it has grown
+    if let Err(e) = update_stack_pr_bodies_with_github_client(&commit_info.potential_branch_name, github_client).await {
+        progress::eprintln(format!("Warning: Failed to update stack links in PR bodies: {}", e));
+    }
     
     Ok(Some(pr_info))
 }
 
+/// Paths a PR changes: everything touched between the parent of its first commit and its last
+fn changed_paths(repo: &Repository, commit_info: &CommitInfo) -> Result<Vec<String>, git2::Error> {
+    let tree = repo.find_commit(commit_info.id)?.tree()?;
+    let first = repo.find_commit(commit_info.first_commit())?;
+    let parent_tree = match first.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+    Ok(diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
+
 /// Create incremental commit with dependency injection for GitHub client
 pub async fn create_incremental_commit_with_github_client(
     original_commit_oid: &Oid,
     updated_commit_oid: &Oid,
     pr_metadata: &metadata::CommitMetadata,
+    commit_type: metadata::IncrementalCommitType,
+    force_update: bool,
     enable_github: bool,
     github_client: Option<&dyn GitHubClientTrait>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if !enable_github {
         // Local-only mode: create persistent local incremental commit
-        create_incremental_commit(original_commit_oid, updated_commit_oid, pr_metadata)
+        create_incremental_commit(original_commit_oid, updated_commit_oid, pr_metadata, commit_type)
             .map_err(|e| e)?;
         return Ok(());
     }
     
     // GitHub mode: create transient incremental commit
     if let Some(client) = github_client {
-        create_transient_incremental_commit_with_github_client(original_commit_oid, updated_commit_oid, pr_metadata, client).await
+        create_transient_incremental_commit_with_github_client(original_commit_oid, updated_commit_oid, pr_metadata, commit_type, force_update, client).await
     } else {
         // Create a GitHub client using factory (real in production, mock in tests)
         let github_client = client_factory::create_github_client().await?;
-        create_transient_incremental_commit_with_github_client(original_commit_oid, updated_commit_oid, pr_metadata, &*github_client).await
+        create_transient_incremental_commit_with_github_client(original_commit_oid, updated_commit_oid, pr_metadata, commit_type, force_update, &*github_client).await
     }
 }
 
@@ -386,9 +1061,11 @@ pub async fn create_incremental_commit_with_github(
     original_commit_oid: &Oid,
     updated_commit_oid: &Oid,
     pr_metadata: &metadata::CommitMetadata,
+    commit_type: metadata::IncrementalCommitType,
+    force_update: bool,
     enable_github: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    create_incremental_commit_with_github_client(original_commit_oid, updated_commit_oid, pr_metadata, enable_github, None).await
+    create_incremental_commit_with_github_client(original_commit_oid, updated_commit_oid, pr_metadata, commit_type, force_update, enable_github, None).await
 }
 
 /// Create a transient incremental commit with dependency injection for GitHub client  
@@ -396,19 +1073,20 @@ pub async fn create_transient_incremental_commit_with_github_client(
     original_commit_oid: &Oid,
     updated_commit_oid: &Oid,
     pr_metadata: &metadata::CommitMetadata,
+    commit_type: metadata::IncrementalCommitType,
+    force_update: bool,
     github_client: &dyn GitHubClientTrait,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if pr_metadata.github_pr_number.is_none() {
-        println!("Warning: No GitHub PR number found, skipping PR update");
+        progress::println("Warning: No GitHub PR number found, skipping PR update");
         return Ok(());
     }
     
-    let repo = Repository::open(".").map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+    let repo = open_repo().map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
     
     // 1. Create temporary local branch with incremental commit
     let updated_commit = repo.find_commit(*updated_commit_oid).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-    let mut temp_branch = repo.branch(&pr_metadata.pr_branch_name, &updated_commit, false)
-        .map_err(|e| e)?;
+    let temp_branch = TransientBranch::new(repo.branch(&pr_metadata.pr_branch_name, &updated_commit, false)?);
     
     // 2. Create incremental commit on the temp branch
     let signature = repo.signature().map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
@@ -429,55 +1107,755 @@ pub async fn create_transient_incremental_commit_with_github_client(
     ).map_err(|e| e)?;
     
     // 3. Push the updated branch to remote
-    GitUtils::push_branch(&pr_metadata.pr_branch_name).await?;
+    GitUtils::push_branch(&pr_metadata.pr_branch_name, force_update).await?;
     
     // 4. Update metadata to track this incremental commit
     let updated_metadata = pr_metadata.clone().add_incremental_commit(
         updated_commit_oid.to_string(),
         updated_commit.message().unwrap_or("").to_string(),
-        metadata::IncrementalCommitType::AmendedCommit,
+        commit_type,
     );
     metadata::update_commit_metadata(original_commit_oid, &updated_metadata)
         .map_err(|e| e)?;
     
     // 5. Update the GitHub PR
     let commit_message = updated_commit.message().unwrap_or("");
-    let pr_body = generate_pr_body(&updated_metadata, commit_message);
+    let pr_body = generate_pr_body(&updated_metadata, commit_message, Some((&repo, *updated_commit_oid)));
     let pr_number = pr_metadata.github_pr_number.unwrap();
     github_client.update_pr(pr_number, None, Some(&pr_body)).await?;
     
+    // Mark the PR ready once the WIP marker has been dropped from the commit
+    let previous_subject = previous_commit_message(&repo, pr_metadata);
+    let current_subject = commit_message.lines().next().unwrap_or("");
+    if previous_subject.lines().next().is_some_and(is_wip_title) && !is_wip_title(current_subject) {
+        github_client.set_draft(pr_number, false).await?;
+        progress::println(format!("WIP marker removed, marked PR #{} ready for review", pr_number));
+    }
+    
     // 6. Delete the local branch (keep only on GitHub)
-    temp_branch.delete().map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+    temp_branch.delete()?;
+    
+    progress::println(format!("Updated GitHub PR #{} (transient branch deleted locally)", pr_number));
+    
+    // The body was regenerated above, so put the stack links back
+    if let Err(e) = update_stack_pr_bodies_with_github_client(&pr_metadata.pr_branch_name, github_client).await {
+        progress::eprintln(format!("Warning: Failed to update stack links in PR bodies: {}", e));
+    }
+    
+    Ok(())
+}
+
+/// Rewrite the body of every PR in the stack containing `branch_name` with an up-to-date "Stack" section
+pub async fn update_stack_pr_bodies_with_github_client(
+    branch_name: &str,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = open_repo()?;
+    let prs = metadata::get_all_pr_status()?;
+    let stack = metadata::compute_stack(&prs, branch_name);
+    
+    // A lone PR has nothing to link to
+    if stack.len() < 2 {
+        return Ok(());
+    }
     
-    println!("Updated GitHub PR #{} (transient branch deleted locally)", pr_number);
+    let pr_numbers: Vec<u64> = stack.iter().filter_map(|pr| pr.pr_number).collect();
+    for pr in &stack {
+        let Some(pr_number) = pr.pr_number else { continue };
+        let oid = Oid::from_str(&pr.commit_id)?;
+        let Some(pr_metadata) = metadata::get_commit_metadata(&oid)? else { continue };
+        
+        // Amended PRs are described by their latest commit
+        let (commit_message, commit_oid) = match &pr.latest_incremental {
+            Some(latest) => (latest.message.as_str(), Oid::from_str(&latest.commit_id).unwrap_or(oid)),
+            None => (pr.commit_message.as_str(), oid),
+        };
+        // Only the Stack section is refreshed so edited descriptions and other sections survive
+        let stack_section = format_stack_section(&pr_numbers, pr_number);
+        let existing = github_client.fetch_pr_body(pr_number).await?;
+        let body = if existing.trim().is_empty() {
+            insert_before_footer(&generate_pr_body(&pr_metadata, commit_message, Some((&repo, commit_oid))), &stack_section)
+        } else {
+            replace_stack_section(&existing, &stack_section)
+        };
+        github_client.update_pr(pr_number, None, Some(&body)).await?;
+    }
     
     Ok(())
 }
 
+/// Get the message of the commit a PR was last updated from
+fn previous_commit_message(repo: &Repository, pr_metadata: &metadata::CommitMetadata) -> String {
+    if let Some(latest) = pr_metadata.incremental_commits.last() {
+        return latest.message.clone();
+    }
+    
+    Oid::from_str(&pr_metadata.original_commit_id)
+        .and_then(|oid| repo.find_commit(oid))
+        .map(|commit| commit.message().unwrap_or("").to_string())
+        .unwrap_or_default()
+}
+
 /// Create a transient incremental commit that only exists on GitHub (legacy wrapper)
 pub async fn create_transient_incremental_commit_with_github(
     original_commit_oid: &Oid,
     updated_commit_oid: &Oid,
     pr_metadata: &metadata::CommitMetadata,
+    commit_type: metadata::IncrementalCommitType,
+    force_update: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Check if GitHub token is available
     if !github::check_github_token() {
-        println!("Warning: GITHUB_TOKEN not set, skipping GitHub PR update");
+        progress::println("Warning: GITHUB_TOKEN not set, skipping GitHub PR update");
         return Ok(());
     }
     
     // Create a GitHub client using factory (real in production, mock in tests)
     let github_client = client_factory::create_github_client().await?;
-    create_transient_incremental_commit_with_github_client(original_commit_oid, updated_commit_oid, pr_metadata, &*github_client).await
+    create_transient_incremental_commit_with_github_client(original_commit_oid, updated_commit_oid, pr_metadata, commit_type, force_update, &*github_client).await
 }
 
-/// Land (cleanup) merged PRs by detecting merged status from GitHub and cleaning up local branches
-pub async fn land_merged_prs(all: bool, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
-    // Check if GitHub token is available
-    if !github::check_github_token() {
+/// Mark the PR tracked by a branch as ready for review, with dependency injection for GitHub client
+pub async fn mark_pr_ready_with_github_client(
+    branch_name: &str,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let (_, pr_metadata) = metadata::find_metadata_by_branch(branch_name)?
+        .ok_or_else(|| format!("No gitx metadata found for branch '{}'", branch_name))?;
+    let pr_number = pr_metadata.github_pr_number
+        .ok_or_else(|| format!("Branch '{}' has no GitHub PR", branch_name))?;
+    
+    github_client.set_draft(pr_number, false).await?;
+    
+    Ok(pr_number)
+}
+
+/// Mark the PR tracked by a branch as ready for review
+pub async fn mark_pr_ready(branch_name: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    // Create a GitHub client using factory (real in production, mock in tests)
+    let github_client = client_factory::create_github_client().await?;
+    mark_pr_ready_with_github_client(branch_name, &*github_client).await
+}
+
+/// Replace the subject line of a commit, keeping its body
+fn replace_subject(message: &str, subject: &str) -> String {
+    match message.split_once('\n') {
+        Some((_, rest)) => format!("{}\n{}", subject.trim(), rest),
+        None => subject.trim().to_string(),
+    }
+}
+
+/// Give a commit (HEAD by default) a new subject line, rewriting any commits above it
+///
+/// gitx metadata follows each rewritten commit. Returns the new ID of the reworded commit.
+pub fn reword_commit(sha: Option<&str>, subject: &str) -> Result<Oid, Box<dyn std::error::Error>> {
+    if subject.trim().is_empty() {
+        return Err("The new commit message can't be empty".into());
+    }
+    
+    let repo = open_repo()?;
+    let head = repo.head()?;
+    let head_oid = head.peel_to_commit()?.id();
+    let target = repo.revparse_single(sha.unwrap_or("HEAD"))?.peel_to_commit()?;
+    
+    if target.id() != head_oid && !repo.graph_descendant_of(head_oid, target.id())? {
+        return Err(format!("Commit {} is not part of the current branch", target.id()).into());
+    }
+    
+    // Commits above the target, oldest first; merges can't be replayed onto a single parent
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head_oid)?;
+    revwalk.hide(target.id())?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    let descendants = revwalk
+        .map(|oid| oid.and_then(|oid| repo.find_commit(oid)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if descendants.iter().any(|commit| commit.parent_count() != 1) {
+        return Err("Can't reword below a merge commit".into());
+    }
+    
+    let message = replace_subject(target.message().unwrap_or(""), subject);
+    let parents = target.parents().collect::<Vec<_>>();
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    let new_target = repo.commit(None, &target.author(), &target.committer(), &message, &target.tree()?, &parent_refs)?;
+    
+    let mut rewritten = vec![(target.id(), new_target)];
+    let mut tip = new_target;
+    for commit in &descendants {
+        let parent_id = commit.parent_id(0)?;
+        let new_parent_id = rewritten
+            .iter()
+            .find(|(old, _)| *old == parent_id)
+            .map(|(_, new)| *new)
+            .unwrap_or(parent_id);
+        let new_parent = repo.find_commit(new_parent_id)?;
+        tip = repo.commit(
+            None,
+            &commit.author(),
+            &commit.committer(),
+            commit.message().unwrap_or(""),
+            &commit.tree()?,
+            &[&new_parent],
+        )?;
+        rewritten.push((commit.id(), tip));
+    }
+    
+    // Trees are unchanged, so only the ref moves; the working tree and index stay as they are
+    if head.is_branch() {
+        let mut head = head;
+        head.set_target(tip, &format!("gitx reword: {}", subject.trim()))?;
+    } else {
+        repo.set_head_detached(tip)?;
+    }
+    
+    for (old, new) in &rewritten {
+        metadata::move_commit_metadata(old, new)?;
+    }
+    
+    Ok(new_target)
+}
+
+/// The commits `gitx reorder` can move: the top of the stack down to the first merge or root commit, newest first
+pub fn get_reorderable_commits() -> Result<Vec<StackCommit>, Box<dyn std::error::Error>> {
+    let repo = open_repo()?;
+    let mut commits = Vec::new();
+    for stack_commit in get_stack_commits()? {
+        if repo.find_commit(stack_commit.id)?.parent_count() != 1 {
+            break;
+        }
+        commits.push(stack_commit);
+    }
+    Ok(commits)
+}
+
+/// `(old, new)` IDs of the commits rewritten while restacking, bottom first
+pub type RewrittenCommits = Vec<(Oid, Oid)>;
+
+/// Cherry-pick `commits` (bottom first) one by one on top of `onto`
+///
+/// Commits already sitting on the right parent are kept as they are. Nothing is changed if one
+/// conflicts. Returns the new tip and the `(old, new)` ID of every rewritten commit.
+fn replay_commits<'repo>(
+    repo: &'repo Repository,
+    onto: git2::Commit<'repo>,
+    commits: &[Oid],
+) -> Result<(git2::Commit<'repo>, RewrittenCommits), Box<dyn std::error::Error>> {
+    let mut tip = onto;
+    let mut rewritten = Vec::new();
+    for &oid in commits {
+        let commit = repo.find_commit(oid)?;
+        if commit.parent_id(0)? == tip.id() {
+            tip = commit;
+            continue;
+        }
+        
+        let mut index = repo.cherrypick_commit(&commit, &tip, 0, None)?;
+        if index.has_conflicts() {
+            let paths = index
+                .conflicts()?
+                .filter_map(|conflict| conflict.ok())
+                .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+                .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+                .collect::<Vec<_>>();
+            return Err(format!(
+                "Commit {} conflicts in its new position ({}); the stack was left unchanged",
+                &oid.to_string()[..8],
+                paths.join(", ")
+            ).into());
+        }
+        
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+        let new_oid = repo.commit(None, &commit.author(), &commit.committer(), commit.message().unwrap_or(""), &tree, &[&tip])?;
+        rewritten.push((oid, new_oid));
+        tip = repo.find_commit(new_oid)?;
+    }
+    
+    Ok((tip, rewritten))
+}
+
+/// Move HEAD and the working tree to a rewritten stack and carry gitx metadata over to it
+///
+/// `checkout` comes from `guarded_checkout` (or `guarded_checkout_keeping_staged`), which has
+/// already checked the working tree holds nothing the rewrite would lose.
+fn finish_rewrite(
+    repo: &Repository,
+    mut checkout: git2::build::CheckoutBuilder,
+    tip: &git2::Commit,
+    rewritten: &[(Oid, Oid)],
+    log_message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    repo.checkout_tree(tip.as_object(), Some(checkout.force()))?;
+    let mut head = repo.head()?;
+    if head.is_branch() {
+        head.set_target(tip.id(), log_message)?;
+    } else {
+        repo.set_head_detached(tip.id())?;
+    }
+    
+    for (old, new) in rewritten {
+        metadata::move_commit_metadata(old, new)?;
+    }
+    Ok(())
+}
+
+/// The top `count` commits of HEAD, newest first, refusing merges and the root commit
+fn top_commits(repo: &Repository, count: usize) -> Result<Vec<git2::Commit<'_>>, Box<dyn std::error::Error>> {
+    let mut commits = vec![repo.head()?.peel_to_commit()?];
+    while commits.len() < count {
+        let parent = commits.last().unwrap().parent(0)?;
+        commits.push(parent);
+    }
+    if commits.iter().any(|commit| commit.parent_count() != 1) {
+        return Err("Can't rewrite merge commits or the root commit".into());
+    }
+    Ok(commits)
+}
+
+/// Rebuild the top of the branch so its commits come in `new_order` (bottom of the stack first)
+///
+/// `new_order` must list each of the top `new_order.len()` commits of HEAD exactly once. Commits
+/// are replayed with cherry-picks; if one conflicts nothing is changed and the conflicting files
+/// are reported. gitx metadata follows each rewritten commit. Returns the `(old, new)` ID of every
+/// commit that was rewritten, bottom first.
+pub fn reorder_commits(new_order: &[Oid]) -> Result<RewrittenCommits, Box<dyn std::error::Error>> {
+    let repo = open_repo()?;
+    let current = top_commits(&repo, new_order.len())?;
+    let mut expected: Vec<Oid> = current.iter().map(|commit| commit.id()).collect();
+    let mut requested = new_order.to_vec();
+    expected.sort();
+    requested.sort();
+    if expected != requested {
+        return Err(format!("The new order must list each of the top {} commits of the branch exactly once", new_order.len()).into());
+    }
+    
+    let checkout = guarded_checkout(&repo, false)?;
+    let (tip, rewritten) = replay_commits(&repo, current.last().unwrap().parent(0)?, new_order)?;
+    if tip.id() == current[0].id() {
+        return Ok(Vec::new());
+    }
+    
+    finish_rewrite(&repo, checkout, &tip, &rewritten, "gitx reorder")?;
+    Ok(rewritten)
+}
+
+/// Commit the staged changes (or nothing) directly on top of `after`, replaying the commits above it
+///
+/// `after` defaults to the commit below HEAD. Unstaged changes must be committed or stashed first.
+/// If the staged changes or a commit above conflict, nothing is changed. Returns the new commit's
+/// ID and the `(old, new)` ID of every commit that was rewritten, bottom first.
+pub fn insert_commit(after: Option<&str>, message: &str) -> Result<(Oid, RewrittenCommits), Box<dyn std::error::Error>> {
+    if message.trim().is_empty() {
+        return Err("The commit message can't be empty".into());
+    }
+    
+    let repo = open_repo()?;
+    let head_commit = repo.head()?.peel_to_commit()?;
+    let target = repo.revparse_single(after.unwrap_or("HEAD~1"))?.peel_to_commit()?;
+    if target.id() != head_commit.id() && !repo.graph_descendant_of(head_commit.id(), target.id())? {
+        return Err(format!("Commit {} is not part of the current branch", target.id()).into());
+    }
+    
+    // Commits above the target, newest first
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head_commit.id())?;
+    revwalk.hide(target.id())?;
+    let above_count = revwalk.count();
+    let above = if above_count == 0 { Vec::new() } else { top_commits(&repo, above_count)? };
+    if above.last().is_some_and(|commit| commit.parent_id(0).ok() != Some(target.id())) {
+        return Err("Can't insert below a merge commit".into());
+    }
+    
+    let checkout = guarded_checkout_keeping_staged(&repo)?;
+    
+    // Apply what is staged on top of HEAD to the target's tree instead
+    let staged = repo.diff_tree_to_index(Some(&head_commit.tree()?), None, None)?;
+    let mut index = repo.apply_to_tree(&target.tree()?, &staged, None)?;
+    if index.has_conflicts() {
+        return Err(format!("The staged changes don't apply on top of {}", &target.id().to_string()[..8]).into());
+    }
+    let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+    let signature = repo.signature()?;
+    let new_oid = repo.commit(None, &signature, &signature, message.trim(), &tree, &[&target])?;
+    
+    let replayed: Vec<Oid> = above.iter().rev().map(|commit| commit.id()).collect();
+    let (tip, rewritten) = replay_commits(&repo, repo.find_commit(new_oid)?, &replayed)?;
+    
+    // Whatever was staged is now part of the new commit, so the index can follow the new tip
+    finish_rewrite(&repo, checkout, &tip, &rewritten, &format!("gitx insert: {}", message.trim()))?;
+    Ok((new_oid, rewritten))
+}
+
+/// Remove a commit from the branch, replaying the commits above it onto its parent
+///
+/// If a commit above depends on the dropped one, its cherry-pick conflicts and nothing is
+/// changed. gitx metadata follows each rewritten commit. Returns the dropped commit's ID and the
+/// `(old, new)` ID of every commit that was rewritten, bottom first.
+pub fn drop_commit(sha: &str) -> Result<(Oid, RewrittenCommits), Box<dyn std::error::Error>> {
+    let repo = open_repo()?;
+    let target = repo.revparse_single(sha)?.peel_to_commit()?;
+    let head_oid = repo.head()?.peel_to_commit()?.id();
+    if target.id() != head_oid && !repo.graph_descendant_of(head_oid, target.id())? {
+        return Err(format!("Commit {} is not part of the current branch", target.id()).into());
+    }
+    
+    // Commits above the target, newest first, followed by the target itself
+    let mut above = Vec::new();
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head_oid)?;
+    revwalk.hide(target.id())?;
+    for oid in revwalk {
+        above.push(oid?);
+    }
+    let current = top_commits(&repo, above.len() + 1)?;
+    if current.last().map(|commit| commit.id()) != Some(target.id()) {
+        return Err("Can't drop a commit below a merge commit".into());
+    }
+    
+    let checkout = guarded_checkout(&repo, false)?;
+    let replayed: Vec<Oid> = current[..above.len()].iter().rev().map(|commit| commit.id()).collect();
+    let (tip, rewritten) = replay_commits(&repo, target.parent(0)?, &replayed)?;
+    
+    finish_rewrite(&repo, checkout, &tip, &rewritten, &format!("gitx drop: {}", target.summary().unwrap_or("")))?;
+    Ok((target.id(), rewritten))
+}
+
+/// Point each moved commit's PR at the branch now below it, with dependency injection for GitHub client
+///
+/// Returns the PRs whose base changed.
+pub async fn retarget_moved_prs_with_github_client(
+    commits: &[Oid],
+    github_client: &dyn GitHubClientTrait,
+) -> Result<Vec<u64>, Box<dyn std::error::Error>> {
+    let mut retargeted = Vec::new();
+    
+    for oid in commits {
+        let Some(pr_metadata) = metadata::get_commit_metadata(oid)? else {
+            continue;
+        };
+        let Some(pr_number) = pr_metadata.github_pr_number else {
+            continue;
+        };
+        let base = determine_base_branch_for_commit(oid)?;
+        if pr_metadata.base_branch.as_deref() == Some(base.as_str()) {
+            continue;
+        }
+        
+        github_client.set_base(pr_number, &base).await?;
+        metadata::update_commit_metadata(oid, &pr_metadata.with_base_branch(base))?;
+        retargeted.push(pr_number);
+    }
+    
+    Ok(retargeted)
+}
+
+/// Reword a commit and retitle its PR, with dependency injection for GitHub client
+///
+/// Returns the new commit ID and the PR that was retitled, if any. Without a client
+/// the PR is left alone and picks up the change on the next `gitx diff`.
+pub async fn reword_commit_with_github_client(
+    sha: Option<&str>,
+    subject: &str,
+    github_client: Option<&dyn GitHubClientTrait>,
+) -> Result<(Oid, Option<u64>), Box<dyn std::error::Error>> {
+    let new_oid = reword_commit(sha, subject)?;
+    
+    let pr_number = metadata::get_commit_metadata(&new_oid)?.and_then(|m| m.github_pr_number);
+    match (pr_number, github_client) {
+        (Some(pr_number), Some(client)) => {
+            client.update_pr(pr_number, Some(&pr_title_for_subject(subject)), None).await?;
+            Ok((new_oid, Some(pr_number)))
+        }
+        _ => Ok((new_oid, None)),
+    }
+}
+
+/// Reword a commit and retitle its PR when GitHub is available
+pub async fn reword_commit_and_pr(sha: Option<&str>, subject: &str) -> Result<(Oid, Option<u64>), Box<dyn std::error::Error>> {
+    if !github::check_github_token() {
+        return reword_commit_with_github_client(sha, subject, None).await;
+    }
+    
+    // Create a GitHub client using factory (real in production, mock in tests)
+    let github_client = client_factory::create_github_client().await?;
+    reword_commit_with_github_client(sha, subject, Some(&*github_client)).await
+}
+
+/// Close the PR tracked by a branch without merging, with dependency injection for GitHub client
+pub async fn close_pr_with_github_client(
+    branch_name: &str,
+    delete_branch: bool,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let (commit_oid, pr_metadata) = metadata::find_metadata_by_branch(branch_name)?
+        .ok_or_else(|| format!("No gitx metadata found for branch '{}'", branch_name))?;
+    let pr_number = pr_metadata.github_pr_number
+        .ok_or_else(|| format!("Branch '{}' has no GitHub PR", branch_name))?;
+    
+    github_client.close_pr(pr_number).await?;
+    
+    metadata::update_commit_metadata(&commit_oid, &pr_metadata.mark_cancelled())?;
+    
+    if delete_branch {
+        let repo = open_repo()?;
+        match repo.find_branch(branch_name, BranchType::Local) {
+            Ok(mut branch) => branch.delete()?,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => {
+                // Branch doesn't exist locally, that's expected with transient branches
+            }
+            Err(e) => return Err(Box::new(e)),
+        }
+        
+        delete_remote_branch(branch_name).await?;
+    }
+    
+    Ok(pr_number)
+}
+
+/// Close the PR tracked by a branch without merging
+pub async fn close_pr(branch_name: &str, delete_branch: bool) -> Result<u64, Box<dyn std::error::Error>> {
+    // Create a GitHub client using factory (real in production, mock in tests)
+    let github_client = client_factory::create_github_client().await?;
+    close_pr_with_github_client(branch_name, delete_branch, &*github_client).await
+}
+
+/// Where `gitx checkout` fetches a PR from and the local branch it lands on
+#[derive(Debug, PartialEq)]
+struct CheckoutTarget {
+    remote: String,
+    remote_ref: String,
+    local_branch: String,
+}
+
+/// Resolve a PR number (`123` or `#123`) or PR branch name to what should be fetched
+async fn resolve_checkout_target(
+    pr: &str,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<CheckoutTarget, Box<dyn std::error::Error>> {
+    if let Some(target) = resolve_known_checkout_target(pr)? {
+        return Ok(target);
+    }
+    
+    // Anyone else's branch may live on a fork, so fetch the PR ref GitHub keeps upstream
+    let pr_info = github_client.find_open_pr_for_branch(pr).await?
+        .ok_or_else(|| format!("No open PR found for branch '{}'", pr))?;
+    Ok(CheckoutTarget {
+        remote: config::get_github_upstream_remote(),
+        remote_ref: format!("refs/pull/{}/head", pr_info.number),
+        local_branch: pr.to_string(),
+    })
+}
+
+/// Resolve a PR number or a branch gitx tracks without asking GitHub; `None` for any other branch
+fn resolve_known_checkout_target(pr: &str) -> Result<Option<CheckoutTarget>, Box<dyn std::error::Error>> {
+    if let Ok(pr_number) = pr.trim_start_matches('#').parse::<u64>() {
+        // Keep the PR's own branch name when it is one of ours
+        let known_branch = metadata::list_all_pr_commits()?
+            .into_iter()
+            .find(|(_, pr_metadata)| pr_metadata.github_pr_number == Some(pr_number))
+            .map(|(_, pr_metadata)| pr_metadata.pr_branch_name);
+        return Ok(Some(CheckoutTarget {
+            remote: config::get_github_upstream_remote(),
+            remote_ref: format!("refs/pull/{}/head", pr_number),
+            local_branch: known_branch.unwrap_or_else(|| format!("pr-{}", pr_number)),
+        }));
+    }
+    
+    // Branches gitx created were pushed to our own remote
+    if metadata::find_metadata_by_branch(pr)?.is_some() {
+        return Ok(Some(CheckoutTarget {
+            remote: config::get_remote(),
+            remote_ref: format!("refs/heads/{}", pr),
+            local_branch: pr.to_string(),
+        }));
+    }
+    
+    Ok(None)
+}
+
+/// Point `branch_name` at `target`, creating it or fast-forwarding it, and check it out
+fn checkout_fetched_commit(
+    repo: &Repository,
+    branch_name: &str,
+    target: &git2::Commit,
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match repo.find_branch(branch_name, BranchType::Local) {
+        Ok(mut branch) => {
+            let current = branch.get().target().ok_or("Local branch has no target")?;
+            if current != target.id() {
+                if !repo.graph_descendant_of(target.id(), current)? {
+                    return Err(format!(
+                        "Local branch '{}' has diverged from the PR; rename or delete it first",
+                        branch_name
+                    ).into());
+                }
+                if branch.is_head() {
+                    // Update the working tree before moving the ref so the dirty check sees the old commit
+                    let mut checkout = guarded_checkout(repo, force)?;
+                    repo.checkout_tree(target.as_object(), Some(&mut checkout))?;
+                }
+                branch.get_mut().set_target(target.id(), "gitx checkout: fast-forward")?;
+            }
+        }
+        Err(e) if e.code() == git2::ErrorCode::NotFound => {
+            // Refuse before creating the branch, so a dirty tree doesn't leave it behind
+            guarded_checkout(repo, force)?;
+            repo.branch(branch_name, target, false)?;
+        }
+        Err(e) => return Err(Box::new(e)),
+    }
+    
+    switch_branch(branch_name, force)?;
+    Ok(())
+}
+
+/// Fetch a PR and check it out into a local branch, with dependency injection for GitHub client
+/// Returns the name of the checked-out branch
+pub async fn checkout_pr_with_github_client(
+    pr: &str,
+    force: bool,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let target = resolve_checkout_target(pr, github_client).await?;
+    checkout_target(target, force).await
+}
+
+/// Fetch what `target` points at and check it out into its local branch
+async fn checkout_target(target: CheckoutTarget, force: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let fetched_oid = fetch_ref(&target.remote, &target.remote_ref).await?;
+    
+    let repo = open_repo()?;
+    let fetched = repo.find_commit(fetched_oid)?;
+    checkout_fetched_commit(&repo, &target.local_branch, &fetched, force)?;
+    
+    Ok(target.local_branch)
+}
+
+/// Fetch a single ref from `remote` and return the commit it points at
+async fn fetch_ref(remote: &str, remote_ref: &str) -> Result<Oid, Box<dyn std::error::Error>> {
+    let output = tokio::process::Command::new("git")
+        .args(["fetch", remote, remote_ref])
+        .output()
+        .await?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to fetch {} from {}: {}", remote_ref, remote, error).into());
+    }
+    
+    let repo = open_repo()?;
+    let fetched = repo.revparse_single("FETCH_HEAD")?.peel_to_commit()?;
+    Ok(fetched.id())
+}
+
+/// Start tracking a PR that was opened outside gitx, with dependency injection for GitHub client
+/// The PR's head commit is annotated with its branch, number and base; returns that commit and its metadata
+pub async fn adopt_pr_with_github_client(
+    pr_number: u64,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<(Oid, metadata::CommitMetadata), Box<dyn std::error::Error>> {
+    let pr_status = github_client.get_pr_status(pr_number).await?;
+    
+    let local_head = {
+        let repo = open_repo()?;
+        Oid::from_str(&pr_status.head_sha).ok().filter(|oid| repo.find_commit(*oid).is_ok())
+    };
+    let head = match local_head {
+        Some(oid) => oid,
+        None => {
+            eprintln!(
+                "Warning: Head commit of PR #{} ({}) isn't in this repository; fetching it",
+                pr_number,
+                pr_status.head_branch
+            );
+            fetch_ref(&config::get_github_upstream_remote(), &format!("refs/pull/{}/head", pr_number)).await?
+        }
+    };
+    
+    if let Some(existing) = metadata::get_commit_metadata(&head)? {
+        return Err(format!("Commit {} already tracks {}", &head.to_string()[..7], existing.pr_branch_name).into());
+    }
+    let mut pr_metadata = metadata::CommitMetadata::new_branch_created(pr_status.head_branch, head.to_string())
+        .with_pr_number(pr_number);
+    if !pr_status.base_branch.is_empty() {
+        pr_metadata = pr_metadata.with_base_branch(pr_status.base_branch);
+    }
+    metadata::store_commit_metadata(&head, &pr_metadata)?;
+    
+    Ok((head, pr_metadata))
+}
+
+/// Start tracking a PR that was opened outside gitx
+pub async fn adopt_pr(pr_number: u64) -> Result<(Oid, metadata::CommitMetadata), Box<dyn std::error::Error>> {
+    // Create a GitHub client using factory (real in production, mock in tests)
+    let github_client = client_factory::create_github_client().await?;
+    adopt_pr_with_github_client(pr_number, &*github_client).await
+}
+
+/// Fetch a PR and check it out into a local branch
+pub async fn checkout_pr(pr: &str, force: bool) -> Result<String, Box<dyn std::error::Error>> {
+    // PR numbers and our own branches resolve without GitHub
+    if let Some(target) = resolve_known_checkout_target(pr)? {
+        return checkout_target(target, force).await;
+    }
+    
+    // Create a GitHub client using factory (real in production, mock in tests)
+    let github_client = client_factory::create_github_client().await?;
+    checkout_pr_with_github_client(pr, force, &*github_client).await
+}
+
+/// Retarget the PRs stacked on a merged branch onto the branch it merged into
+/// Returns the numbers of the retargeted PRs
+pub async fn retarget_dependent_prs_with_github_client(
+    merged_branch: &str,
+    new_base: &str,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<Vec<u64>, Box<dyn std::error::Error>> {
+    let mut retargeted = Vec::new();
+    
+    for (commit_oid, dependent) in metadata::find_dependent_prs(merged_branch)? {
+        let Some(pr_number) = dependent.github_pr_number else {
+            continue;
+        };
+        
+        github_client.set_base(pr_number, new_base).await?;
+        metadata::update_commit_metadata(&commit_oid, &dependent.with_base_branch(new_base.to_string()))?;
+        retargeted.push(pr_number);
+    }
+    
+    Ok(retargeted)
+}
+
+/// Land (cleanup) merged PRs by detecting merged status from GitHub and cleaning up local branches
+///
+/// With `keep_remote`, PR branches stay on the remote; only local state is cleaned up.
+///
+/// PRs closed without merging are listed separately and, with `all` or `include_closed`,
+/// cleaned up too and marked cancelled.
+pub async fn land_merged_prs(all: bool, dry_run: bool, keep_remote: bool, include_closed: bool) -> Result<(), Box<dyn std::error::Error>> {
+    // Check if GitHub token is available (a dry run can still preview from local metadata)
+    if !github::check_github_token() {
+        if dry_run {
+            return preview_land_offline(None, keep_remote);
+        }
+        if config::is_offline() {
+            return Err("Offline mode is on; landing needs GitHub to confirm which PRs merged.".into());
+        }
         return Err("GITHUB_TOKEN environment variable not set. Required to check PR merge status.".into());
     }
     
+    // Get GitHub client using factory (real in production, mock in tests)
+    let github_client = client_factory::create_github_client().await?;
+    land_merged_prs_with_github_client(all, dry_run, keep_remote, include_closed, &*github_client).await
+}
+
+/// Land merged PRs with dependency injection for GitHub client
+pub async fn land_merged_prs_with_github_client(
+    all: bool,
+    dry_run: bool,
+    keep_remote: bool,
+    include_closed: bool,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Get all PR metadata
     let pr_statuses = metadata::get_all_pr_status()
         .map_err(|e| e)?;
@@ -489,9 +1867,6 @@ pub async fn land_merged_prs(all: bool, dry_run: bool) -> Result<(), Box<dyn std
     
     println!("🔍 Checking PR statuses...");
     
-    // Get GitHub client using factory (real in production, mock in tests)
-    let github_client = client_factory::create_github_client().await?;
-    
     // Find PRs that have GitHub PR numbers
     let prs_to_check: Vec<_> = pr_statuses.iter()
         .filter_map(|pr| pr.pr_number.map(|num| (num, pr)))
@@ -515,62 +1890,134 @@ pub async fn land_merged_prs(all: bool, dry_run: bool) -> Result<(), Box<dyn std
         }
     }
     
+    // Confirm the changes actually reached the base branch before cleaning up
+    if !merged_prs.is_empty() {
+        let base = fetch_verification_base().await;
+        merged_prs.retain(|(github_status, pr_info)| {
+            let landed = Oid::from_str(&pr_info.commit_id)
+                .ok()
+                .and_then(|oid| metadata::get_commit_metadata(&oid).ok().flatten())
+                .and_then(|pr_metadata| is_effectively_merged(&pr_metadata, &base).ok());
+            
+            if landed == Some(false) && !all {
+                eprintln!(
+                    "  ⚠️  PR #{} is reported merged but its changes are not on {}; skipping (use --all to clean up anyway)",
+                    github_status.number, base
+                );
+                return false;
+            }
+            true
+        });
+    }
+    
+    // PRs closed without merging would otherwise stay tracked forever
+    let closed_prs: Vec<_> = github_statuses.iter()
+        .filter(|status| status.state == "closed")
+        .filter_map(|status| {
+            prs_to_check.iter()
+                .find(|(num, pr)| *num == status.number && pr.status != metadata::PRStatus::Cancelled)
+                .map(|(_, pr_info)| (status, *pr_info))
+        })
+        .collect();
+    let clean_closed = all || include_closed;
+    
     if merged_prs.is_empty() {
         println!("No merged PRs found ready for cleanup.");
-        if !all {
+        if !all && closed_prs.is_empty() {
             println!("Use --all to force cleanup of all tracked PRs (requires confirmation).");
         }
-        return Ok(());
+    } else {
+        println!("Found {} merged PR{} ready for cleanup:", 
+            merged_prs.len(), 
+            if merged_prs.len() == 1 { "" } else { "s" }
+        );
+        
+        for (github_status, _pr_info) in &merged_prs {
+            println!("  ✅ PR #{}: {} (merged)", github_status.number, github_status.title);
+        }
     }
     
-    println!("Found {} merged PR{} ready for cleanup:", 
-        merged_prs.len(), 
-        if merged_prs.len() == 1 { "" } else { "s" }
-    );
+    if !closed_prs.is_empty() {
+        println!("Found {} closed (not merged) PR{}:",
+            closed_prs.len(),
+            if closed_prs.len() == 1 { "" } else { "s" }
+        );
+        for (github_status, _pr_info) in &closed_prs {
+            println!("  ✖️  PR #{}: {} (closed)", github_status.number, github_status.title);
+        }
+        if !clean_closed {
+            println!("Use --include-closed to clean these up too.");
+        }
+    }
+    
+    // Each PR to clean up, with the status its metadata ends in
+    let to_clean: Vec<_> = merged_prs.iter()
+        .map(|(github_status, pr_info)| (*github_status, *pr_info, metadata::PRStatus::PRMerged))
+        .chain(closed_prs.iter()
+            .filter(|_| clean_closed)
+            .map(|(github_status, pr_info)| (*github_status, *pr_info, metadata::PRStatus::Cancelled)))
+        .collect();
     
-    for (github_status, _pr_info) in &merged_prs {
-        println!("  ✅ PR #{}: {} (merged)", github_status.number, github_status.title);
+    if to_clean.is_empty() {
+        return Ok(());
     }
     
     if dry_run {
         println!("\n🧪 DRY RUN - would perform these actions:");
-        println!("🧹 Cleaning up merged PRs:");
+        println!("🧹 Cleaning up PRs:");
         
-        for (_, pr_info) in &merged_prs {
-            println!("  🗑️  Would delete remote branch: {}", pr_info.branch_name);
-            println!("  📝 Would update metadata: mark PR as merged");
+        for (_, pr_info, final_status) in &to_clean {
+            print_remote_branch_action(&pr_info.branch_name, keep_remote, true);
+            println!("  📝 Would update metadata: mark PR as {}", final_status_label(final_status));
+            for (_, dependent) in metadata::find_dependent_prs(&pr_info.branch_name)? {
+                if let Some(pr_number) = dependent.github_pr_number {
+                    println!("  🎯 Would retarget PR #{} off {}", pr_number, pr_info.branch_name);
+                }
+            }
+            print_planned_retargets(pr_info)?;
         }
         
-        println!("  🔄 Would sync with origin/main");
+        if !merged_prs.is_empty() {
+            println!("  🔄 Would sync with {}/{}", config::get_remote(), config::get_base_branch());
+        }
         println!("\nTo actually perform cleanup, run without --dry-run");
         return Ok(());
     }
     
     // Perform actual cleanup
-    println!("\n🧹 Cleaning up merged PRs:");
+    println!("\n🧹 Cleaning up PRs:");
     let mut cleaned_up = 0;
+    let mut merged_cleaned_up = 0;
+    let progress = progress::Progress::new(to_clean.len());
     
-    for (github_status, pr_info) in &merged_prs {
-        match cleanup_merged_pr(pr_info, github_status.number).await {
+    for (step, (github_status, pr_info, final_status)) in to_clean.iter().enumerate() {
+        progress.start(step, "Landing PR", &github_status.title);
+        let keep_remote = !retarget_after_merge(pr_info, github_client).await || keep_remote;
+        match cleanup_finished_pr(pr_info, github_status.number, keep_remote, final_status.clone()).await {
             Ok(()) => {
-                println!("  🗑️  Deleted remote branch: {}", pr_info.branch_name);
-                println!("  📝 Updated metadata: marked PR #{} as merged", github_status.number);
+                print_remote_branch_action(&pr_info.branch_name, keep_remote, false);
+                progress::println(format!("  📝 Updated metadata: marked PR #{} as {}", github_status.number, final_status_label(final_status)));
                 cleaned_up += 1;
+                if *final_status == metadata::PRStatus::PRMerged {
+                    merged_cleaned_up += 1;
+                }
             }
             Err(e) => {
-                eprintln!("  ❌ Failed to cleanup PR #{}: {}", github_status.number, e);
+                progress::eprintln(format!("  ❌ Failed to cleanup PR #{}: {}", github_status.number, e));
             }
         }
+        progress.inc();
     }
+    progress.finish();
     
-    // Sync with origin/main
-    if cleaned_up > 0 {
+    // Sync with the base branch on the remote; closed PRs didn't change it
+    if merged_cleaned_up > 0 {
         match sync_with_origin_main().await {
-            Ok(()) => {
-                println!("  🔄 Synced with origin/main");
+            Ok(synced) => {
+                println!("  🔄 Synced with {}", synced);
             }
             Err(e) => {
-                eprintln!("  ⚠️  Warning: Failed to sync with origin/main: {}", e);
+                eprintln!("  ⚠️  Warning: Failed to sync with the base branch: {}", e);
             }
         }
     }
@@ -583,42 +2030,286 @@ pub async fn land_merged_prs(all: bool, dry_run: bool) -> Result<(), Box<dyn std
     Ok(())
 }
 
-/// Clean up a single merged PR: delete remote branch and update metadata
-async fn cleanup_merged_pr(
-    pr_info: &metadata::PRStatusInfo, 
-    _pr_number: u64
-) -> Result<(), Box<dyn std::error::Error>> {
-    let repo = Repository::open(".")
-        .map_err(|e| e)?;
+/// Fetch the base branch from the remote and return the ref merged PRs should be found on
+async fn fetch_verification_base() -> String {
+    let remote = config::get_remote();
+    let base_branch = config::get_base_branch();
     
-    // Delete the local branch if it exists (for backward compatibility with old workflow)
-    match repo.find_branch(&pr_info.branch_name, BranchType::Local) {
-        Ok(mut branch) => {
-            branch.delete()?;
-        }
-        Err(e) if e.code() == git2::ErrorCode::NotFound => {
-            // Branch doesn't exist locally, that's expected with transient branches
-        }
-        Err(e) => return Err(Box::new(e) as Box<dyn std::error::Error>),
+    let fetched = tokio::process::Command::new("git")
+        .args(["fetch", &remote, &base_branch])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success());
+    
+    let remote_base = format!("{}/{}", remote, base_branch);
+    let has_remote_base = open_repo()
+        .is_ok_and(|repo| repo.revparse_single(&remote_base).is_ok());
+    
+    if fetched || has_remote_base {
+        remote_base
+    } else {
+        base_branch
     }
+}
+
+/// Check whether a tracked PR's changes have landed on `base`, either by a merge or
+/// rebase that kept the commit, or by a squash merge that produced a commit with the
+/// same patch or tree
+pub fn is_effectively_merged(pr_metadata: &metadata::CommitMetadata, base: &str) -> Result<bool, git2::Error> {
+    let repo = open_repo()?;
+    let base_oid = repo.revparse_single(base)?.peel_to_commit()?.id();
     
-    // Delete the remote branch on GitHub
-    match delete_remote_branch(&pr_info.branch_name).await {
-        Ok(()) => {
-            // Remote branch deleted successfully
-        }
-        Err(e) => {
-            eprintln!("Warning: Failed to delete remote branch {}: {}", pr_info.branch_name, e);
-            // Continue with metadata cleanup even if remote deletion fails
-        }
+    let original = repo.find_commit(Oid::from_str(&pr_metadata.original_commit_id)?)?;
+    let head_id = pr_metadata.incremental_commits
+        .last()
+        .map(|commit| commit.commit_id.as_str())
+        .unwrap_or(&pr_metadata.original_commit_id);
+    let head = repo.find_commit(Oid::from_str(head_id)?)?;
+    
+    // Merge commit or fast-forward: the PR commit itself is on base
+    if head.id() == base_oid || repo.graph_descendant_of(base_oid, head.id())? {
+        return Ok(true);
     }
     
-    // Update metadata to mark as merged
-    let commit_oid = Oid::from_str(&pr_info.commit_id)?;
-    if let Some(mut metadata) = metadata::get_commit_metadata(&commit_oid)
-        .map_err(|e| e)? 
+    // Squash merge: look for the PR's combined change among commits made on base since it branched off
+    let start_tree = match original.parent_count() {
+        0 => None,
+        _ => Some(original.parent(0)?.tree()?),
+    };
+    let pr_patch_id = repo
+        .diff_tree_to_tree(start_tree.as_ref(), Some(&head.tree()?), None)?
+        .patchid(None)?;
+    
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(base_oid)?;
+    if original.parent_count() > 0
+        && let Ok(merge_base) = repo.merge_base(base_oid, original.parent_id(0)?)
+    {
+        revwalk.hide(merge_base)?;
+    }
+    
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.tree_id() == head.tree_id() {
+            return Ok(true);
+        }
+        
+        let parent_tree = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0)?.tree()?),
+        };
+        let patch_id = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?
+            .patchid(None)?;
+        if patch_id == pr_patch_id {
+            return Ok(true);
+        }
+    }
+    
+    Ok(false)
+}
+
+/// Land a single tracked PR by number, with dependency injection for GitHub client
+/// Unless `force` is set, the PR must be merged on GitHub
+pub async fn land_pr_with_github_client(
+    pr_number: u64,
+    force: bool,
+    dry_run: bool,
+    keep_remote: bool,
+    github_client: &dyn GitHubClientTrait,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pr_info = metadata::get_all_pr_status()?
+        .into_iter()
+        .find(|pr| pr.pr_number == Some(pr_number))
+        .ok_or_else(|| format!("PR #{} is not tracked by gitx", pr_number))?;
+    
+    let github_status = github_client.get_pr_status(pr_number).await?;
+    if github_status.state != "merged" && !force {
+        return Err(format!(
+            "PR #{} is not merged (state: {}). Use --force to clean it up anyway.",
+            pr_number, github_status.state
+        ).into());
+    }
+    
+    if dry_run {
+        println!("🧪 DRY RUN - would perform these actions:");
+        print_remote_branch_action(&pr_info.branch_name, keep_remote, true);
+        println!("  📝 Would update metadata: mark PR #{} as merged", pr_number);
+        print_planned_retargets(&pr_info)?;
+        println!("\nTo actually perform cleanup, run without --dry-run");
+        return Ok(());
+    }
+    
+    println!("🧹 Cleaning up PR #{}:", pr_number);
+    let keep_remote = !retarget_after_merge(&pr_info, github_client).await || keep_remote;
+    cleanup_finished_pr(&pr_info, pr_number, keep_remote, metadata::PRStatus::PRMerged).await?;
+    print_remote_branch_action(&pr_info.branch_name, keep_remote, false);
+    println!("  📝 Updated metadata: marked PR #{} as merged", pr_number);
+    
+    match sync_with_origin_main().await {
+        Ok(synced) => println!("  🔄 Synced with {}", synced),
+        Err(e) => eprintln!("  ⚠️  Warning: Failed to sync with the base branch: {}", e),
+    }
+    
+    Ok(())
+}
+
+/// Land a single tracked PR by number
+pub async fn land_pr(pr_number: u64, force: bool, dry_run: bool, keep_remote: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if !github::check_github_token() {
+        if dry_run {
+            return preview_land_offline(Some(pr_number), keep_remote);
+        }
+        if config::is_offline() {
+            return Err("Offline mode is on; landing needs GitHub to confirm which PRs merged.".into());
+        }
+        return Err("GITHUB_TOKEN environment variable not set. Required to check PR merge status.".into());
+    }
+    
+    // Get GitHub client using factory (real in production, mock in tests)
+    let github_client = client_factory::create_github_client().await?;
+    land_pr_with_github_client(pr_number, force, dry_run, keep_remote, &*github_client).await
+}
+
+/// Preview land cleanup from local metadata alone, for dry runs without a GitHub token
+fn preview_land_offline(pr_number: Option<u64>, keep_remote: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let candidates: Vec<_> = metadata::get_all_pr_status()?
+        .into_iter()
+        .filter(|pr| pr.pr_number.is_some())
+        .filter(|pr| pr_number.is_none_or(|number| pr.pr_number == Some(number)))
+        .filter(|pr| !matches!(pr.status, metadata::PRStatus::PRMerged | metadata::PRStatus::Cancelled))
+        .collect();
+    
+    if config::is_offline() {
+        println!("📴 Offline: PR states are unknown, previewing from local metadata only.");
+    } else {
+        println!("⚠️  No GitHub token configured; PR states are unknown, previewing from local metadata only.");
+    }
+    
+    if candidates.is_empty() {
+        println!("No tracked PRs to preview.");
+        return Ok(());
+    }
+    
+    println!("\n🧪 DRY RUN - if merged, would perform these actions:");
+    for pr_info in &candidates {
+        if let Some(number) = pr_info.pr_number {
+            let title = pr_info.commit_message.lines().next().unwrap_or("Untitled");
+            println!("  ❓ PR #{}: {} (state unknown)", number, title);
+        }
+        print_remote_branch_action(&pr_info.branch_name, keep_remote, true);
+        println!("  📝 Would update metadata: mark PR as merged");
+    }
+    
+    println!("\nConfigure a token with 'gitx init' to check which PRs are actually merged");
+    Ok(())
+}
+
+/// Branch the PRs stacked on a landed PR move to: the one it merged into
+fn base_after_merge(pr_info: &metadata::PRStatusInfo) -> String {
+    Oid::from_str(&pr_info.commit_id)
+        .ok()
+        .and_then(|oid| metadata::get_commit_metadata(&oid).ok().flatten())
+        .and_then(|merged| merged.base_branch)
+        .unwrap_or_else(config::get_base_branch)
+}
+
+/// At `--verbose`, print the retargeting requests landing `pr_info` would make
+fn print_planned_retargets(pr_info: &metadata::PRStatusInfo) -> Result<(), Box<dyn std::error::Error>> {
+    if config::verbosity() != config::Verbosity::Verbose {
+        return Ok(());
+    }
+    let repo = crate::github_utils::planned_repo();
+    let new_base = base_after_merge(pr_info);
+    for (_, dependent) in metadata::find_dependent_prs(&pr_info.branch_name)? {
+        if let Some(pr_number) = dependent.github_pr_number {
+            println!("  {}", crate::github_utils::PlannedRequest::update_pr(&repo, pr_number, vec![("base", new_base.clone())]));
+        }
+    }
+    Ok(())
+}
+
+/// Move PRs stacked on a just-merged PR onto the branch it merged into
+///
+/// Must run before the merged PR's remote branch is deleted: GitHub closes PRs whose base
+/// branch disappears. Returns false if any couldn't be moved, so the branch can be kept.
+async fn retarget_after_merge(pr_info: &metadata::PRStatusInfo, github_client: &dyn GitHubClientTrait) -> bool {
+    let new_base = base_after_merge(pr_info);
+    
+    match retarget_dependent_prs_with_github_client(&pr_info.branch_name, &new_base, github_client).await {
+        Ok(retargeted) => {
+            for pr_number in retargeted {
+                progress::println(format!("  🎯 Retargeted PR #{} onto {}", pr_number, new_base));
+            }
+            true
+        }
+        Err(e) => {
+            progress::eprintln(format!("  ⚠️  Warning: Failed to retarget PRs stacked on {}: {}", pr_info.branch_name, e));
+            progress::eprintln(format!("  ⚠️  Keeping remote branch {} so the PRs stacked on it stay open", pr_info.branch_name));
+            false
+        }
+    }
+}
+
+/// Report what land does (or would do, for a dry run) with a PR's remote branch
+fn print_remote_branch_action(branch_name: &str, keep_remote: bool, dry_run: bool) {
+    match (keep_remote, dry_run) {
+        (false, false) => progress::println(format!("  🗑️  Deleted remote branch: {}", branch_name)),
+        (false, true) => progress::println(format!("  🗑️  Would delete remote branch: {}", branch_name)),
+        (true, false) => progress::println(format!("  📌 Kept remote branch: {}", branch_name)),
+        (true, true) => progress::println(format!("  📌 Would keep remote branch: {}", branch_name)),
+    }
+}
+
+/// How land describes the status a cleaned-up PR ends in
+fn final_status_label(status: &metadata::PRStatus) -> &'static str {
+    match status {
+        metadata::PRStatus::Cancelled => "closed",
+        _ => "merged",
+    }
+}
+
+/// Clean up a merged or closed PR: delete its branches (the remote one unless `keep_remote`)
+/// and record `final_status` in its metadata
+async fn cleanup_finished_pr(
+    pr_info: &metadata::PRStatusInfo, 
+    _pr_number: u64,
+    keep_remote: bool,
+    final_status: metadata::PRStatus,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = open_repo()?;
+    
+    // Delete the local branch if it exists (for backward compatibility with old workflow)
+    match repo.find_branch(&pr_info.branch_name, BranchType::Local) {
+        Ok(mut branch) => {
+            branch.delete()?;
+        }
+        Err(e) if e.code() == git2::ErrorCode::NotFound => {
+            // Branch doesn't exist locally, that's expected with transient branches
+        }
+        Err(e) => return Err(Box::new(e) as Box<dyn std::error::Error>),
+    }
+    
+    // Delete the remote branch on GitHub, unless the team keeps merged branches around
+    if !keep_remote {
+        match delete_remote_branch(&pr_info.branch_name).await {
+            Ok(()) => {
+                // Remote branch deleted successfully
+            }
+            Err(e) => {
+                progress::eprintln(format!("Warning: Failed to delete remote branch {}: {}", pr_info.branch_name, e));
+                // Continue with metadata cleanup even if remote deletion fails
+            }
+        }
+    }
+    
+    // Update metadata to mark as merged (or cancelled)
+    let commit_oid = Oid::from_str(&pr_info.commit_id)?;
+    if let Some(mut metadata) = metadata::get_commit_metadata(&commit_oid)
+        .map_err(|e| e)? 
     {
-        metadata.status = metadata::PRStatus::PRMerged;
+        metadata.status = final_status;
         
         metadata::update_commit_metadata(&commit_oid, &metadata)
             .map_err(|e| e)?;
@@ -627,11 +2318,11 @@ async fn cleanup_merged_pr(
     Ok(())
 }
 
-/// Delete a remote branch from GitHub
+/// Delete a remote branch from the configured remote (`gitx.remote`)
 async fn delete_remote_branch(branch_name: &str) -> Result<(), Box<dyn std::error::Error>> {
     // Use git command to delete the remote branch
     let output = tokio::process::Command::new("git")
-        .args(&["push", "origin", "--delete", branch_name])
+        .args(&["push", &config::get_remote(), "--delete", branch_name])
         .output()
         .await?;
     
@@ -643,158 +2334,1657 @@ async fn delete_remote_branch(branch_name: &str) -> Result<(), Box<dyn std::erro
     Ok(())
 }
 
-/// Sync local main branch with origin/main
-async fn sync_with_origin_main() -> Result<(), Box<dyn std::error::Error>> {
-    // Use git command to pull latest changes
-    let output = tokio::process::Command::new("git")
-        .args(&["pull", "origin", "main"])
-        .output()
-        .await?;
-    
-    if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to sync with origin/main: {}", error).into());
+/// A metadata note that `gitx prune` removes, and why
+#[derive(Debug)]
+pub struct StaleMetadata {
+    pub commit_id: Oid,
+    pub metadata: metadata::CommitMetadata,
+    pub reason: &'static str,
+}
+
+/// Names of the branches on the configured remote
+async fn list_remote_branches() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let output = tokio::process::Command::new("git")
+        .args(["ls-remote", "--heads", &config::get_remote()])
+        .output()
+        .await?;
+    
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to list remote branches: {}", error).into());
+    }
+    
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .filter_map(|refname| refname.strip_prefix("refs/heads/"))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Check whether a PR was merged or closed, either as recorded by gitx or as GitHub last reported
+fn is_pr_finished(pr_metadata: &metadata::CommitMetadata) -> bool {
+    matches!(pr_metadata.status, metadata::PRStatus::PRMerged | metadata::PRStatus::Cancelled)
+        || pr_metadata
+            .last_known_status
+            .as_ref()
+            .is_some_and(|snapshot| matches!(snapshot.state.as_str(), "merged" | "closed"))
+}
+
+/// Remove metadata for commits that no longer exist, or whose open PR's branch is gone from the remote
+/// The remote is only checked when online. Returns what was (or, with `dry_run`, would be) removed
+pub async fn prune_metadata(dry_run: bool) -> Result<Vec<StaleMetadata>, Box<dyn std::error::Error>> {
+    let pr_commits = metadata::list_all_pr_commits()?;
+    
+    let remote_branches = if config::is_offline() {
+        None
+    } else {
+        match list_remote_branches().await {
+            Ok(branches) => Some(branches),
+            Err(e) => {
+                eprintln!("Warning: Not checking remote branches: {}", e);
+                None
+            }
+        }
+    };
+    
+    let repo = open_repo()?;
+    let mut stale = Vec::new();
+    for (commit_id, pr_metadata) in pr_commits {
+        let reason = if repo.find_commit(commit_id).is_err() {
+            "commit no longer exists"
+        } else if pr_metadata.github_pr_number.is_some()
+            && !is_pr_finished(&pr_metadata)
+            && remote_branches.as_ref().is_some_and(|branches| !branches.contains(&pr_metadata.pr_branch_name))
+        {
+            // Only open PRs are expected to keep their branch on the remote; landing deletes it
+            "branch is gone from the remote"
+        } else {
+            continue;
+        };
+        
+        if !dry_run {
+            metadata::remove_commit_metadata(&commit_id)?;
+        }
+        stale.push(StaleMetadata { commit_id, metadata: pr_metadata, reason });
+    }
+    
+    Ok(stale)
+}
+
+/// Tracking ref that fetched notes land in before being merged into the local notes ref
+pub fn remote_notes_ref(notes_ref: &str) -> String {
+    let name = notes_ref.strip_prefix("refs/notes/").unwrap_or(notes_ref);
+    format!("refs/notes/{}/{}", config::get_remote(), name)
+}
+
+/// Push the gitx metadata notes ref to the remote so the stack state can be shared
+pub async fn push_metadata_notes() -> Result<(), Box<dyn std::error::Error>> {
+    let notes_ref = metadata::notes_ref();
+    
+    let output = tokio::process::Command::new("git")
+        .args(["push", &config::get_remote(), &notes_ref])
+        .output()
+        .await?;
+    
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        if error.contains("non-fast-forward") || error.contains("fetch first") {
+            return Err("Remote metadata has changes you don't have. Run 'gitx fetch-notes' first, then push again.".into());
+        }
+        return Err(format!("Failed to push metadata notes: {}", error).into());
+    }
+    
+    Ok(())
+}
+
+/// Fetch the gitx metadata notes ref from the remote and merge it into the local notes
+pub async fn fetch_metadata_notes(strategy: metadata::NotesMergeStrategy) -> Result<(), Box<dyn std::error::Error>> {
+    let notes_ref = metadata::notes_ref();
+    let tracking_ref = remote_notes_ref(&notes_ref);
+    
+    // 1. Fetch into a tracking ref so local notes are never overwritten
+    let output = tokio::process::Command::new("git")
+        .args(["fetch", &config::get_remote(), &format!("+{}:{}", notes_ref, tracking_ref)])
+        .output()
+        .await?;
+    
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        if error.contains("couldn't find remote ref") {
+            return Err("Origin has no gitx metadata yet. Run 'gitx push-notes' from a machine that has it.".into());
+        }
+        return Err(format!("Failed to fetch metadata notes: {}", error).into());
+    }
+    
+    // 2. Work out field-level merges before git resolves the conflicts in our favour
+    let merged = match strategy {
+        metadata::NotesMergeStrategy::Merge => metadata::merge_notes(&tracking_ref)?,
+        _ => Vec::new(),
+    };
+    
+    // 3. Merge into the local notes (fast-forwards when the local notes haven't diverged)
+    let git_strategy = match strategy {
+        metadata::NotesMergeStrategy::Theirs => "theirs",
+        metadata::NotesMergeStrategy::Ours | metadata::NotesMergeStrategy::Merge => "ours",
+    };
+    let output = tokio::process::Command::new("git")
+        .args(["notes", &format!("--ref={}", notes_ref), "merge", "-s", git_strategy, &tracking_ref])
+        .output()
+        .await?;
+    
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to merge fetched metadata notes: {}", error).into());
+    }
+    
+    // 4. Record the field-level merges on top of the merge commit
+    for (commit_oid, merged_metadata) in &merged {
+        metadata::update_commit_metadata(commit_oid, merged_metadata)?;
+    }
+    if !merged.is_empty() {
+        println!("Merged diverging metadata for {} commit{}", merged.len(), if merged.len() == 1 { "" } else { "s" });
+    }
+    
+    Ok(())
+}
+
+/// Bring the current branch up to date with the base branch on the remote, returning
+/// the ref it synced with (e.g. `origin/main`)
+///
+/// With `gitx.land.rebase` (the default) this only fast-forwards, so no merge commits are
+/// made; if the branch has diverged it warns and falls back to `git pull`.
+async fn sync_with_origin_main() -> Result<String, Box<dyn std::error::Error>> {
+    let remote = config::get_remote();
+    let base = config::get_base_branch();
+    let remote_base = format!("{}/{}", remote, base);
+    
+    if config::is_land_rebase_enabled() {
+        run_git_for_sync(&["fetch", &remote, &base]).await?;
+        if run_git_for_sync(&["merge", "--ff-only", &remote_base]).await.is_ok() {
+            return Ok(remote_base);
+        }
+        eprintln!("  ⚠️  Warning: Can't fast-forward to {}; falling back to git pull", remote_base);
+    }
+    
+    run_git_for_sync(&["pull", &remote, &base]).await?;
+    Ok(remote_base)
+}
+
+/// Run a git command for `sync_with_origin_main`, turning a failure into its stderr
+async fn run_git_for_sync(args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        .output()
+        .await?;
+    
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args[0], error.trim()).into());
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use git2::{Repository, Signature};
+
+    fn create_test_repo() -> Result<(Repository, tempfile::TempDir), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir()?;
+        let repo = Repository::init(&temp_dir)?;
+        
+        // Configure user for commits
+        let mut config = repo.config()?;
+        config.set_str("user.name", "Test User")?;
+        config.set_str("user.email", "test@example.com")?;
+        
+        // Create initial commit
+        let signature = Signature::now("Test User", "test@example.com")?;
+        let tree_id = {
+            let mut index = repo.index()?;
+            // Create a test file
+            let test_file_path = temp_dir.path().join("test.txt");
+            fs::write(&test_file_path, "test content")?;
+            index.add_path(Path::new("test.txt"))?;
+            index.write()?;
+            index.write_tree()?
+        };
+        let tree = repo.find_tree(tree_id)?;
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )?;
+        drop(tree); // Explicitly drop the tree to release the borrow
+        
+        Ok((repo, temp_dir))
+    }
+
+    #[test]
+    fn test_get_all_branches_with_single_branch() {
+        let (_repo, _temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        // Change to the test repo directory
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(_temp_dir.path()).unwrap();
+        
+        let branches = get_all_branches().expect("Failed to get branches");
+        
+        // Should have at least the main/master branch
+        assert!(!branches.is_empty());
+        assert!(branches.contains(&"main".to_string()) || branches.contains(&"master".to_string()));
+        
+        // Restore original directory
+        std::env::set_current_dir(original_dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_all_branches_basic() {
+        let (_repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        // Change to the test repo directory
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let branches = get_all_branches().expect("Failed to get branches");
+        
+        // Should have at least one branch (master/main)
+        assert!(!branches.is_empty());
+        
+        // Restore original directory
+        std::env::set_current_dir(original_dir).unwrap();
+    }
+
+    #[test]
+    fn test_switch_to_nonexistent_branch() {
+        let (_repo, _temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        // Change to the test repo directory
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(_temp_dir.path()).unwrap();
+        
+        // Try to switch to a branch that doesn't exist
+        let result = switch_branch("nonexistent-branch", false);
+        assert!(result.is_err());
+        
+        // Restore original directory
+        std::env::set_current_dir(original_dir).unwrap();
+    }
+
+    /// Create a `feature` branch whose test.txt differs from the current branch
+    fn create_feature_branch(repo: &Repository) {
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let blob = repo.blob(b"feature content").unwrap();
+        let mut builder = repo.treebuilder(Some(&head.tree().unwrap())).unwrap();
+        builder.insert("test.txt", blob, 0o100644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        let feature_commit = repo.commit(None, &signature, &signature, "Feature", &tree, &[&head]).unwrap();
+        repo.branch("feature", &repo.find_commit(feature_commit).unwrap(), false).unwrap();
+    }
+
+    #[test]
+    fn test_switch_branch_refuses_dirty_tree() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        create_feature_branch(&repo);
+        fs::write(temp_dir.path().join("test.txt"), "local edits").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let result = switch_branch("feature", false);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let err = result.expect_err("Switching with a dirty tree should fail");
+        assert!(err.message().contains("uncommitted changes"));
+        assert_ne!(repo.head().unwrap().shorthand(), Some("feature"));
+        assert_eq!(fs::read_to_string(temp_dir.path().join("test.txt")).unwrap(), "local edits");
+    }
+
+    #[test]
+    fn test_switch_branch_force_discards_changes() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        create_feature_branch(&repo);
+        fs::write(temp_dir.path().join("test.txt"), "local edits").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let result = switch_branch("feature", true);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        result.expect("Forced switch should succeed");
+        assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+        assert_eq!(fs::read_to_string(temp_dir.path().join("test.txt")).unwrap(), "feature content");
+    }
+
+    /// Commit `content` to `filename` on top of `parent` without moving any branch
+    fn commit_file(repo: &Repository, parent: &git2::Commit, filename: &str, content: &str, message: &str) -> Oid {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        let mut builder = repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+        builder.insert(filename, blob, 0o100644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        repo.commit(None, &signature, &signature, message, &tree, &[parent]).unwrap()
+    }
+
+    #[test]
+    fn test_is_effectively_merged_detects_squash_merge() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        
+        // The PR commit lives off to the side, as with transient PR branches
+        let pr_commit = commit_file(&repo, &root, "test.txt", "feature content", "Add feature");
+        let pr_metadata = metadata::CommitMetadata::new_branch_created(
+            "gitx/test/add-feature".to_string(),
+            pr_commit.to_string()
+        ).with_pr_number(1);
+        
+        // Base moves on with an unrelated commit...
+        let unrelated = commit_file(&repo, &root, "other.txt", "other content", "Unrelated change");
+        let unrelated = repo.find_commit(unrelated).unwrap();
+        repo.branch("before-squash", &unrelated, false).unwrap();
+        
+        // ...then GitHub squashes the PR into a commit with a new SHA and message
+        let squash = commit_file(&repo, &unrelated, "test.txt", "feature content", "Add feature (#1)");
+        repo.branch("after-squash", &repo.find_commit(squash).unwrap(), false).unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let before = is_effectively_merged(&pr_metadata, "before-squash");
+        let after = is_effectively_merged(&pr_metadata, "after-squash");
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(!before.expect("Failed to check merge state"));
+        assert!(after.expect("Failed to check merge state"));
+    }
+
+    #[test]
+    fn test_is_effectively_merged_detects_merged_commit() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let pr_commit = commit_file(&repo, &root, "test.txt", "feature content", "Add feature");
+        repo.branch("landed", &repo.find_commit(pr_commit).unwrap(), false).unwrap();
+        let pr_metadata = metadata::CommitMetadata::new_branch_created(
+            "gitx/test/add-feature".to_string(),
+            pr_commit.to_string()
+        );
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let result = is_effectively_merged(&pr_metadata, "landed");
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(result.expect("Failed to check merge state"));
+    }
+
+    #[test]
+    fn test_create_branch() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        create_feature_branch(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let from_head = create_branch("from-head", None);
+        let from_feature = create_branch("from-feature", Some("feature"));
+        let invalid = create_branch("bad..name", None);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        from_head.expect("Failed to create branch at HEAD");
+        from_feature.expect("Failed to create branch from feature");
+        assert!(invalid.is_err());
+        
+        let head_id = repo.head().unwrap().target().unwrap();
+        let feature_id = repo.refname_to_id("refs/heads/feature").unwrap();
+        assert_eq!(repo.refname_to_id("refs/heads/from-head").unwrap(), head_id);
+        assert_eq!(repo.refname_to_id("refs/heads/from-feature").unwrap(), feature_id);
+    }
+
+    #[test]
+    fn test_create_branch_rejects_existing_name() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        create_feature_branch(&repo);
+        let feature_id = repo.refname_to_id("refs/heads/feature").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let result = create_branch("feature", None);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let err = result.expect_err("Creating a duplicate branch should fail");
+        assert!(err.message().contains("already exists"));
+        assert_eq!(repo.refname_to_id("refs/heads/feature").unwrap(), feature_id);
+    }
+
+    #[test]
+    fn test_delete_branch() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        create_feature_branch(&repo);
+        let current = repo.head().unwrap().shorthand().unwrap().to_string();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let deleted = delete_branch("feature");
+        let current_result = delete_branch(&current);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        deleted.expect("Failed to delete branch");
+        assert!(repo.find_branch("feature", BranchType::Local).is_err());
+        assert!(current_result.is_err());
+        assert!(repo.find_branch(&current, BranchType::Local).is_ok());
+    }
+
+    #[test]
+    fn test_get_commits_needing_processing_latest_only() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        // Change to the test repo directory
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Add multiple commits
+        let signature = repo.signature().unwrap();
+        for i in 1..=3 {
+            let content = format!("Feature {}", i);
+            let test_file_path = temp_dir.path().join("features.txt");
+            let existing = std::fs::read_to_string(&test_file_path).unwrap_or_default();
+            std::fs::write(&test_file_path, format!("{}{}\n", existing, content)).unwrap();
+            
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("features.txt")).unwrap();
+            index.write().unwrap();
+            
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &format!("Add feature {}", i),
+                &tree,
+                &[&parent],
+            ).unwrap();
+        }
+        
+        // Test latest only
+        let updates = get_latest_commit_needing_processing(false).expect("Failed to get latest commits");
+        assert_eq!(updates.len(), 1);
+        
+        // Test all commits
+        let updates = get_commits_needing_processing(false).expect("Failed to get all commits");
+        assert!(updates.len() >= 3);
+        
+        // Restore original directory
+        std::env::set_current_dir(original_dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_commits_needing_processing_respects_limit() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        repo.config().unwrap().set_str("gitx.diff.limit", "2").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        config::invalidate_cache();
+        
+        let mut parent = repo.head().unwrap().peel_to_commit().unwrap();
+        for i in 1..=5 {
+            let oid = commit_file(&repo, &parent, &format!("feature{}.txt", i), "content", &format!("Add feature {}", i));
+            parent = repo.find_commit(oid).unwrap();
+        }
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&head_ref, parent.id(), true, "Build stack").unwrap();
+        
+        let updates = get_commits_needing_processing(false).expect("Failed to get commits");
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        config::invalidate_cache();
+        
+        assert_eq!(updates.len(), 2);
+        match &updates[0] {
+            CommitUpdateType::NewCommit(info) => assert_eq!(info.message, "Add feature 5"),
+            other => panic!("Expected a new commit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_get_commits_needing_processing_rejects_merge_commits() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        config::invalidate_cache();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let ours = repo.find_commit(commit_file(&repo, &root, "ours.txt", "content", "Add ours")).unwrap();
+        let theirs = repo.find_commit(commit_file(&repo, &root, "theirs.txt", "content", "Add theirs")).unwrap();
+        let signature = repo.signature().unwrap();
+        let merge = repo.commit(None, &signature, &signature, "Merge theirs", &theirs.tree().unwrap(), &[&ours, &theirs]).unwrap();
+        let merge = repo.find_commit(merge).unwrap();
+        let top = commit_file(&repo, &merge, "top.txt", "content", "Add top");
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&head_ref, top, true, "Build stack").unwrap();
+        
+        let result = get_commits_needing_processing(false);
+        let single = get_commit_needing_processing(&merge.id().to_string(), false);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        config::invalidate_cache();
+        
+        let error = result.expect_err("Merge commits should be rejected").to_string();
+        assert!(error.contains("gitx expects a linear stack; found a merge commit"), "unexpected error: {}", error);
+        assert!(error.contains("Merge theirs"));
+        assert!(single.is_err());
+    }
+
+    #[test]
+    fn test_get_commits_needing_processing_allow_merges_skips_merge_commits() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        config::invalidate_cache();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let ours = repo.find_commit(commit_file(&repo, &root, "ours.txt", "content", "Add ours")).unwrap();
+        let theirs = repo.find_commit(commit_file(&repo, &root, "theirs.txt", "content", "Add theirs")).unwrap();
+        let signature = repo.signature().unwrap();
+        let merge = repo.commit(None, &signature, &signature, "Merge theirs", &theirs.tree().unwrap(), &[&ours, &theirs]).unwrap();
+        let merge = repo.find_commit(merge).unwrap();
+        let top = commit_file(&repo, &merge, "top.txt", "content", "Add top");
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&head_ref, top, true, "Build stack").unwrap();
+        
+        let result = get_commits_needing_processing(true);
+        let single = get_commit_needing_processing(&merge.id().to_string(), true);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        config::invalidate_cache();
+        
+        // The merge is stepped over, and so is the side branch it brought in
+        let messages: Vec<String> = result
+            .expect("Merge commits should be skipped")
+            .iter()
+            .map(|update| match update {
+                CommitUpdateType::NewCommit(commit_info) => commit_info.message.clone(),
+                CommitUpdateType::IncrementalUpdate { message, .. } => message.clone(),
+            })
+            .collect();
+        assert_eq!(messages, vec!["Add top".to_string(), "Add ours".to_string(), "Initial commit".to_string()]);
+        assert!(single.expect("Diffing the merge itself should skip it").is_empty());
+    }
+
+    #[test]
+    fn test_get_commits_needing_processing_skips_marked_commits() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        config::invalidate_cache();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let below = commit_file(&repo, &root, "below.txt", "content", "Add feature below");
+        let below = repo.find_commit(below).unwrap();
+        let scratch = commit_file(&repo, &below, "scratch.txt", "content", "Scratch work [skip gitx]");
+        let scratch = repo.find_commit(scratch).unwrap();
+        let above = commit_file(&repo, &scratch, "above.txt", "content", "Add feature above");
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&head_ref, above, true, "Build stack").unwrap();
+        
+        let updates = get_commits_needing_processing(false).expect("Failed to get commits");
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        config::invalidate_cache();
+        
+        let messages: Vec<&str> = updates
+            .iter()
+            .filter_map(|update| match update {
+                CommitUpdateType::NewCommit(info) => Some(info.message.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(messages, vec!["Add feature above", "Add feature below", "Initial commit"]);
+    }
+
+    /// Move the branch HEAD points at to `oid`, returning the branch name
+    fn advance_head_branch(repo: &Repository, oid: Oid) -> String {
+        let head = repo.head().unwrap();
+        let name = head.shorthand().unwrap().to_string();
+        repo.reference(head.name().unwrap(), oid, true, "advance").unwrap();
+        name
+    }
+
+    #[test]
+    fn test_find_merge_conflicts_lists_conflicting_files() {
+        let (repo, _temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        
+        // The base moves on and edits the same file as the commit
+        let mine = commit_file(&repo, &root, "test.txt", "my change", "Change test file");
+        let clean = commit_file(&repo, &root, "other.txt", "other", "Add other file");
+        let theirs = commit_file(&repo, &root, "test.txt", "their change", "Change test file upstream");
+        let base = advance_head_branch(&repo, theirs);
+        
+        let conflicts = find_merge_conflicts(&repo, &mine, &base).unwrap();
+        assert_eq!(conflicts, vec!["test.txt".to_string()]);
+        assert!(find_merge_conflicts(&repo, &clean, &base).unwrap().is_empty());
+        assert!(find_merge_conflicts(&repo, &mine, "missing-branch").unwrap().is_empty());
+        
+        let warning = format_conflict_warning(&base, &conflicts);
+        assert!(warning.contains(&format!("conflicts with '{}'", base)));
+        assert!(warning.contains("  - test.txt"));
+    }
+
+    #[test]
+    fn test_incremental_update_distinguishes_amended_and_additional_commits() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let pushed = commit_file(&repo, &root, "feature.txt", "v1", "Add feature");
+        let pr_metadata = metadata::CommitMetadata::new_branch_created("gitx/test/add-feature".to_string(), pushed.to_string())
+            .with_pr_number(1);
+        metadata::store_commit_metadata(&pushed, &pr_metadata).unwrap();
+        
+        // Amending replaces the pushed commit: same parent, new oid, and notes.rewriteRef copies its note
+        let amended = commit_file(&repo, &root, "feature.txt", "v2", "Add feature");
+        metadata::store_commit_metadata(&amended, &pr_metadata).unwrap();
+        // A fixup made on top of the pushed commit has no note of its own
+        let additional = commit_file(&repo, &repo.find_commit(pushed).unwrap(), "feature.txt", "v3", "fixup! Add feature");
+        // Any other commit on top starts a stacked PR
+        let stacked = commit_file(&repo, &repo.find_commit(pushed).unwrap(), "other.txt", "other", "Add other feature");
+        
+        advance_head_branch(&repo, amended);
+        let amended_updates = get_commit_needing_processing(&amended.to_string(), false);
+        advance_head_branch(&repo, additional);
+        let additional_updates = get_commit_needing_processing(&additional.to_string(), false);
+        // Once added to the PR, the fixup isn't offered again
+        metadata::update_commit_metadata(&pushed, &pr_metadata.clone().add_incremental_commit(
+            additional.to_string(),
+            "fixup! Add feature".to_string(),
+            metadata::IncrementalCommitType::AdditionalCommit,
+        )).unwrap();
+        let recorded_updates = get_commit_needing_processing(&additional.to_string(), false);
+        advance_head_branch(&repo, stacked);
+        let stacked_updates = get_commit_needing_processing(&stacked.to_string(), false);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        match amended_updates.unwrap().as_slice() {
+            [CommitUpdateType::IncrementalUpdate { commit_type, .. }] => assert_eq!(*commit_type, metadata::IncrementalCommitType::AmendedCommit),
+            other => panic!("Expected one incremental update, got {:?}", other),
+        }
+        match additional_updates.unwrap().as_slice() {
+            [CommitUpdateType::IncrementalUpdate { original_oid, updated_oid, commit_type, .. }] => {
+                assert_eq!(*commit_type, metadata::IncrementalCommitType::AdditionalCommit);
+                assert_eq!(*original_oid, pushed);
+                assert_eq!(*updated_oid, additional);
+            }
+            other => panic!("Expected one incremental update, got {:?}", other),
+        }
+        assert!(recorded_updates.unwrap().is_empty());
+        assert!(matches!(stacked_updates.unwrap().as_slice(), [CommitUpdateType::NewCommit(_)]));
+    }
+
+    #[test]
+    fn test_get_commits_needing_processing_since() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let second = commit_file(&repo, &root, "second.txt", "second", "Add second");
+        let third = commit_file(&repo, &repo.find_commit(second).unwrap(), "third.txt", "third", "Add third");
+        let fourth = commit_file(&repo, &repo.find_commit(third).unwrap(), "fourth.txt", "fourth", "Add fourth");
+        advance_head_branch(&repo, fourth);
+        
+        let result = get_commits_needing_processing_since(&second.to_string(), false);
+        let missing = get_commits_needing_processing_since("no-such-ref", false);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let ids: Vec<Oid> = result.expect("Since ref should resolve").iter().map(|update| match update {
+            CommitUpdateType::NewCommit(commit_info) => commit_info.id,
+            CommitUpdateType::IncrementalUpdate { updated_oid, .. } => *updated_oid,
+        }).collect();
+        assert_eq!(ids, vec![fourth, third]);
+        assert!(missing.unwrap_err().message().contains("'no-such-ref'"));
+    }
+
+    #[test]
+    fn test_get_commit_needing_processing_by_short_sha() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let bottom = commit_file(&repo, &root, "bottom.txt", "bottom", "Add bottom");
+        let middle = commit_file(&repo, &repo.find_commit(bottom).unwrap(), "middle.txt", "middle", "Add middle");
+        let top = commit_file(&repo, &repo.find_commit(middle).unwrap(), "top.txt", "top", "Add top");
+        advance_head_branch(&repo, top);
+        let unreachable = commit_file(&repo, &root, "side.txt", "side", "Side commit");
+        
+        let result = get_commit_needing_processing(&middle.to_string()[..7], false);
+        let unreachable_result = get_commit_needing_processing(&unreachable.to_string(), false);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let updates = result.expect("Short sha should resolve");
+        assert_eq!(updates.len(), 1);
+        match &updates[0] {
+            CommitUpdateType::NewCommit(commit_info) => {
+                assert_eq!(commit_info.id, middle);
+                assert_eq!(commit_info.message, "Add middle");
+            }
+            other => panic!("Expected a new commit, got {:?}", other),
+        }
+        assert!(unreachable_result.unwrap_err().message().contains("not reachable from HEAD"));
+    }
+
+    #[tokio::test]
+    async fn test_grouped_commits_share_one_pr() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let first = commit_file(&repo, &root, "api.txt", "api", "Add search API\n\nGitx-Group: search");
+        let second = commit_file(&repo, &repo.find_commit(first).unwrap(), "ui.txt", "ui", "Add search UI\n\nGitx-Group: search");
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&head_ref, second, true, "Add search").unwrap();
+        
+        let updates = get_commits_needing_processing_since(&root.id().to_string(), false).unwrap();
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let result = match updates.as_slice() {
+            [CommitUpdateType::NewCommit(commit_info)] => {
+                create_transient_pr_branch_with_github_client(commit_info, &github::PRCreateOptions::default(), &mock).await
+            }
+            other => panic!("Expected one grouped commit, got {:?}", other),
+        };
+        let stored = metadata::get_commit_metadata(&second).unwrap();
+        let remaining = get_commits_needing_processing_since(&root.id().to_string(), false).unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let pr = result.expect("PR creation should succeed").expect("PR should be created");
+        let stored = stored.expect("Top commit should carry the PR metadata");
+        assert_eq!(mock.get_created_prs().len(), 1);
+        assert_eq!(pr.title, "Add search API");
+        assert!(stored.pr_branch_name.ends_with("/add-search-api"));
+        let body = mock.get_pr_body(&stored.pr_branch_name).unwrap();
+        assert!(body.contains(&format!("## Commits\n\n- {} Add search API\n- {} Add search UI\n", &first.to_string()[..8], &second.to_string()[..8])));
+        assert!(!body.contains("Gitx-Group"));
+        assert_eq!(stored.commits, vec![first.to_string(), second.to_string()]);
+        assert!(remaining.is_empty(), "Grouped commits shouldn't be offered again: {:?}", remaining);
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_with_explicit_base() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("develop", &root, false).unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let missing = github::PRCreateOptions { base: Some("release".to_string()), ..Default::default() };
+        let missing_result = create_transient_pr_branch_with_github_client(&commit_info, &missing, &mock).await;
+        let pr_options = github::PRCreateOptions { base: Some("develop".to_string()), ..Default::default() };
+        let result = create_transient_pr_branch_with_github_client(&commit_info, &pr_options, &mock).await;
+        let stored = metadata::get_commit_metadata(&oid).unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(missing_result.expect_err("Unknown base should be rejected").to_string().contains("'release'"));
+        let pr = result.expect("PR creation should succeed").expect("PR should be created");
+        assert_eq!(mock.get_pr_base(pr.number), Some("develop".to_string()));
+        assert_eq!(stored.unwrap().base_branch, Some("develop".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_skips_conflicting_commit() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let mine = commit_file(&repo, &root, "test.txt", "my change", "Change test file");
+        let theirs = commit_file(&repo, &root, "test.txt", "their change", "Change test file upstream");
+        advance_head_branch(&repo, theirs);
+        
+        let commit_info = CommitInfo {
+            id: mine,
+            message: "Change test file".to_string(),
+            potential_branch_name: "gitx/test/change-test-file".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let pr_options = github::PRCreateOptions { skip_conflicts: true, ..Default::default() };
+        let result = create_transient_pr_branch_with_github_client(&commit_info, &pr_options, &mock).await;
+        let stored = metadata::get_commit_metadata(&mine).unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let error = result.expect_err("Conflicting commit should be skipped").to_string();
+        assert!(error.contains("test.txt"));
+        assert!(error.contains("--skip-conflicts"));
+        assert!(mock.get_created_prs().is_empty());
+        assert!(stored.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_auto_labels_conventional_commit() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        repo.config().unwrap().set_bool("gitx.pr.autoLabel", true).unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "search.txt", "search", "feat(search): Add search");
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "feat(search): Add search".to_string(),
+            potential_branch_name: "gitx/test/add-search".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let options = github::PRCreateOptions { labels: vec!["team-a".to_string()], ..Default::default() };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let result = create_transient_pr_branch_with_github_client(&commit_info, &options, &mock).await;
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let pr = result.expect("PR creation should succeed").expect("PR should be created");
+        assert_eq!(mock.get_pr_labels(pr.number), vec!["team-a", "enhancement"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_requests_configured_and_passed_reviewers() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        repo.config().unwrap().set_str("gitx.pr.reviewers", "alice, bob").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        config::invalidate_cache();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        // As `gitx diff --reviewer bob --reviewer carol` builds them
+        let pr_options = github::PRCreateOptions {
+            reviewers: vec!["bob".to_string(), "carol".to_string()],
+            ..Default::default()
+        }
+        .with_default_reviewers();
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let result = create_transient_pr_branch_with_github_client(&commit_info, &pr_options, &mock).await;
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        config::invalidate_cache();
+        
+        let pr = result.expect("PR creation should succeed").expect("PR should be created");
+        assert_eq!(mock.get_requested_reviewers(pr.number), vec!["alice", "bob", "carol"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_requests_codeowners() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        repo.config().unwrap().set_bool("gitx.pr.codeowners", true).unwrap();
+        fs::create_dir(temp_dir.path().join(".github")).unwrap();
+        fs::write(
+            temp_dir.path().join(".github/CODEOWNERS"),
+            "* @acme/core\n*.rs @octo @mock-user @acme/rust\n",
+        ).unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "routes.rs", "fn routes() {}", "Add routes");
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add routes".to_string(),
+            potential_branch_name: "gitx/test/add-routes".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let result = create_transient_pr_branch_with_github_client(&commit_info, &github::PRCreateOptions::default(), &mock).await;
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let pr = result.expect("PR creation should succeed").expect("PR should be created");
+        // `*.rs` is the last match, and the PR's author (mock-user) can't review their own PR
+        assert_eq!(mock.get_requested_reviewers(pr.number), vec!["octo"]);
+        assert_eq!(mock.get_requested_team_reviewers(pr.number), vec!["acme/rust"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_from_fork_namespaces_head() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        repo.config().unwrap().set_str("gitx.github.fork", "forker").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/forker/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let result = create_transient_pr_branch_with_github_client(&commit_info, &github::PRCreateOptions::default(), &mock).await;
+        let stored = metadata::get_commit_metadata(&oid).unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let pr = result.expect("PR creation should succeed").expect("PR should be created");
+        let created = mock.get_created_prs();
+        assert_eq!(created.get("forker:gitx/forker/add-feature").map(|p| p.number), Some(pr.number));
+        // The branch itself, on the fork, keeps its plain name
+        assert_eq!(stored.unwrap().pr_branch_name, "gitx/forker/add-feature");
+    }
+
+    #[tokio::test]
+    async fn test_push_and_delete_use_configured_remote() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let fork_dir = tempfile::tempdir().unwrap();
+        let fork = Repository::init_bare(fork_dir.path()).unwrap();
+        repo.remote("fork", fork_dir.path().to_str().unwrap()).unwrap();
+        repo.config().unwrap().set_str("gitx.remote", "fork").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("gitx/test/add-feature", &root, false).unwrap();
+        let push_result = GitUtils::push_branch("gitx/test/add-feature", false).await;
+        let pushed = fork.find_reference("refs/heads/gitx/test/add-feature").is_ok();
+        let delete_result = delete_remote_branch("gitx/test/add-feature").await;
+        let deleted = fork.find_reference("refs/heads/gitx/test/add-feature").is_err();
+        let remote_url = GitUtils::get_remote_url();
+        // Base branches are looked up on the configured remote too
+        repo.reference("refs/remotes/fork/develop", root.id(), false, "fetch").unwrap();
+        let base_found = branch_exists_locally_or_remotely(&repo, "develop");
+        let notes_tracking_ref = remote_notes_ref("refs/notes/gitx-metadata");
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        push_result.expect("Push to the fork remote should succeed");
+        assert!(pushed, "Branch should land on the fork remote");
+        delete_result.expect("Delete on the fork remote should succeed");
+        assert!(deleted, "Branch should be removed from the fork remote");
+        assert_eq!(remote_url.unwrap(), fork_dir.path().to_str().unwrap());
+        assert!(base_found, "develop should be found as fork/develop");
+        assert_eq!(notes_tracking_ref, "refs/notes/fork/gitx-metadata");
+    }
+
+    #[tokio::test]
+    async fn test_checkout_pr_resolves_branch_name_through_github() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let pr = mock.create_pr("alice/add-search", "Add search", "Body", "main", false).await.unwrap();
+
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        // Publish a commit only as the PR's head ref, the way GitHub exposes PRs from forks
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let pr_head = commit_file(&repo, &root, "search.txt", "search", "Add search");
+        let published = std::process::Command::new("git")
+            .args(["push", "origin", &format!("{}:refs/pull/{}/head", pr_head, pr.number)])
+            .output()
+            .unwrap();
+        let result = checkout_pr_with_github_client("alice/add-search", false, &mock).await;
+        let head = repo.head().unwrap();
+
+        std::env::set_current_dir(original_dir).unwrap();
+
+        assert!(published.status.success());
+        assert_eq!(result.expect("Checkout should succeed"), "alice/add-search");
+        assert_eq!(head.shorthand(), Some("alice/add-search"));
+        assert_eq!(head.target(), Some(pr_head));
+    }
+
+    #[tokio::test]
+    async fn test_checkout_pr_by_number_refuses_dirty_tree_without_creating_branch() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let pr_head = commit_file(&repo, &root, "search.txt", "search", "Add search");
+        let published = std::process::Command::new("git")
+            .args(["push", "origin", &format!("{}:refs/pull/7/head", pr_head)])
+            .output()
+            .unwrap();
+        fs::write(temp_dir.path().join("test.txt"), "uncommitted edit").unwrap();
+        // A PR number resolves without a GitHub client
+        let refused = checkout_pr("#7", false).await;
+        let left_behind = repo.find_branch("pr-7", BranchType::Local).is_ok();
+        fs::write(temp_dir.path().join("test.txt"), "test content").unwrap();
+        let retried = checkout_pr("#7", false).await;
+        let head = repo.head().unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(published.status.success());
+        assert!(refused.unwrap_err().to_string().contains("uncommitted changes"));
+        assert!(!left_behind, "the refused checkout shouldn't create pr-7");
+        assert_eq!(retried.expect("Checkout should succeed once the tree is clean"), "pr-7");
+        assert_eq!(head.target(), Some(pr_head));
+    }
+    
+    #[tokio::test]
+    async fn test_rejected_push_needs_force_update() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let remote_dir = add_bare_origin(&repo);
+        let origin = Repository::open_bare(remote_dir.path()).unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Push one version of the branch, then rewrite it locally so the two diverge
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let pushed = commit_file(&repo, &root, "feature.txt", "first", "Add feature");
+        repo.branch("gitx/test/add-feature", &repo.find_commit(pushed).unwrap(), false).unwrap();
+        let first_push = GitUtils::push_branch("gitx/test/add-feature", false).await;
+        let rewritten = commit_file(&repo, &root, "feature.txt", "second", "Add feature");
+        repo.branch("gitx/test/add-feature", &repo.find_commit(rewritten).unwrap(), true).unwrap();
+        let rejected = GitUtils::push_branch("gitx/test/add-feature", false).await;
+        let forced = GitUtils::push_branch("gitx/test/add-feature", true).await;
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        first_push.expect("First push should succeed");
+        assert!(rejected.expect_err("Diverged push should be rejected").to_string().contains("--force"));
+        forced.expect("Force update should succeed");
+        let remote_head = origin.find_reference("refs/heads/gitx/test/add-feature").unwrap().target();
+        assert_eq!(remote_head, Some(rewritten));
+    }
+
+    #[tokio::test]
+    async fn test_force_update_keeps_changes_pushed_by_someone_else() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let remote_dir = add_bare_origin(&repo);
+        let origin = Repository::open_bare(remote_dir.path()).unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let pushed = commit_file(&repo, &root, "feature.txt", "first", "Add feature");
+        repo.branch("gitx/test/add-feature", &repo.find_commit(pushed).unwrap(), false).unwrap();
+        GitUtils::push_branch("gitx/test/add-feature", false).await.unwrap();
+        // A collaborator pushes on top of the branch, then it's rewritten locally
+        let theirs = commit_file(&repo, &repo.find_commit(pushed).unwrap(), "feature.txt", "theirs", "Fix feature");
+        let publish = std::process::Command::new("git")
+            .args(["push", "--quiet", remote_dir.path().to_str().unwrap(), &format!("{}:refs/heads/gitx/test/add-feature", theirs)])
+            .output()
+            .unwrap();
+        let rewritten = commit_file(&repo, &root, "feature.txt", "second", "Add feature");
+        repo.branch("gitx/test/add-feature", &repo.find_commit(rewritten).unwrap(), true).unwrap();
+        let forced = GitUtils::push_branch("gitx/test/add-feature", true).await;
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(publish.status.success());
+        assert!(forced.expect_err("The lease should protect their commit").to_string().contains("Failed to force-update branch"));
+        let remote_head = origin.find_reference("refs/heads/gitx/test/add-feature").unwrap().target();
+        assert_eq!(remote_head, Some(theirs));
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_cleans_up_branch_after_rejected_push() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Someone else's version of the PR branch is already on the remote
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let theirs = commit_file(&repo, &root, "feature.txt", "theirs", "Add feature elsewhere");
+        let mut their_branch = repo.branch("gitx/test/add-feature", &repo.find_commit(theirs).unwrap(), false).unwrap();
+        GitUtils::push_branch("gitx/test/add-feature", false).await.unwrap();
+        their_branch.delete().unwrap();
+        
+        let oid = commit_file(&repo, &root, "feature.txt", "mine", "Add feature");
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let result = create_transient_pr_branch_with_github_client(&commit_info, &github::PRCreateOptions::default(), &mock).await;
+        let leftover = repo.find_branch("gitx/test/add-feature", BranchType::Local).is_ok();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(result.expect_err("Diverged push should fail").to_string().contains("diverged"));
+        assert!(!leftover, "The transient branch should be deleted after a failed push");
+        assert!(mock.get_created_prs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_failure_leaves_no_local_branch() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let failing = crate::mock_github::MockGitHubClient::new();
+        failing.fail_create_pr("Validation Failed");
+        let failed = create_transient_pr_branch_with_github_client(&commit_info, &github::PRCreateOptions::default(), &failing).await;
+        let leftover = repo.find_branch("gitx/test/add-feature", BranchType::Local).is_ok();
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let retried = create_transient_pr_branch_with_github_client(&commit_info, &github::PRCreateOptions::default(), &mock).await;
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(failed.expect_err("create_pr should fail").to_string().contains("Validation Failed"));
+        assert!(!leftover, "The transient branch should be deleted after create_pr fails");
+        // Neither a leftover branch nor leftover metadata gets in the way of trying again
+        retried.expect("Retry should succeed").expect("PR should be created");
+    }
+
+    #[tokio::test]
+    async fn test_push_only_pushes_without_pr_until_next_diff() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let remote_dir = add_bare_origin(&repo);
+        let origin = Repository::open_bare(remote_dir.path()).unwrap();
+
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let push_only = github::PRCreateOptions { push_only: true, ..Default::default() };
+        let pushed = create_transient_pr_branch_with_github_client(&commit_info, &push_only, &mock).await;
+        let kept_locally = repo.find_branch("gitx/test/add-feature", BranchType::Local).is_ok();
+        let pushed_metadata = metadata::get_commit_metadata(&oid).unwrap().unwrap();
+        let prs_after_push = mock.get_created_prs().len();
+
+        // The next `gitx diff` picks the commit up again and opens its PR
+        let update = classify_commit(&repo.find_commit(oid).unwrap(), "test", false).unwrap();
+        let opened = match &update {
+            Some(CommitUpdateType::NewCommit(info)) => create_transient_pr_branch_with_github_client(info, &github::PRCreateOptions::default(), &mock).await,
+            other => panic!("Expected the pushed commit to need a PR, got {:?}", other),
+        };
+        let final_metadata = metadata::get_commit_metadata(&oid).unwrap().unwrap();
+
+        std::env::set_current_dir(original_dir).unwrap();
+
+        assert!(pushed.expect("Push-only should succeed").is_none());
+        assert!(kept_locally, "Push-only should keep the local branch");
+        assert_eq!(origin.refname_to_id("refs/heads/gitx/test/add-feature").unwrap(), oid);
+        assert_eq!(pushed_metadata.status, metadata::PRStatus::BranchCreated);
+        assert_eq!(pushed_metadata.github_pr_number, None);
+        assert_eq!(prs_after_push, 0);
+
+        let pr = opened.expect("The follow-up diff should succeed").expect("PR should be created");
+        assert_eq!(final_metadata.github_pr_number, Some(pr.number));
+        assert!(repo.find_branch("gitx/test/add-feature", BranchType::Local).is_err());
+    }
+
+    #[test]
+    fn test_create_pr_branch_keeps_branch_at_same_commit() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        repo.branch("gitx/test/add-feature", &repo.find_commit(oid).unwrap(), false).unwrap();
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let result = create_pr_branch(&commit_info);
+        let has_metadata = metadata::has_pr_metadata(&oid);
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        result.expect("An existing branch at the same commit should be reused");
+        assert!(has_metadata);
+        let target = repo.find_branch("gitx/test/add-feature", BranchType::Local).unwrap().get().target();
+        assert_eq!(target, Some(oid));
+    }
+
+    #[test]
+    fn test_create_pr_branch_with_branch_at_different_commit() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let stale = commit_file(&repo, &root, "feature.txt", "old", "Add feature");
+        let oid = commit_file(&repo, &root, "feature.txt", "new", "Add feature");
+        repo.branch("gitx/test/add-feature", &repo.find_commit(stale).unwrap(), false).unwrap();
+        repo.branch("add-feature", &repo.find_commit(stale).unwrap(), false).unwrap();
+        let managed = create_pr_branch(&CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        });
+        let unmanaged = create_pr_branch(&CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        });
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        managed.expect("A stale gitx branch should be moved to the new commit");
+        let managed_target = repo.find_branch("gitx/test/add-feature", BranchType::Local).unwrap().get().target();
+        assert_eq!(managed_target, Some(oid));
+        assert!(unmanaged.expect_err("Other branches should be left alone").message().contains("isn't managed by gitx"));
+        let unmanaged_target = repo.find_branch("add-feature", BranchType::Local).unwrap().get().target();
+        assert_eq!(unmanaged_target, Some(stale));
+    }
+
+    #[tokio::test]
+    async fn test_log_shows_pr_number_of_diffed_commit() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        repo.set_head_detached(oid).unwrap();
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let diffed = create_transient_pr_branch_with_github_client(&commit_info, &github::PRCreateOptions::default(), &mock).await;
+        let commits = get_branch_commits();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let pr_info = diffed.expect("Diff should succeed").expect("PR should be created");
+        let lines: Vec<String> = commits.unwrap().iter().map(crate::commands::log::format_log_line).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(&oid.to_string()[..7]));
+        assert!(lines[0].contains("Add feature"));
+        assert!(lines[0].contains(&format!("gitx/test/add-feature #{}", pr_info.number)));
+        assert!(lines[1].ends_with("Initial commit"));
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_metadata_for_collected_commit() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Annotate a commit nothing points at, then let gc throw it away
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let dropped = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        let dropped_metadata = metadata::CommitMetadata::new_branch_created("gitx/test/add-feature".to_string(), dropped.to_string());
+        metadata::store_commit_metadata(&dropped, &dropped_metadata).unwrap();
+        let kept_metadata = metadata::CommitMetadata::new_branch_created("gitx/test/initial-commit".to_string(), root.id().to_string());
+        metadata::store_commit_metadata(&root.id(), &kept_metadata).unwrap();
+        let gc = std::process::Command::new("git").args(["gc", "--prune=now", "--quiet"]).output().unwrap();
+        
+        let preview = prune_metadata(true).await;
+        let still_listed = metadata::list_all_pr_commits().unwrap().len();
+        let pruned = prune_metadata(false).await;
+        let remaining = metadata::list_all_pr_commits().unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(gc.status.success());
+        assert!(repo.find_commit(dropped).is_err(), "gc should have removed the commit");
+        assert_eq!(preview.unwrap().len(), 1);
+        assert_eq!(still_listed, 2, "A dry run should not remove anything");
+        let pruned = pruned.unwrap();
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].commit_id, dropped);
+        assert_eq!(pruned[0].reason, "commit no longer exists");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].0, root.id());
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_metadata_for_landed_pr() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Neither PR branch is on the remote: the landed one was deleted after merging
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let landed = commit_file(&repo, &root, "landed.txt", "content", "Add landed feature");
+        let landed_metadata = metadata::CommitMetadata::new_branch_created("gitx/test/add-landed-feature".to_string(), landed.to_string())
+            .with_pr_number(1)
+            .mark_merged();
+        metadata::store_commit_metadata(&landed, &landed_metadata).unwrap();
+        let open_pr = commit_file(&repo, &root, "open.txt", "content", "Add open feature");
+        let open_metadata = metadata::CommitMetadata::new_branch_created("gitx/test/add-open-feature".to_string(), open_pr.to_string())
+            .with_pr_number(2);
+        metadata::store_commit_metadata(&open_pr, &open_metadata).unwrap();
+        
+        let pruned = prune_metadata(false).await;
+        let remaining = metadata::list_all_pr_commits().unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let pruned = pruned.unwrap();
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].commit_id, open_pr);
+        assert_eq!(pruned[0].reason, "branch is gone from the remote");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].0, landed);
+    }
+
+    #[tokio::test]
+    async fn test_adopt_pr_tracks_existing_pr() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let pr = mock.create_pr("feature/manual", "Made by hand", "Body", "main", false).await.unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let head = commit_file(&repo, &root, "manual.txt", "content", "Made by hand");
+        mock.set_pr_head_sha(pr.number, &head.to_string());
+        let adopted = adopt_pr_with_github_client(pr.number, &mock).await;
+        let listed = metadata::get_all_pr_status();
+        let adopted_again = adopt_pr_with_github_client(pr.number, &mock).await;
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        let (commit_id, pr_metadata) = adopted.expect("Adopting should succeed");
+        assert_eq!(commit_id, head);
+        assert_eq!(pr_metadata.base_branch.as_deref(), Some("main"));
+        let listed = listed.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].branch_name, "feature/manual");
+        assert_eq!(listed[0].pr_number, Some(pr.number));
+        assert!(adopted_again.is_err(), "A PR can only be adopted once");
     }
-    
-    Ok(())
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::path::Path;
-    use git2::{Repository, Signature};
+    /// Point the test repo's origin at a fresh bare repository so pushes succeed
+    fn add_bare_origin(repo: &Repository) -> tempfile::TempDir {
+        let remote_dir = tempfile::tempdir().unwrap();
+        Repository::init_bare(remote_dir.path()).unwrap();
+        repo.remote("origin", remote_dir.path().to_str().unwrap()).unwrap();
+        remote_dir
+    }
 
-    fn create_test_repo() -> Result<(Repository, tempfile::TempDir), Box<dyn std::error::Error>> {
-        let temp_dir = tempfile::tempdir()?;
-        let repo = Repository::init(&temp_dir)?;
+    #[tokio::test]
+    async fn test_create_pr_with_edited_message() {
+        use std::os::unix::fs::PermissionsExt;
         
-        // Configure user for commits
-        let mut config = repo.config()?;
-        config.set_str("user.name", "Test User")?;
-        config.set_str("user.email", "test@example.com")?;
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
         
-        // Create initial commit
-        let signature = Signature::now("Test User", "test@example.com")?;
-        let tree_id = {
-            let mut index = repo.index()?;
-            // Create a test file
-            let test_file_path = temp_dir.path().join("test.txt");
-            fs::write(&test_file_path, "test content")?;
-            index.add_path(Path::new("test.txt"))?;
-            index.write()?;
-            index.write_tree()?
+        // GIT_EDITOR takes precedence over any editor configured on the machine
+        let editor_dir = tempfile::tempdir().unwrap();
+        let editor = editor_dir.path().join("editor.sh");
+        let write_editor = |script: &str| {
+            fs::write(&editor, format!("#!/bin/sh\n{}\n", script)).unwrap();
+            fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
         };
-        let tree = repo.find_tree(tree_id)?;
-        repo.commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            "Initial commit",
-            &tree,
-            &[],
-        )?;
-        drop(tree); // Explicitly drop the tree to release the borrow
+        let previous_editor = std::env::var("GIT_EDITOR").ok();
+        unsafe { std::env::set_var("GIT_EDITOR", &editor); }
         
-        Ok((repo, temp_dir))
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        let commit_info = CommitInfo {
+            id: oid,
+            message: "Add feature".to_string(),
+            potential_branch_name: "gitx/test/add-feature".to_string(),
+            grouped_commits: Vec::new(),
+        };
+        let pr_options = github::PRCreateOptions { edit: true, ..Default::default() };
+        let mock = crate::mock_github::MockGitHubClient::new();
+        
+        // Closing the editor without changes aborts before anything is pushed
+        write_editor("exit 0");
+        let unchanged = create_transient_pr_branch_with_github_client(&commit_info, &pr_options, &mock).await;
+        let stored_after_abort = metadata::get_commit_metadata(&oid).unwrap();
+        
+        write_editor("printf 'Edited title\\n\\nEdited body\\n' > \"$1\"");
+        let edited = create_transient_pr_branch_with_github_client(&commit_info, &pr_options, &mock).await;
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        match previous_editor {
+            Some(value) => unsafe { std::env::set_var("GIT_EDITOR", value) },
+            None => unsafe { std::env::remove_var("GIT_EDITOR") },
+        }
+        
+        let err = unchanged.expect_err("Unchanged message should abort");
+        assert!(err.to_string().contains("unchanged"), "{}", err);
+        assert!(stored_after_abort.is_none());
+        
+        let pr_info = edited.expect("PR creation should succeed").unwrap();
+        assert_eq!(pr_info.title, "Edited title");
+        assert_eq!(mock.get_pr_body("gitx/test/add-feature"), Some("Edited body".to_string()));
     }
 
-    #[test]
-    fn test_get_all_branches_with_single_branch() {
-        let (_repo, _temp_dir) = create_test_repo().expect("Failed to create test repo");
+    #[tokio::test]
+    async fn test_update_stack_pr_bodies_links_base() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
         
-        // Change to the test repo directory
         let original_dir = std::env::current_dir().unwrap();
-        std::env::set_current_dir(_temp_dir.path()).unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        let branches = get_all_branches().expect("Failed to get branches");
+        // Two-PR stack: #2 is based on #1's branch
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let bottom_oid = root.id();
+        let top_oid = commit_file(&repo, &root, "top.txt", "top content", "Add top feature");
         
-        // Should have at least the main/master branch
-        assert!(!branches.is_empty());
-        assert!(branches.contains(&"main".to_string()) || branches.contains(&"master".to_string()));
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let bottom_pr = mock.create_pr("gitx/test/bottom", "Bottom", "Body", "main", false).await.unwrap();
+        let top_pr = mock.create_pr("gitx/test/top", "Top", "Body", "gitx/test/bottom", false).await.unwrap();
+        
+        let bottom = metadata::CommitMetadata::new_branch_created("gitx/test/bottom".to_string(), bottom_oid.to_string())
+            .with_pr_number(bottom_pr.number)
+            .with_base_branch("main".to_string());
+        let top = metadata::CommitMetadata::new_branch_created("gitx/test/top".to_string(), top_oid.to_string())
+            .with_pr_number(top_pr.number)
+            .with_base_branch("gitx/test/bottom".to_string());
+        metadata::store_commit_metadata(&bottom_oid, &bottom).unwrap();
+        metadata::store_commit_metadata(&top_oid, &top).unwrap();
+        
+        let result = update_stack_pr_bodies_with_github_client("gitx/test/top", &mock).await;
         
-        // Restore original directory
         std::env::set_current_dir(original_dir).unwrap();
+        
+        result.expect("Stack update should succeed");
+        let updates = mock.get_pr_updates();
+        let top_body = updates[&top_pr.number].1.clone().unwrap();
+        let bottom_body = updates[&bottom_pr.number].1.clone().unwrap();
+        assert!(top_body.contains("## Stack\n\n1. #1\n2. #2 (this)"), "{}", top_body);
+        assert!(bottom_body.contains("1. #1 (this)\n2. #2"), "{}", bottom_body);
     }
 
-    #[test]
-    fn test_get_all_branches_basic() {
-        let (_repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+    #[tokio::test]
+    async fn test_update_stack_pr_bodies_keeps_custom_body() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
         
-        // Change to the test repo directory
         let original_dir = std::env::current_dir().unwrap();
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        let branches = get_all_branches().expect("Failed to get branches");
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let bottom_oid = root.id();
+        let top_oid = commit_file(&repo, &root, "top.txt", "top content", "Add top feature");
         
-        // Should have at least one branch (master/main)
-        assert!(!branches.is_empty());
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let bottom_pr = mock.create_pr("gitx/test/bottom", "Bottom", "Body", "main", false).await.unwrap();
+        let custom_body = "Edited by hand\n\n## Commits\n\n- abc12345 Add top feature\n";
+        let top_pr = mock.create_pr("gitx/test/top", "Top", custom_body, "gitx/test/bottom", false).await.unwrap();
+        
+        let bottom = metadata::CommitMetadata::new_branch_created("gitx/test/bottom".to_string(), bottom_oid.to_string())
+            .with_pr_number(bottom_pr.number)
+            .with_base_branch("main".to_string());
+        let top = metadata::CommitMetadata::new_branch_created("gitx/test/top".to_string(), top_oid.to_string())
+            .with_pr_number(top_pr.number)
+            .with_base_branch("gitx/test/bottom".to_string());
+        metadata::store_commit_metadata(&bottom_oid, &bottom).unwrap();
+        metadata::store_commit_metadata(&top_oid, &top).unwrap();
+        
+        let first = update_stack_pr_bodies_with_github_client("gitx/test/top", &mock).await;
+        let second = update_stack_pr_bodies_with_github_client("gitx/test/top", &mock).await;
         
-        // Restore original directory
         std::env::set_current_dir(original_dir).unwrap();
+        
+        first.expect("Stack update should succeed");
+        second.expect("Repeated stack update should succeed");
+        let top_body = mock.get_pr_updates()[&top_pr.number].1.clone().unwrap();
+        assert!(top_body.starts_with("Edited by hand\n\n## Commits\n\n- abc12345 Add top feature"), "{}", top_body);
+        assert_eq!(top_body.matches("## Stack").count(), 1, "{}", top_body);
+        assert!(top_body.contains("## Stack\n\n1. #1\n2. #2 (this)"), "{}", top_body);
     }
 
-    #[test]
-    fn test_switch_to_nonexistent_branch() {
-        let (_repo, _temp_dir) = create_test_repo().expect("Failed to create test repo");
+    #[tokio::test]
+    async fn test_reword_head_updates_commit_and_pr_title() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
         
-        // Change to the test repo directory
         let original_dir = std::env::current_dir().unwrap();
-        std::env::set_current_dir(_temp_dir.path()).unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        // Try to switch to a branch that doesn't exist
-        let result = switch_branch("nonexistent-branch");
-        assert!(result.is_err());
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feture\n\nLonger description");
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&head_ref, oid, true, "Add feature").unwrap();
+        
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let pr = mock.create_pr("gitx/test/add-feture", "Add feture", "Body", "main", false).await.unwrap();
+        let pr_metadata = metadata::CommitMetadata::new_branch_created("gitx/test/add-feture".to_string(), oid.to_string())
+            .with_pr_number(pr.number);
+        metadata::store_commit_metadata(&oid, &pr_metadata).unwrap();
+        
+        let result = reword_commit_with_github_client(None, "Add feature", Some(&mock)).await;
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let moved = metadata::get_commit_metadata(&head.id()).unwrap();
+        let old = metadata::get_commit_metadata(&oid).unwrap();
         
-        // Restore original directory
         std::env::set_current_dir(original_dir).unwrap();
+        
+        let (new_oid, retitled) = result.expect("Reword should succeed");
+        assert_eq!(new_oid, head.id());
+        assert_eq!(head.message().unwrap(), "Add feature\n\nLonger description");
+        assert_eq!(retitled, Some(pr.number));
+        assert_eq!(mock.get_pr_updates()[&pr.number].0, Some("Add feature".to_string()));
+        assert_eq!(moved.unwrap().github_pr_number, Some(pr.number));
+        assert!(old.is_none());
     }
 
-    #[test]
-    fn test_get_commits_needing_processing_latest_only() {
+    #[tokio::test]
+    async fn test_reword_rewrites_descendants() {
         let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
         
-        // Change to the test repo directory
         let original_dir = std::env::current_dir().unwrap();
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        // Add multiple commits
-        let signature = repo.signature().unwrap();
-        for i in 1..=3 {
-            let content = format!("Feature {}", i);
-            let test_file_path = temp_dir.path().join("features.txt");
-            let existing = std::fs::read_to_string(&test_file_path).unwrap_or_default();
-            std::fs::write(&test_file_path, format!("{}{}\n", existing, content)).unwrap();
-            
-            let mut index = repo.index().unwrap();
-            index.add_path(Path::new("features.txt")).unwrap();
-            index.write().unwrap();
-            
-            let tree_id = index.write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
-            let parent = repo.head().unwrap().peel_to_commit().unwrap();
-            
-            repo.commit(
-                Some("HEAD"),
-                &signature,
-                &signature,
-                &format!("Add feature {}", i),
-                &tree,
-                &[&parent],
-            ).unwrap();
-        }
-        
-        // Test latest only
-        let updates = get_latest_commit_needing_processing().expect("Failed to get latest commits");
-        assert_eq!(updates.len(), 1);
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let bottom = commit_file(&repo, &root, "bottom.txt", "bottom", "Add botom");
+        let top = commit_file(&repo, &repo.find_commit(bottom).unwrap(), "top.txt", "top", "Add top");
+        let head_ref = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&head_ref, top, true, "Build stack").unwrap();
+        let top_tree = repo.find_commit(top).unwrap().tree_id();
         
-        // Test all commits
-        let updates = get_commits_needing_processing().expect("Failed to get all commits");
-        assert!(updates.len() >= 3);
+        let result = reword_commit_with_github_client(Some(&bottom.to_string()), "Add bottom", None).await;
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
         
-        // Restore original directory
         std::env::set_current_dir(original_dir).unwrap();
+        
+        let (new_bottom, retitled) = result.expect("Reword should succeed");
+        assert_eq!(retitled, None);
+        assert_eq!(head.message().unwrap(), "Add top");
+        assert_eq!(head.tree_id(), top_tree);
+        assert_eq!(head.parent_id(0).unwrap(), new_bottom);
+        assert_eq!(repo.find_commit(new_bottom).unwrap().message().unwrap(), "Add bottom");
     }
 
     #[test]
@@ -803,6 +3993,7 @@ mod tests {
             id: git2::Oid::from_str("1234567890abcdef1234567890abcdef12345678").unwrap(),
             message: "Add user authentication".to_string(),
             potential_branch_name: "gitx/test/add-user-authentication".to_string(),
+            grouped_commits: Vec::new(),
         };
         
         assert_eq!(commit_info.message, "Add user authentication");
@@ -825,6 +4016,7 @@ mod tests {
             id: commit.id(),
             message: "Add new feature".to_string(),
             potential_branch_name: "gitx/test/add-new-feature".to_string(),
+            grouped_commits: Vec::new(),
         };
         
         // Create PR branch
@@ -841,6 +4033,281 @@ mod tests {
         std::env::set_current_dir(original_dir).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_close_pr_updates_github_and_metadata() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        // Change to the test repo directory
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Track the HEAD commit as an open PR
+        let commit_oid = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let pr_info = mock.create_pr("gitx/test/feature", "Add feature", "Body", "main", false).await.unwrap();
+        let pr_metadata = metadata::CommitMetadata::new_branch_created(
+            "gitx/test/feature".to_string(),
+            commit_oid.to_string()
+        ).with_pr_number(pr_info.number);
+        metadata::store_commit_metadata(&commit_oid, &pr_metadata).unwrap();
+        
+        let closed = close_pr_with_github_client("gitx/test/feature", false, &mock).await.unwrap();
+        assert_eq!(closed, pr_info.number);
+        
+        // GitHub state and local metadata should both reflect the close
+        assert_eq!(mock.get_pr_status(pr_info.number).await.unwrap().state, "closed");
+        let stored = metadata::get_commit_metadata(&commit_oid).unwrap().unwrap();
+        assert_eq!(stored.status, metadata::PRStatus::Cancelled);
+        
+        // Unknown branches are an error
+        assert!(close_pr_with_github_client("gitx/test/unknown", false, &mock).await.is_err());
+        
+        // Restore original directory
+        std::env::set_current_dir(original_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_land_retargets_dependent_pr() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Two-PR stack: bottom targets main, top targets the bottom PR's branch
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let bottom_oid = root.id();
+        let top_oid = commit_file(&repo, &root, "top.txt", "top content", "Add top feature");
+        
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let bottom_pr = mock.create_pr("gitx/test/bottom", "Bottom", "Body", "main", false).await.unwrap();
+        let top_pr = mock.create_pr("gitx/test/top", "Top", "Body", "gitx/test/bottom", false).await.unwrap();
+        
+        let bottom = metadata::CommitMetadata::new_branch_created("gitx/test/bottom".to_string(), bottom_oid.to_string())
+            .with_pr_number(bottom_pr.number)
+            .with_base_branch("main".to_string());
+        let top = metadata::CommitMetadata::new_branch_created("gitx/test/top".to_string(), top_oid.to_string())
+            .with_pr_number(top_pr.number)
+            .with_base_branch("gitx/test/bottom".to_string());
+        metadata::store_commit_metadata(&bottom_oid, &bottom).unwrap();
+        metadata::store_commit_metadata(&top_oid, &top).unwrap();
+        
+        // The bottom PR merges on GitHub
+        let mut merged_status = mock.get_pr_status(bottom_pr.number).await.unwrap();
+        merged_status.state = "merged".to_string();
+        mock.add_pr_status(bottom_pr.number, merged_status);
+        
+        let result = land_merged_prs_with_github_client(false, false, false, false, &mock).await;
+        let stored_bottom = metadata::get_commit_metadata(&bottom_oid).unwrap().unwrap();
+        let stored_top = metadata::get_commit_metadata(&top_oid).unwrap().unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        result.expect("Land should succeed");
+        assert_eq!(stored_bottom.status, metadata::PRStatus::PRMerged);
+        assert_eq!(mock.get_pr_base(top_pr.number), Some("main".to_string()));
+        assert_eq!(stored_top.base_branch, Some("main".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_land_keeps_remote_branch_until_dependents_are_retargeted() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let remote_dir = add_bare_origin(&repo);
+        let origin = Repository::open_bare(remote_dir.path()).unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let bottom_oid = root.id();
+        let top_oid = commit_file(&repo, &root, "top.txt", "top content", "Add top feature");
+        repo.branch("gitx/test/bottom", &root, false).unwrap();
+        GitUtils::push_branch("gitx/test/bottom", false).await.unwrap();
+        
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let bottom_pr = mock.create_pr("gitx/test/bottom", "Bottom", "Body", "main", false).await.unwrap();
+        let bottom = metadata::CommitMetadata::new_branch_created("gitx/test/bottom".to_string(), bottom_oid.to_string())
+            .with_pr_number(bottom_pr.number)
+            .with_base_branch("main".to_string());
+        // GitHub doesn't know the top PR, so retargeting it fails
+        let top = metadata::CommitMetadata::new_branch_created("gitx/test/top".to_string(), top_oid.to_string())
+            .with_pr_number(99)
+            .with_base_branch("gitx/test/bottom".to_string());
+        metadata::store_commit_metadata(&bottom_oid, &bottom).unwrap();
+        metadata::store_commit_metadata(&top_oid, &top).unwrap();
+        
+        let result = land_pr_with_github_client(bottom_pr.number, true, false, false, &mock).await;
+        let stored_bottom = metadata::get_commit_metadata(&bottom_oid).unwrap().unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        result.expect("Land should succeed");
+        assert_eq!(stored_bottom.status, metadata::PRStatus::PRMerged);
+        // set_base has to succeed before the branch it moves PRs off is deleted
+        assert!(origin.find_reference("refs/heads/gitx/test/bottom").is_ok(), "the stacked PR's base was deleted");
+    }
+
+    #[tokio::test]
+    async fn test_land_separates_closed_prs_from_merged() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // One PR merged on GitHub, one closed without merging
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let merged_oid = root.id();
+        let closed_oid = commit_file(&repo, &root, "abandoned.txt", "content", "Abandoned idea");
+        
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let mut tracked = Vec::new();
+        for (oid, branch, state) in [(merged_oid, "gitx/test/merged", "merged"), (closed_oid, "gitx/test/closed", "closed")] {
+            let pr = mock.create_pr(branch, branch, "Body", "main", false).await.unwrap();
+            let mut status = mock.get_pr_status(pr.number).await.unwrap();
+            status.state = state.to_string();
+            mock.add_pr_status(pr.number, status);
+            let pr_metadata = metadata::CommitMetadata::new_branch_created(branch.to_string(), oid.to_string())
+                .with_pr_number(pr.number);
+            metadata::store_commit_metadata(&oid, &pr_metadata).unwrap();
+            tracked.push(oid);
+        }
+        let statuses = || -> Vec<metadata::PRStatus> {
+            tracked.iter().map(|oid| metadata::get_commit_metadata(oid).unwrap().unwrap().status).collect()
+        };
+        
+        let without_flag = land_merged_prs_with_github_client(false, false, true, false, &mock).await;
+        let after_default = statuses();
+        let with_flag = land_merged_prs_with_github_client(false, false, true, true, &mock).await;
+        let after_include_closed = statuses();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        without_flag.expect("Land should succeed");
+        with_flag.expect("Land with --include-closed should succeed");
+        // Closed PRs are left alone unless asked for
+        assert_eq!(after_default, vec![metadata::PRStatus::PRMerged, metadata::PRStatus::PRCreated]);
+        assert_eq!(after_include_closed, vec![metadata::PRStatus::PRMerged, metadata::PRStatus::Cancelled]);
+    }
+
+    #[tokio::test]
+    async fn test_land_single_pr() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Three tracked PRs, two of them merged on GitHub
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let mut tracked = Vec::new();
+        for name in ["one", "two", "three"] {
+            let oid = commit_file(&repo, &root, &format!("{}.txt", name), name, name);
+            let branch = format!("gitx/test/{}", name);
+            let pr = mock.create_pr(&branch, name, "Body", "main", false).await.unwrap();
+            let pr_metadata = metadata::CommitMetadata::new_branch_created(branch, oid.to_string())
+                .with_pr_number(pr.number);
+            metadata::store_commit_metadata(&oid, &pr_metadata).unwrap();
+            tracked.push((oid, pr.number));
+        }
+        for &(_, pr_number) in &tracked[..2] {
+            let mut status = mock.get_pr_status(pr_number).await.unwrap();
+            status.state = "merged".to_string();
+            mock.add_pr_status(pr_number, status);
+        }
+        
+        let landed = land_pr_with_github_client(tracked[1].1, false, false, false, &mock).await;
+        let open_without_force = land_pr_with_github_client(tracked[2].1, false, false, false, &mock).await;
+        let untracked = land_pr_with_github_client(99, false, false, false, &mock).await;
+        let statuses: Vec<metadata::PRStatus> = tracked
+            .iter()
+            .map(|(oid, _)| metadata::get_commit_metadata(oid).unwrap().unwrap().status)
+            .collect();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        landed.expect("Landing a merged PR should succeed");
+        assert!(open_without_force.is_err());
+        assert!(untracked.is_err());
+        
+        // Only the requested PR was cleaned up, even though another one is merged too
+        assert_eq!(statuses, vec![
+            metadata::PRStatus::PRCreated,
+            metadata::PRStatus::PRMerged,
+            metadata::PRStatus::PRCreated,
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_land_pr_keep_remote_leaves_remote_branch() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let remote_dir = add_bare_origin(&repo);
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let oid = commit_file(&repo, &root, "feature.txt", "content", "Add feature");
+        let branch = "gitx/test/add-feature";
+        repo.branch(branch, &repo.find_commit(oid).unwrap(), false).unwrap();
+        let push_result = GitUtils::push_branch(branch, false).await;
+        
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let pr = mock.create_pr(branch, "Add feature", "Body", "main", false).await.unwrap();
+        let mut status = mock.get_pr_status(pr.number).await.unwrap();
+        status.state = "merged".to_string();
+        mock.add_pr_status(pr.number, status);
+        let pr_metadata = metadata::CommitMetadata::new_branch_created(branch.to_string(), oid.to_string())
+            .with_pr_number(pr.number);
+        metadata::store_commit_metadata(&oid, &pr_metadata).unwrap();
+        
+        let landed = land_pr_with_github_client(pr.number, false, false, true, &mock).await;
+        let stored = metadata::get_commit_metadata(&oid).unwrap().unwrap();
+        let local_branch_left = repo.find_branch(branch, BranchType::Local).is_ok();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        push_result.expect("Push to origin should succeed");
+        landed.expect("Landing a merged PR should succeed");
+        assert_eq!(stored.status, metadata::PRStatus::PRMerged);
+        assert!(!local_branch_left, "The local branch should be removed");
+        let remote = Repository::open_bare(remote_dir.path()).unwrap();
+        assert!(remote.find_reference(&format!("refs/heads/{}", branch)).is_ok(), "The remote branch should be kept");
+    }
+
+    #[tokio::test]
+    async fn test_sync_with_origin_main_fast_forwards() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        let _remote_dir = add_bare_origin(&repo);
+        let base = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.config().unwrap().set_str("gitx.github.baseBranch", &base).unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Someone else's commit lands on the remote base
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let upstream = commit_file(&repo, &root, "upstream.txt", "content", "Upstream change");
+        let push = std::process::Command::new("git")
+            .args(["push", "origin", &format!("{}:refs/heads/{}", upstream, base)])
+            .output()
+            .unwrap();
+        
+        let synced = sync_with_origin_main().await;
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(push.status.success());
+        assert_eq!(synced.expect("Sync should succeed"), format!("origin/{}", base));
+        assert_eq!(head.id(), upstream, "The base branch should be fast-forwarded");
+        assert_eq!(head.parent_count(), 1, "No merge commit should be created");
+    }
+
+    #[test]
+    fn test_remote_notes_ref() {
+        assert_eq!(remote_notes_ref("refs/notes/gitx-metadata"), "refs/notes/origin/gitx-metadata");
+        assert_eq!(remote_notes_ref("gitx-metadata"), "refs/notes/origin/gitx-metadata");
+    }
+
     #[test]
     fn test_get_git_username() {
         let (_repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
@@ -855,4 +4322,37 @@ mod tests {
         // Restore original directory
         std::env::set_current_dir(original_dir).unwrap();
     }
+
+    #[test]
+    fn test_branch_namespace_falls_back_to_sanitized_username() {
+        let (_repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Without a fetched GitHub login, the namespace falls back to a sanitized user.name
+        let namespace = get_branch_namespace();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert_eq!(namespace, "Test-User");
+    }
+
+    #[test]
+    fn test_branch_namespace_without_username() {
+        let (repo, temp_dir) = create_test_repo().expect("Failed to create test repo");
+        // An empty local value hides any global user.name
+        repo.config().unwrap().set_str("user.name", "").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let username = get_git_username();
+        let namespace = get_branch_namespace();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert!(username.is_err());
+        assert_eq!(namespace, "unknown");
+    }
 }
\ No newline at end of file
@@ -1,10 +1,11 @@
+use std::time::Duration;
 use octocrab::Octocrab;
 use async_trait::async_trait;
 use crate::metadata::CommitMetadata;
 use crate::github_utils::{generate_pr_body, get_github_repo_from_remote};
 
 // Re-export commonly used items
-pub use crate::github_utils::{GitHubRepo, PRInfo, GitHubPRStatus, check_github_token};
+pub use crate::github_utils::{GitHubRepo, PRInfo, PRCreateOptions, GitHubPRStatus, RateLimit, ReviewDecision, check_github_token};
 
 /// Trait for GitHub API operations to enable dependency injection and mocking
 #[async_trait]
@@ -15,6 +16,7 @@ pub trait GitHubClientTrait {
         title: &str,
         body: &str,
         base_branch: &str,
+        draft: bool,
     ) -> Result<PRInfo, Box<dyn std::error::Error>>;
     
     async fn update_pr(
@@ -24,15 +26,197 @@ pub trait GitHubClientTrait {
         body: Option<&str>,
     ) -> Result<(), Box<dyn std::error::Error>>;
     
+    async fn fetch_pr_body(&self, pr_number: u64) -> Result<String, Box<dyn std::error::Error>>;
+    
     async fn get_pr_status(&self, pr_number: u64) -> Result<GitHubPRStatus, Box<dyn std::error::Error>>;
     
     async fn get_multiple_pr_statuses(&self, pr_numbers: &[u64]) -> Result<Vec<GitHubPRStatus>, Box<dyn std::error::Error>>;
+    
+    async fn set_draft(&self, pr_number: u64, draft: bool) -> Result<(), Box<dyn std::error::Error>>;
+    
+    async fn add_labels(&self, pr_number: u64, labels: &[String]) -> Result<(), Box<dyn std::error::Error>>;
+    
+    async fn request_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<(), Box<dyn std::error::Error>>;
+    
+    async fn request_team_reviewers(&self, pr_number: u64, teams: &[String]) -> Result<(), Box<dyn std::error::Error>>;
+    
+    async fn add_assignees(&self, pr_number: u64, assignees: &[String]) -> Result<(), Box<dyn std::error::Error>>;
+    
+    async fn close_pr(&self, pr_number: u64) -> Result<(), Box<dyn std::error::Error>>;
+    
+    async fn set_base(&self, pr_number: u64, base_branch: &str) -> Result<(), Box<dyn std::error::Error>>;
+    
+    async fn get_current_user(&self) -> Result<String, Box<dyn std::error::Error>>;
+    
+    async fn get_review_decision(&self, pr_number: u64) -> Result<ReviewDecision, Box<dyn std::error::Error>>;
+    
+    async fn find_open_pr_for_branch(&self, branch_name: &str) -> Result<Option<PRInfo>, Box<dyn std::error::Error>>;
+    
+    async fn list_open_prs(&self) -> Result<Vec<OpenPRInfo>, Box<dyn std::error::Error>>;
+    
+    async fn rate_limit(&self) -> Result<RateLimit, Box<dyn std::error::Error>>;
+}
+
+/// An open PR as listed by GitHub, with the branch it was opened from
+#[derive(Debug, Clone)]
+pub struct OpenPRInfo {
+    pub branch_name: String,
+    pub pr: PRInfo,
 }
 
 /// GitHub API client wrapper
 pub struct GitHubClient {
     octocrab: Octocrab,
     repo: crate::github_utils::GitHubRepo,
+    max_attempts: u32,
+}
+
+/// Longest we back off between attempts when GitHub gives no hint
+const MAX_BACKOFF_SECONDS: u64 = 60;
+
+/// HTTP method for a REST call made through `send_with_retry`
+#[derive(Debug, Clone, Copy)]
+enum RequestMethod {
+    Get,
+    Post,
+    Patch,
+}
+
+/// The parts of a pull request gitx keeps track of
+fn to_pr_info(pr: octocrab::models::pulls::PullRequest) -> PRInfo {
+    PRInfo {
+        number: pr.number,
+        url: pr.html_url.map(|u| u.to_string()).unwrap_or_default(),
+        title: pr.title.unwrap_or_default(),
+    }
+}
+
+/// Check whether a failed request is GitHub refusing a second PR for the same head branch
+fn is_pr_already_exists(error: &(dyn std::error::Error + 'static)) -> bool {
+    match error.downcast_ref::<octocrab::Error>() {
+        Some(octocrab::Error::GitHub { source, .. }) => {
+            is_pr_already_exists_response(source.status_code.as_u16(), source.errors.as_deref().unwrap_or_default())
+        }
+        _ => false,
+    }
+}
+
+/// Check a 422 validation response for the "A pull request already exists" error
+fn is_pr_already_exists_response(status: u16, errors: &[serde_json::Value]) -> bool {
+    status == 422
+        && errors.iter().any(|error| {
+            error.get("message")
+                .and_then(|m| m.as_str())
+                .is_some_and(|m| m.starts_with("A pull request already exists"))
+        })
+}
+
+/// Pull the review decision out of a GraphQL response
+///
+/// A `null` decision (e.g. no branch protection on the base) is `ReviewDecision::None`.
+fn parse_review_decision(response: &serde_json::Value) -> Result<ReviewDecision, Box<dyn std::error::Error>> {
+    if let Some(message) = response["errors"][0]["message"].as_str() {
+        return Err(format!("GitHub GraphQL error: {}", message).into());
+    }
+    
+    let pull_request = &response["data"]["repository"]["pullRequest"];
+    if pull_request.is_null() {
+        return Err("Pull request not found".into());
+    }
+    Ok(ReviewDecision::from_graphql(pull_request["reviewDecision"].as_str()))
+}
+
+/// Most PRs fetched by one GraphQL status query, keeping it well inside GitHub's query limits
+const GRAPHQL_PR_BATCH_SIZE: usize = 50;
+
+/// Build a GraphQL query fetching each PR's status under the alias `pr<number>`
+fn pr_statuses_query(pr_numbers: &[u64]) -> String {
+    let pull_requests: String = pr_numbers
+        .iter()
+        .map(|number| format!(" pr{0}: pullRequest(number: {0}) {{ ...status }}", number))
+        .collect();
+    
+    format!(
+        "query($owner: String!, $name: String!) {{ repository(owner: $owner, name: $name) {{{} }} }} \
+         fragment status on PullRequest {{ number state title url mergeable isDraft additions deletions changedFiles \
+         headRefName headRefOid baseRefName mergedAt }}",
+        pull_requests
+    )
+}
+
+/// Pull PR statuses out of a response to `pr_statuses_query`
+///
+/// PRs GitHub couldn't find are warned about and left out, as the REST path does.
+fn parse_pr_statuses(response: &serde_json::Value, pr_numbers: &[u64]) -> Result<Vec<GitHubPRStatus>, Box<dyn std::error::Error>> {
+    let repository = &response["data"]["repository"];
+    if repository.is_null() {
+        let message = response["errors"][0]["message"].as_str().unwrap_or("no repository in response");
+        return Err(format!("GitHub GraphQL error: {}", message).into());
+    }
+    
+    let mut statuses = Vec::new();
+    for &pr_number in pr_numbers {
+        let pr = &repository[format!("pr{}", pr_number)];
+        if pr.is_null() {
+            eprintln!("Warning: Failed to get status for PR #{}: not found", pr_number);
+            continue;
+        }
+        
+        let text = |field: &str| pr[field].as_str().unwrap_or_default().to_string();
+        statuses.push(GitHubPRStatus {
+            number: pr["number"].as_u64().unwrap_or(pr_number),
+            // Lower-cased to match the REST states: open, closed or merged
+            state: text("state").to_lowercase(),
+            title: text("title"),
+            url: text("url"),
+            mergeable: match pr["mergeable"].as_str() {
+                Some("MERGEABLE") => Some(true),
+                Some("CONFLICTING") => Some(false),
+                _ => None,
+            },
+            draft: pr["isDraft"].as_bool().unwrap_or(false),
+            additions: pr["additions"].as_u64(),
+            deletions: pr["deletions"].as_u64(),
+            changed_files: pr["changedFiles"].as_u64(),
+            review_decision: ReviewDecision::None,
+            head_branch: text("headRefName"),
+            head_sha: text("headRefOid"),
+            base_branch: text("baseRefName"),
+            merged_at: pr["mergedAt"].as_str().and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok()).map(|at| at.with_timezone(&chrono::Utc)),
+        });
+    }
+    
+    Ok(statuses)
+}
+
+/// How long to wait before retrying a response, or `None` if it isn't rate limited
+///
+/// 429s are always rate limits; 403s only count when GitHub says so via
+/// `Retry-After` or an exhausted `X-RateLimit-Remaining`. `Retry-After` wins,
+/// then `X-RateLimit-Reset` (epoch seconds), then exponential backoff.
+fn rate_limit_delay(
+    status: u16,
+    retry_after: Option<&str>,
+    remaining: Option<&str>,
+    reset: Option<&str>,
+    attempt: u32,
+    now: i64,
+) -> Option<Duration> {
+    let exhausted = remaining.is_some_and(|r| r.trim() == "0");
+    if status != 429 && !(status == 403 && (retry_after.is_some() || exhausted)) {
+        return None;
+    }
+    
+    if let Some(seconds) = retry_after.and_then(|v| v.trim().parse::<u64>().ok()) {
+        return Some(Duration::from_secs(seconds));
+    }
+    
+    if exhausted && let Some(reset) = reset.and_then(|v| v.trim().parse::<i64>().ok()) {
+        return Some(Duration::from_secs(reset.saturating_sub(now).max(0) as u64));
+    }
+    
+    let backoff = 1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX);
+    Some(Duration::from_secs(backoff.min(MAX_BACKOFF_SECONDS)))
 }
 
 #[async_trait]
@@ -43,8 +227,9 @@ impl GitHubClientTrait for GitHubClient {
         title: &str,
         body: &str,
         base_branch: &str,
+        draft: bool,
     ) -> Result<PRInfo, Box<dyn std::error::Error>> {
-        self.create_pr_impl(branch_name, title, body, base_branch).await
+        self.create_pr_impl(branch_name, title, body, base_branch, draft).await
     }
     
     async fn update_pr(
@@ -56,6 +241,10 @@ impl GitHubClientTrait for GitHubClient {
         self.update_pr_impl(pr_number, title, body).await
     }
     
+    async fn fetch_pr_body(&self, pr_number: u64) -> Result<String, Box<dyn std::error::Error>> {
+        self.fetch_pr_body_impl(pr_number).await
+    }
+    
     async fn get_pr_status(&self, pr_number: u64) -> Result<GitHubPRStatus, Box<dyn std::error::Error>> {
         self.get_pr_status_impl(pr_number).await
     }
@@ -63,6 +252,54 @@ impl GitHubClientTrait for GitHubClient {
     async fn get_multiple_pr_statuses(&self, pr_numbers: &[u64]) -> Result<Vec<GitHubPRStatus>, Box<dyn std::error::Error>> {
         self.get_multiple_pr_statuses_impl(pr_numbers).await
     }
+    
+    async fn set_draft(&self, pr_number: u64, draft: bool) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_draft_impl(pr_number, draft).await
+    }
+    
+    async fn add_labels(&self, pr_number: u64, labels: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        self.add_labels_impl(pr_number, labels).await
+    }
+    
+    async fn request_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        self.request_reviewers_impl(pr_number, reviewers).await
+    }
+    
+    async fn request_team_reviewers(&self, pr_number: u64, teams: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        self.request_team_reviewers_impl(pr_number, teams).await
+    }
+    
+    async fn add_assignees(&self, pr_number: u64, assignees: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        self.add_assignees_impl(pr_number, assignees).await
+    }
+    
+    async fn close_pr(&self, pr_number: u64) -> Result<(), Box<dyn std::error::Error>> {
+        self.close_pr_impl(pr_number).await
+    }
+    
+    async fn set_base(&self, pr_number: u64, base_branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_base_impl(pr_number, base_branch).await
+    }
+    
+    async fn get_current_user(&self) -> Result<String, Box<dyn std::error::Error>> {
+        self.get_current_user_impl().await
+    }
+    
+    async fn get_review_decision(&self, pr_number: u64) -> Result<ReviewDecision, Box<dyn std::error::Error>> {
+        self.get_review_decision_impl(pr_number).await
+    }
+    
+    async fn find_open_pr_for_branch(&self, branch_name: &str) -> Result<Option<PRInfo>, Box<dyn std::error::Error>> {
+        self.find_open_pr_for_branch_impl(branch_name).await
+    }
+    
+    async fn list_open_prs(&self) -> Result<Vec<OpenPRInfo>, Box<dyn std::error::Error>> {
+        self.list_open_prs_impl().await
+    }
+    
+    async fn rate_limit(&self) -> Result<RateLimit, Box<dyn std::error::Error>> {
+        self.rate_limit_impl().await
+    }
 }
 
 impl GitHubClient {
@@ -72,67 +309,343 @@ impl GitHubClient {
         let token = crate::config::get_github_token()
             .ok_or("GitHub token not configured. Run 'gitx init' to set up.")?;
         
-        // Initialize octocrab with token
-        let octocrab = Octocrab::builder()
-            .personal_token(token)
-            .build()?;
+        // Initialize octocrab with token, honouring proxies and the Enterprise API URL
+        let octocrab = crate::http_client::build_octocrab(&crate::http_client::github_api_base_url(), Some(token))?;
         
         // Get repository info from git remote
         let repo = Self::get_github_repo_from_remote()?;
         
-        Ok(Self { octocrab, repo })
+        let max_attempts = crate::config::get_github_max_attempts();
+        
+        Ok(Self { octocrab, repo, max_attempts })
+    }
+    
+    /// Send a REST request, backing off and retrying while GitHub rate-limits it
+    async fn send_with_retry<R: octocrab::FromResponse>(
+        &self,
+        method: RequestMethod,
+        route: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<R, Box<dyn std::error::Error>> {
+        let max_attempts = self.max_attempts.max(1);
+        
+        for attempt in 1..=max_attempts {
+            match body {
+                Some(body) => tracing::debug!("GitHub request: {:?} {} {}", method, route, body),
+                None => tracing::debug!("GitHub request: {:?} {}", method, route),
+            }
+            let response = match method {
+                RequestMethod::Get => self.octocrab._get(route).await?,
+                RequestMethod::Post => self.octocrab._post(route, body).await?,
+                RequestMethod::Patch => self.octocrab._patch(route, body).await?,
+            };
+            tracing::debug!("GitHub response: {} for {:?} {}", response.status(), method, route);
+            
+            let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok());
+            let delay = rate_limit_delay(
+                response.status().as_u16(),
+                header("retry-after"),
+                header("x-ratelimit-remaining"),
+                header("x-ratelimit-reset"),
+                attempt,
+                chrono::Utc::now().timestamp(),
+            );
+            
+            match delay {
+                None => {
+                    let response = octocrab::map_github_error(response).await?;
+                    return Ok(R::from_response(response).await?);
+                }
+                Some(delay) if attempt < max_attempts => {
+                    crate::progress::eprintln(format!(
+                        "GitHub rate limit hit; retrying in {}s (attempt {}/{})",
+                        delay.as_secs(),
+                        attempt + 1,
+                        max_attempts
+                    ));
+                    tokio::time::sleep(delay).await;
+                }
+                Some(_) => {}
+            }
+        }
+        
+        Err(format!(
+            "GitHub rate limit still exceeded after {} attempts; try again later or raise gitx.github.maxAttempts",
+            max_attempts
+        ).into())
+    }
+    
+    /// REST route for this repository's pull requests
+    fn pulls_route(&self) -> String {
+        format!("/repos/{}/{}/pulls", self.repo.owner, self.repo.name)
     }
     
     /// Create a new pull request (implementation)
+    #[tracing::instrument(name = "create_pr", level = "debug", skip_all, fields(endpoint = %self.pulls_route(), branch = branch_name, base = base_branch))]
     pub async fn create_pr_impl(
         &self,
         branch_name: &str,
         title: &str,
         body: &str,
         base_branch: &str,
+        draft: bool,
     ) -> Result<PRInfo, Box<dyn std::error::Error>> {
-        println!("Creating PR: {} -> {}", branch_name, base_branch);
+        tracing::info!("Creating PR: {} -> {}", branch_name, base_branch);
         
-        let pr = self
-            .octocrab
-            .pulls(&self.repo.owner, &self.repo.name)
-            .create(title, branch_name, base_branch)
-            .body(body)
-            .send()
+        let request = serde_json::json!({
+            "title": title,
+            "head": branch_name,
+            "base": base_branch,
+            "body": body,
+            "draft": draft,
+        });
+        match self
+            .send_with_retry::<octocrab::models::pulls::PullRequest>(RequestMethod::Post, &self.pulls_route(), Some(&request))
+            .await
+        {
+            Ok(pr) => return Ok(to_pr_info(pr)),
+            Err(e) if is_pr_already_exists(e.as_ref()) => {}
+            Err(e) => return Err(e),
+        }
+        
+        // The branch already has an open PR (e.g. from an earlier run): adopt and update it
+        let existing = self.find_open_pr_for_branch_impl(branch_name).await?.ok_or_else(|| {
+            format!("GitHub reports a PR already exists for {}, but no open PR was found", branch_name)
+        })?;
+        tracing::info!("PR #{} already exists for {}, updating it instead", existing.number, branch_name);
+        self.update_pr_impl(existing.number, Some(title), Some(body)).await?;
+        
+        Ok(PRInfo { title: title.to_string(), ..existing })
+    }
+    
+    /// Find the open PR whose head is `branch_name` (implementation)
+    ///
+    /// `branch_name` may already be namespaced as `owner:branch`; otherwise the repository owner is assumed.
+    pub async fn find_open_pr_for_branch_impl(&self, branch_name: &str) -> Result<Option<PRInfo>, Box<dyn std::error::Error>> {
+        let head = if branch_name.contains(':') {
+            branch_name.to_string()
+        } else {
+            format!("{}:{}", self.repo.owner, branch_name)
+        };
+        let route = format!(
+            "{}?state=open&head={}",
+            self.pulls_route(),
+            url::form_urlencoded::byte_serialize(head.as_bytes()).collect::<String>()
+        );
+        let prs: Vec<octocrab::models::pulls::PullRequest> = self
+            .send_with_retry(RequestMethod::Get, &route, None)
             .await?;
         
-        Ok(PRInfo {
-            number: pr.number,
-            url: pr.html_url.map(|u| u.to_string()).unwrap_or_default(),
-            title: pr.title.unwrap_or_default(),
-        })
+        Ok(prs.into_iter().next().map(to_pr_info))
+    }
+    
+    /// List every open pull request in the repository (implementation)
+    pub async fn list_open_prs_impl(&self) -> Result<Vec<OpenPRInfo>, Box<dyn std::error::Error>> {
+        const PER_PAGE: usize = 100;
+        let mut open_prs = Vec::new();
+        
+        for page in 1.. {
+            let route = format!("{}?state=open&per_page={}&page={}", self.pulls_route(), PER_PAGE, page);
+            let prs: Vec<octocrab::models::pulls::PullRequest> = self
+                .send_with_retry(RequestMethod::Get, &route, None)
+                .await?;
+            let last_page = prs.len() < PER_PAGE;
+            
+            open_prs.extend(prs.into_iter().map(|pr| OpenPRInfo {
+                branch_name: pr.head.ref_field.clone(),
+                pr: to_pr_info(pr),
+            }));
+            if last_page {
+                break;
+            }
+        }
+        
+        Ok(open_prs)
     }
     
     /// Update an existing pull request (implementation)
+    #[tracing::instrument(name = "update_pr", level = "debug", skip_all, fields(endpoint = %format!("{}/{}", self.pulls_route(), pr_number)))]
     pub async fn update_pr_impl(
         &self,
         pr_number: u64,
         title: Option<&str>,
         body: Option<&str>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("Updating PR #{}", pr_number);
+        tracing::info!("Updating PR #{}", pr_number);
         
-        let pulls = self.octocrab.pulls(&self.repo.owner, &self.repo.name);
-        let mut update = pulls.update(pr_number);
+        let mut update = serde_json::Map::new();
         
         if let Some(title) = title {
-            update = update.title(title);
+            update.insert("title".to_string(), title.into());
         }
         
         if let Some(body) = body {
-            update = update.body(body);
+            update.insert("body".to_string(), body.into());
         }
         
-        update.send().await?;
+        let route = format!("{}/{}", self.pulls_route(), pr_number);
+        let _: octocrab::models::pulls::PullRequest = self
+            .send_with_retry(RequestMethod::Patch, &route, Some(&update.into()))
+            .await?;
         
         Ok(())
     }
     
+    /// Get a pull request's current description (implementation)
+    pub async fn fetch_pr_body_impl(&self, pr_number: u64) -> Result<String, Box<dyn std::error::Error>> {
+        let route = format!("{}/{}", self.pulls_route(), pr_number);
+        let pr: octocrab::models::pulls::PullRequest = self
+            .send_with_retry(RequestMethod::Get, &route, None)
+            .await?;
+        
+        Ok(pr.body.unwrap_or_default())
+    }
+    
+    /// Get the login of the user the token belongs to (implementation)
+    pub async fn get_current_user_impl(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let user = self.octocrab.current().user().await?;
+        Ok(user.login)
+    }
+    
+    /// Get the core REST API rate limit (implementation)
+    /// Checking `/rate_limit` doesn't count against the limit itself
+    pub async fn rate_limit_impl(&self) -> Result<RateLimit, Box<dyn std::error::Error>> {
+        let rate_limit: octocrab::models::RateLimit = self
+            .send_with_retry(RequestMethod::Get, "/rate_limit", None)
+            .await?;
+        let core = rate_limit.resources.core;
+        
+        Ok(RateLimit {
+            remaining: core.remaining as u64,
+            limit: core.limit as u64,
+            reset: chrono::DateTime::from_timestamp(core.reset as i64, 0).unwrap_or_default(),
+        })
+    }
+    
+    /// Get a pull request's review decision via GraphQL (implementation)
+    pub async fn get_review_decision_impl(&self, pr_number: u64) -> Result<ReviewDecision, Box<dyn std::error::Error>> {
+        let query = serde_json::json!({
+            "query": "query($owner: String!, $name: String!, $number: Int!) { repository(owner: $owner, name: $name) { pullRequest(number: $number) { reviewDecision } } }",
+            "variables": {
+                "owner": self.repo.owner,
+                "name": self.repo.name,
+                "number": pr_number,
+            },
+        });
+        let response: serde_json::Value = self
+            .send_with_retry(RequestMethod::Post, "/graphql", Some(&query))
+            .await?;
+        
+        parse_review_decision(&response)
+    }
+    
+    /// Close a pull request without merging it (implementation)
+    pub async fn close_pr_impl(&self, pr_number: u64) -> Result<(), Box<dyn std::error::Error>> {
+        println!("Closing PR #{}", pr_number);
+        
+        self.octocrab
+            .pulls(&self.repo.owner, &self.repo.name)
+            .update(pr_number)
+            .state(octocrab::params::pulls::State::Closed)
+            .send()
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Change the branch a pull request targets (implementation)
+    pub async fn set_base_impl(&self, pr_number: u64, base_branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+        crate::progress::println(format!("Retargeting PR #{} onto {}", pr_number, base_branch));
+        
+        self.octocrab
+            .pulls(&self.repo.owner, &self.repo.name)
+            .update(pr_number)
+            .base(base_branch)
+            .send()
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Convert a pull request to draft or mark it ready for review (implementation)
+    /// The REST API can't change draft state, so this goes through GraphQL
+    pub async fn set_draft_impl(
+        &self,
+        pr_number: u64,
+        draft: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let pr = self
+            .octocrab
+            .pulls(&self.repo.owner, &self.repo.name)
+            .get(pr_number)
+            .await?;
+        let node_id = pr.node_id.ok_or("PR has no GraphQL node ID")?;
+        
+        let mutation = if draft { "convertPullRequestToDraft" } else { "markPullRequestReadyForReview" };
+        let query = format!(
+            "mutation($id: ID!) {{ {}(input: {{ pullRequestId: $id }}) {{ pullRequest {{ isDraft }} }} }}",
+            mutation
+        );
+        
+        let response: serde_json::Value = self
+            .octocrab
+            .graphql(&serde_json::json!({ "query": query, "variables": { "id": node_id } }))
+            .await?;
+        
+        if let Some(errors) = response.get("errors") {
+            return Err(format!("GraphQL {} failed: {}", mutation, errors).into());
+        }
+        
+        Ok(())
+    }
+    
+    /// Add labels to a pull request (implementation)
+    pub async fn add_labels_impl(&self, pr_number: u64, labels: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        self.octocrab
+            .issues(&self.repo.owner, &self.repo.name)
+            .add_labels(pr_number, labels)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Request reviews on a pull request (implementation)
+    pub async fn request_reviewers_impl(&self, pr_number: u64, reviewers: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        // The endpoint responds with the full PR, so don't rely on octocrab's typed wrapper
+        let route = format!("/repos/{}/{}/pulls/{}/requested_reviewers", self.repo.owner, self.repo.name, pr_number);
+        let _: serde_json::Value = self
+            .octocrab
+            .post(route, Some(&serde_json::json!({ "reviewers": reviewers })))
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Request reviews from teams, given as `org/team` or a bare team slug (implementation)
+    pub async fn request_team_reviewers_impl(&self, pr_number: u64, teams: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        // The API takes slugs within the repository's organization
+        let slugs: Vec<&str> = teams
+            .iter()
+            .map(|team| team.rsplit('/').next().unwrap_or(team))
+            .collect();
+        let route = format!("{}/{}/requested_reviewers", self.pulls_route(), pr_number);
+        let _: serde_json::Value = self
+            .send_with_retry(RequestMethod::Post, &route, Some(&serde_json::json!({ "team_reviewers": slugs })))
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Assign users to a pull request (implementation)
+    pub async fn add_assignees_impl(&self, pr_number: u64, assignees: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let assignees: Vec<&str> = assignees.iter().map(|a| a.as_str()).collect();
+        self.octocrab
+            .issues(&self.repo.owner, &self.repo.name)
+            .add_assignees(pr_number, &assignees)
+            .await?;
+        
+        Ok(())
+    }
     
     /// Get GitHub repository info from git remote
     fn get_github_repo_from_remote() -> Result<crate::github_utils::GitHubRepo, Box<dyn std::error::Error>> {
@@ -143,29 +656,81 @@ impl GitHubClient {
 
 impl GitHubClient {
     /// Get PR status from GitHub (implementation)
+    #[tracing::instrument(name = "get_pr_status", level = "debug", skip_all, fields(endpoint = %format!("{}/{}", self.pulls_route(), pr_number)))]
     pub async fn get_pr_status_impl(&self, pr_number: u64) -> Result<GitHubPRStatus, Box<dyn std::error::Error>> {
-        let pr = self
-            .octocrab
-            .pulls(&self.repo.owner, &self.repo.name)
-            .get(pr_number)
+        let route = format!("{}/{}", self.pulls_route(), pr_number);
+        let pr: octocrab::models::pulls::PullRequest = self
+            .send_with_retry(RequestMethod::Get, &route, None)
             .await?;
         
+        // GitHub reports merged PRs as closed; merged_at tells them apart
+        let state = if pr.merged_at.is_some() {
+            "merged".to_string()
+        } else {
+            pr.state.map(|s| format!("{:?}", s).to_lowercase()).unwrap_or_default()
+        };
+        
         Ok(GitHubPRStatus {
             number: pr.number,
-            state: pr.state.map(|s| format!("{:?}", s).to_lowercase()).unwrap_or_default(),
+            state,
             title: pr.title.unwrap_or_default(),
             url: pr.html_url.map(|u| u.to_string()).unwrap_or_default(),
             mergeable: pr.mergeable,
             draft: pr.draft.unwrap_or(false),
+            additions: pr.additions,
+            deletions: pr.deletions,
+            changed_files: pr.changed_files,
+            review_decision: ReviewDecision::None,
+            head_branch: pr.head.ref_field,
+            head_sha: pr.head.sha,
+            base_branch: pr.base.ref_field,
+            merged_at: pr.merged_at,
         })
     }
 
     /// Get multiple PR statuses efficiently (implementation)
+    /// One GraphQL query per batch of PRs, falling back to a REST call per PR if GraphQL fails
     pub async fn get_multiple_pr_statuses_impl(&self, pr_numbers: &[u64]) -> Result<Vec<GitHubPRStatus>, Box<dyn std::error::Error>> {
         let mut statuses = Vec::new();
         
-        // Note: In a production system, you'd want to batch these requests
-        // For now, we'll do them sequentially to avoid rate limiting
+        for batch in pr_numbers.chunks(GRAPHQL_PR_BATCH_SIZE) {
+            // The error isn't Send, so it must be dropped before the REST fallback awaits
+            let batch_statuses = match self.get_pr_statuses_graphql(batch).await {
+                Ok(batch_statuses) => Some(batch_statuses),
+                Err(e) => {
+                    tracing::debug!("Batched GraphQL status query failed, falling back to REST: {}", e);
+                    None
+                }
+            };
+            match batch_statuses {
+                Some(batch_statuses) => statuses.extend(batch_statuses),
+                None => statuses.extend(self.get_pr_statuses_rest(batch).await),
+            }
+        }
+        
+        Ok(statuses)
+    }
+    
+    /// Fetch the statuses of several PRs in a single GraphQL query
+    async fn get_pr_statuses_graphql(&self, pr_numbers: &[u64]) -> Result<Vec<GitHubPRStatus>, Box<dyn std::error::Error>> {
+        let query = serde_json::json!({
+            "query": pr_statuses_query(pr_numbers),
+            "variables": {
+                "owner": self.repo.owner,
+                "name": self.repo.name,
+            },
+        });
+        let response: serde_json::Value = self
+            .send_with_retry(RequestMethod::Post, "/graphql", Some(&query))
+            .await?;
+        
+        parse_pr_statuses(&response, pr_numbers)
+    }
+    
+    /// Fetch the statuses of several PRs with one REST call each
+    async fn get_pr_statuses_rest(&self, pr_numbers: &[u64]) -> Vec<GitHubPRStatus> {
+        let mut statuses = Vec::new();
+        
         for &pr_number in pr_numbers {
             match self.get_pr_status_impl(pr_number).await {
                 Ok(status) => statuses.push(status),
@@ -175,7 +740,265 @@ impl GitHubClient {
             }
         }
         
-        Ok(statuses)
+        statuses
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client_for(server: &MockServer, max_attempts: u32) -> GitHubClient {
+        let octocrab = Octocrab::builder()
+            .personal_token("test-token".to_string())
+            .base_uri(server.uri())
+            .unwrap()
+            .build()
+            .unwrap();
+        let repo = GitHubRepo { owner: "octo".to_string(), name: "repo".to_string() };
+        
+        GitHubClient { octocrab, repo, max_attempts }
+    }
+
+    fn rate_limited() -> ResponseTemplate {
+        ResponseTemplate::new(403)
+            .insert_header("retry-after", "0")
+            .set_body_json(serde_json::json!({ "message": "API rate limit exceeded" }))
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_retries_after_rate_limit() {
+        let server = MockServer::start().await;
+        
+        Mock::given(method("POST"))
+            .and(path("/repos/octo/repo/pulls"))
+            .respond_with(rate_limited())
+            .up_to_n_times(2)
+            .with_priority(1)
+            .expect(2)
+            .mount(&server)
+            .await;
+        
+        Mock::given(method("POST"))
+            .and(path("/repos/octo/repo/pulls"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "url": "https://api.github.com/repos/octo/repo/pulls/7",
+                "id": 1,
+                "number": 7,
+                "title": "Add feature",
+                "html_url": "https://github.com/octo/repo/pull/7",
+                "head": { "ref": "feature", "sha": "abc123" },
+                "base": { "ref": "main", "sha": "def456" },
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let client = client_for(&server, 3);
+        let pr = client.create_pr_impl("feature", "Add feature", "", "main", false).await.unwrap();
+        
+        assert_eq!(pr.number, 7);
+        assert_eq!(pr.url, "https://github.com/octo/repo/pull/7");
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_attempts() {
+        let server = MockServer::start().await;
+        
+        Mock::given(method("GET"))
+            .and(path("/repos/octo/repo/pulls/7"))
+            .respond_with(rate_limited())
+            .expect(2)
+            .mount(&server)
+            .await;
+        
+        let client = client_for(&server, 2);
+        let err = client.get_pr_status_impl(7).await.unwrap_err();
+        
+        assert!(err.to_string().contains("rate limit still exceeded after 2 attempts"), "{}", err);
+    }
+
+    /// Writer that keeps formatted tracing output for assertions
+    #[derive(Clone, Default)]
+    struct CapturedOutput(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_emits_span() {
+        let server = MockServer::start().await;
+        
+        Mock::given(method("POST"))
+            .and(path("/repos/octo/repo/pulls"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "url": "https://api.github.com/repos/octo/repo/pulls/7",
+                "id": 1,
+                "number": 7,
+                "head": { "ref": "feature", "sha": "abc123" },
+                "base": { "ref": "main", "sha": "def456" },
+            })))
+            .mount(&server)
+            .await;
+        
+        let output = CapturedOutput::default();
+        let writer = output.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+        
+        let client = client_for(&server, 1);
+        client.create_pr_impl("feature", "Add feature", "", "main", false).await.unwrap();
+        
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("create_pr{endpoint=/repos/octo/repo/pulls branch=\"feature\" base=\"main\"}"), "{}", output);
+        assert!(output.contains("close time.busy="), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_adopts_existing_pr() {
+        use wiremock::matchers::query_param;
+        
+        let server = MockServer::start().await;
+        let pr_json = serde_json::json!({
+            "url": "https://api.github.com/repos/octo/repo/pulls/7",
+            "id": 1,
+            "number": 7,
+            "title": "Old title",
+            "html_url": "https://github.com/octo/repo/pull/7",
+            "head": { "ref": "feature", "sha": "abc123" },
+            "base": { "ref": "main", "sha": "def456" },
+        });
+        
+        Mock::given(method("POST"))
+            .and(path("/repos/octo/repo/pulls"))
+            .respond_with(ResponseTemplate::new(422).set_body_json(serde_json::json!({
+                "message": "Validation Failed",
+                "errors": [{ "resource": "PullRequest", "code": "custom", "message": "A pull request already exists for octo:feature." }],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octo/repo/pulls"))
+            .and(query_param("head", "octo:feature"))
+            .and(query_param("state", "open"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([pr_json])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/octo/repo/pulls/7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pr_json.clone()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let client = client_for(&server, 1);
+        let pr = client.create_pr_impl("feature", "Add feature", "Body", "main", false).await.unwrap();
+        
+        assert_eq!(pr.number, 7);
+        assert_eq!(pr.title, "Add feature");
+        assert_eq!(pr.url, "https://github.com/octo/repo/pull/7");
+    }
+
+    #[test]
+    fn test_is_pr_already_exists_response() {
+        let exists = serde_json::json!({ "message": "A pull request already exists for octo:feature." });
+        let other = serde_json::json!({ "message": "No commits between main and feature" });
+        
+        assert!(is_pr_already_exists_response(422, std::slice::from_ref(&exists)));
+        assert!(!is_pr_already_exists_response(422, &[other]));
+        assert!(!is_pr_already_exists_response(403, &[exists]));
+        assert!(!is_pr_already_exists_response(422, &[]));
+    }
+
+    #[test]
+    fn test_rate_limit_delay() {
+        // Plain permission errors are not retried
+        assert_eq!(rate_limit_delay(403, None, Some("12"), None, 1, 0), None);
+        assert_eq!(rate_limit_delay(404, None, None, None, 1, 0), None);
+        
+        assert_eq!(rate_limit_delay(403, Some("5"), None, None, 1, 0), Some(Duration::from_secs(5)));
+        assert_eq!(rate_limit_delay(403, None, Some("0"), Some("1030"), 1, 1000), Some(Duration::from_secs(30)));
+        assert_eq!(rate_limit_delay(429, None, None, None, 3, 0), Some(Duration::from_secs(4)));
+        assert_eq!(rate_limit_delay(429, None, None, None, 20, 0), Some(Duration::from_secs(MAX_BACKOFF_SECONDS)));
+    }
+
+    #[test]
+    fn test_parse_review_decision() {
+        let response = |decision: serde_json::Value| serde_json::json!({
+            "data": { "repository": { "pullRequest": { "reviewDecision": decision } } }
+        });
+        
+        assert_eq!(parse_review_decision(&response("APPROVED".into())).unwrap(), ReviewDecision::Approved);
+        assert_eq!(parse_review_decision(&response("CHANGES_REQUESTED".into())).unwrap(), ReviewDecision::ChangesRequested);
+        assert_eq!(parse_review_decision(&response("REVIEW_REQUIRED".into())).unwrap(), ReviewDecision::ReviewRequired);
+        // Repos without branch protection report no decision
+        assert_eq!(parse_review_decision(&response(serde_json::Value::Null)).unwrap(), ReviewDecision::None);
+        
+        let not_found = serde_json::json!({ "data": { "repository": { "pullRequest": null } } });
+        assert!(parse_review_decision(&not_found).is_err());
+        let error = serde_json::json!({ "errors": [{ "message": "Bad credentials" }] });
+        assert!(parse_review_decision(&error).unwrap_err().to_string().contains("Bad credentials"));
+    }
+
+    #[tokio::test]
+    async fn test_multiple_pr_statuses_use_one_graphql_query() {
+        let server = MockServer::start().await;
+        let pr_numbers = [1, 2, 3, 4, 5];
+        let repository: serde_json::Map<String, serde_json::Value> = pr_numbers
+            .iter()
+            .map(|number| (format!("pr{}", number), serde_json::json!({
+                "number": number,
+                "state": if *number == 5 { "MERGED" } else { "OPEN" },
+                "title": format!("PR {}", number),
+                "url": format!("https://github.com/octo/repo/pull/{}", number),
+                "mergeable": if *number == 2 { "CONFLICTING" } else { "MERGEABLE" },
+                "isDraft": *number == 3,
+                "additions": 10,
+                "deletions": 2,
+                "changedFiles": 1,
+                "headRefName": format!("feature-{}", number),
+                "headRefOid": "abc123",
+                "baseRefName": "main",
+                "mergedAt": if *number == 5 { serde_json::json!("2024-01-01T00:00:00Z") } else { serde_json::Value::Null },
+            })))
+            .collect();
+        
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "repository": repository }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let client = client_for(&server, 1);
+        let statuses = client.get_multiple_pr_statuses_impl(&pr_numbers).await.unwrap();
+        
+        assert_eq!(statuses.iter().map(|status| status.number).collect::<Vec<_>>(), pr_numbers);
+        assert_eq!(statuses[0].state, "open");
+        assert_eq!(statuses[1].mergeable, Some(false));
+        assert!(statuses[2].draft);
+        assert_eq!(statuses[4].state, "merged");
+        assert!(statuses[4].merged_at.is_some());
+        assert_eq!(statuses[0].head_branch, "feature-1");
+    }
+}
\ No newline at end of file
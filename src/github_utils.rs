@@ -1,7 +1,28 @@
+use std::path::Path;
+use std::sync::OnceLock;
 use serde::{Deserialize, Serialize};
 use crate::metadata::CommitMetadata;
 use crate::git_utils::GitUtils;
 
+/// The authenticated user's login, fetched at most once per run
+static GITHUB_LOGIN: OnceLock<String> = OnceLock::new();
+
+/// Get the authenticated user's GitHub login, calling `/user` only the first time
+pub async fn get_github_login() -> Result<String, Box<dyn std::error::Error>> {
+    if let Some(login) = GITHUB_LOGIN.get() {
+        return Ok(login.clone());
+    }
+    
+    let github_client = crate::client_factory::create_github_client().await?;
+    let login = github_client.get_current_user().await?;
+    Ok(GITHUB_LOGIN.get_or_init(|| login).clone())
+}
+
+/// The GitHub login, if `get_github_login` has already fetched it
+pub fn cached_github_login() -> Option<String> {
+    GITHUB_LOGIN.get().cloned()
+}
+
 /// GitHub repository information
 #[derive(Debug, Clone)]
 pub struct GitHubRepo {
@@ -17,6 +38,41 @@ pub struct PRInfo {
     pub title: String,
 }
 
+/// Options applied when creating a new PR
+#[derive(Debug, Clone, Default)]
+pub struct PRCreateOptions {
+    pub draft: bool,
+    pub labels: Vec<String>,
+    pub reviewers: Vec<String>,
+    pub assignees: Vec<String>,
+    /// Review the title and body in an editor before creating the PR
+    pub edit: bool,
+    /// Don't push or open a PR for a commit that conflicts with its base
+    pub skip_conflicts: bool,
+    /// Open the PR against this branch instead of the auto-detected base
+    pub base: Option<String>,
+    /// Overwrite PR branches that diverged on the remote (also `gitx.push.forceUpdate`)
+    pub force: bool,
+    /// Skip merge commits in the stack instead of refusing to process it
+    pub allow_merges: bool,
+    /// Push the branch and record it, but don't open a PR yet
+    pub push_only: bool,
+}
+
+impl PRCreateOptions {
+    /// Add the configured default reviewers (`gitx.pr.reviewers`) ahead of the requested ones
+    pub fn with_default_reviewers(mut self) -> Self {
+        let mut reviewers = crate::config::get_default_reviewers();
+        for reviewer in self.reviewers.drain(..) {
+            if !reviewers.contains(&reviewer) {
+                reviewers.push(reviewer);
+            }
+        }
+        self.reviewers = reviewers;
+        self
+    }
+}
+
 /// GitHub PR status information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubPRStatus {
@@ -26,19 +82,292 @@ pub struct GitHubPRStatus {
     pub url: String,
     pub mergeable: Option<bool>,
     pub draft: bool,
+    /// Size of the PR, when the API reports it
+    #[serde(default)]
+    pub additions: Option<u64>,
+    #[serde(default)]
+    pub deletions: Option<u64>,
+    #[serde(default)]
+    pub changed_files: Option<u64>,
+    /// Filled in separately from `GitHubClientTrait::get_review_decision`
+    #[serde(default)]
+    pub review_decision: ReviewDecision,
+    /// Branch the PR is opened from and the commit it points at
+    #[serde(default)]
+    pub head_branch: String,
+    #[serde(default)]
+    pub head_sha: String,
+    /// Branch the PR merges into
+    #[serde(default)]
+    pub base_branch: String,
+    /// When the PR was merged, if it has been
+    #[serde(default)]
+    pub merged_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Overall review state of a PR, as GraphQL's `reviewDecision` reports it
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    ReviewRequired,
+    /// No decision, e.g. the base branch has no review requirements
+    #[default]
+    None,
 }
 
-/// Generate PR body content from commit metadata
-pub fn generate_pr_body(metadata: &CommitMetadata, commit_message: &str) -> String {
+impl ReviewDecision {
+    /// Parse GraphQL's `reviewDecision` value (`null` when there is none)
+    pub fn from_graphql(value: Option<&str>) -> Self {
+        match value {
+            Some("APPROVED") => ReviewDecision::Approved,
+            Some("CHANGES_REQUESTED") => ReviewDecision::ChangesRequested,
+            Some("REVIEW_REQUIRED") => ReviewDecision::ReviewRequired,
+            _ => ReviewDecision::None,
+        }
+    }
+}
+
+/// Requests left in GitHub's core REST API rate limit, and when it resets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub remaining: u64,
+    pub limit: u64,
+    pub reset: chrono::DateTime<chrono::Utc>,
+}
+
+/// Where a PR template goes in the generated body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PRTemplateMode {
+    /// Template first, followed by the full generated body
+    Prepend,
+    /// Template takes the place of the commit description
+    ReplaceDescription,
+}
+
+impl PRTemplateMode {
+    /// Parse a `gitx.pr.templateMode` value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "prepend" => Some(Self::Prepend),
+            "replace-description" => Some(Self::ReplaceDescription),
+            _ => None,
+        }
+    }
+}
+
+/// A repository's pull request template
+#[derive(Debug, Clone, PartialEq)]
+pub struct PRTemplate {
+    pub content: String,
+    pub mode: PRTemplateMode,
+}
+
+/// Locations GitHub looks for a PR template, relative to the repository root
+const PR_TEMPLATE_PATHS: &[&str] = &[
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    ".github/pull_request_template.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+];
+
+/// Find the PR template under `repo_root`, or at `custom_path` (relative to the root) if given
+pub fn find_pr_template(repo_root: &Path, custom_path: Option<&str>, mode: PRTemplateMode) -> Option<PRTemplate> {
+    let content = match custom_path {
+        Some(path) => std::fs::read_to_string(repo_root.join(path)).ok(),
+        None => PR_TEMPLATE_PATHS
+            .iter()
+            .find_map(|path| std::fs::read_to_string(repo_root.join(path)).ok()),
+    }?;
+    
+    let content = content.trim().to_string();
+    (!content.is_empty()).then_some(PRTemplate { content, mode })
+}
+
+/// Load the PR template for the current repository, honoring `gitx.pr.template` and `gitx.pr.templateMode`
+pub fn load_pr_template() -> Option<PRTemplate> {
+    let repo = git2::Repository::open(".").ok()?;
+    let root = repo.workdir()?;
+    find_pr_template(root, crate::config::get_pr_template().as_deref(), crate::config::get_pr_template_mode())
+}
+
+/// Locations GitHub looks for a CODEOWNERS file, relative to the repository root
+const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Owners CODEOWNERS assigns to a set of paths: users by login, teams as `org/team`
+#[derive(Debug, Default, PartialEq)]
+pub struct CodeOwners {
+    pub users: Vec<String>,
+    pub teams: Vec<String>,
+}
+
+/// A CODEOWNERS rule: a path pattern and the owners of whatever it matches
+struct CodeOwnersRule {
+    pattern: regex::Regex,
+    owners: Vec<String>,
+}
+
+/// Translate a CODEOWNERS (gitignore-style) pattern into a regex over repository-relative paths
+///
+/// Patterns containing a `/` other than a trailing one are anchored at the root; others match at
+/// any depth. A pattern that matches a directory also matches everything below it.
+fn codeowners_pattern_regex(pattern: &str) -> Option<regex::Regex> {
+    let directory_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+    
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut rest = trimmed;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            regex.push_str("(?:.*/)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = after;
+        } else {
+            match c {
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                _ => regex.push_str(&regex::escape(&c.to_string())),
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    regex.push_str(if directory_only { "/.*$" } else { "(?:/.*)?$" });
+    
+    regex::Regex::new(&regex).ok()
+}
+
+/// Parse CODEOWNERS content into rules, in file order
+fn parse_codeowners(content: &str) -> Vec<CodeOwnersRule> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pattern = codeowners_pattern_regex(fields.next()?)?;
+            let owners = fields
+                .take_while(|field| !field.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+            Some(CodeOwnersRule { pattern, owners })
+        })
+        .collect()
+}
+
+/// Owners of `paths` according to CODEOWNERS `content`
+/// For each path the last matching rule wins, as on GitHub. Email owners can't be requested and are skipped
+pub fn codeowners_from_content(content: &str, paths: &[String]) -> CodeOwners {
+    let rules = parse_codeowners(content);
+    let mut code_owners = CodeOwners::default();
+    
+    for path in paths {
+        let Some(rule) = rules.iter().rev().find(|rule| rule.pattern.is_match(path)) else {
+            continue;
+        };
+        for owner in rule.owners.iter().filter_map(|owner| owner.strip_prefix('@')) {
+            let owners = if owner.contains('/') { &mut code_owners.teams } else { &mut code_owners.users };
+            if !owners.iter().any(|existing| existing == owner) {
+                owners.push(owner.to_string());
+            }
+        }
+    }
+    
+    code_owners
+}
+
+/// Owners of `paths` according to the current repository's CODEOWNERS file, if it has one
+pub fn codeowners_for(paths: &[String]) -> CodeOwners {
+    let content = git2::Repository::open(".").ok().and_then(|repo| {
+        let root = repo.workdir()?.to_path_buf();
+        CODEOWNERS_PATHS
+            .iter()
+            .find_map(|path| std::fs::read_to_string(root.join(path)).ok())
+    });
+    
+    content
+        .map(|content| codeowners_from_content(&content, paths))
+        .unwrap_or_default()
+}
+
+/// Generate PR body content from commit metadata, including the repository's PR template if it has one
+///
+/// When `commit` is given and `gitx.pr.includeDiffstat` is on, a diffstat of the commit is included.
+pub fn generate_pr_body(
+    metadata: &CommitMetadata,
+    commit_message: &str,
+    commit: Option<(&git2::Repository, git2::Oid)>,
+) -> String {
+    let commit = commit.filter(|_| crate::config::is_diffstat_enabled());
+    generate_pr_body_with_template(metadata, commit_message, commit, load_pr_template().as_ref())
+}
+
+/// Generate PR body content from commit metadata, an optional diffstat source and an optional PR template
+pub fn generate_pr_body_with_template(
+    metadata: &CommitMetadata,
+    commit_message: &str,
+    commit: Option<(&git2::Repository, git2::Oid)>,
+    template: Option<&PRTemplate>,
+) -> String {
     let mut body = String::new();
     
-    // Add main commit message
-    if commit_message.lines().count() > 1 {
+    if let Some(template) = template.filter(|t| t.mode == PRTemplateMode::Prepend) {
+        body.push_str(&template.content);
+        body.push_str("\n\n");
+    }
+    
+    let (description, trailers) = split_trailers(commit_message);
+    
+    // Add main commit message, unless the template replaces it
+    if let Some(template) = template.filter(|t| t.mode == PRTemplateMode::ReplaceDescription) {
+        body.push_str(&template.content);
+        body.push_str("\n\n");
+    } else if !description.is_empty() {
         body.push_str("## Description\n\n");
-        body.push_str(&commit_message.lines().skip(1).collect::<Vec<_>>().join("\n"));
+        body.push_str(&description);
+        body.push_str("\n\n");
+    }
+    
+    // Issues closed by trailers, in the form GitHub recognizes for auto-close
+    for issue in &trailers.closes {
+        body.push_str(&format!("Closes {}\n", issue));
+    }
+    if !trailers.closes.is_empty() {
+        body.push('\n');
+    }
+    
+    // Link the referenced ticket if any
+    if let Some(ticket) = crate::branch_naming::extract_ticket(commit_message)
+        && !trailers.closes.contains(&ticket)
+    {
+        body.push_str(&format_ticket_reference(&ticket));
         body.push_str("\n\n");
     }
     
+    if !trailers.co_authors.is_empty() {
+        body.push_str("## Co-authors\n\n");
+        for co_author in &trailers.co_authors {
+            body.push_str(&format!("- {}\n", co_author));
+        }
+        body.push('\n');
+    }
+    
+    if let Some((repo, oid)) = commit
+        && let Ok(diffstat) = format_diffstat(repo, oid)
+    {
+        body.push_str("## Changes\n\n```\n");
+        body.push_str(&diffstat);
+        body.push_str("```\n\n");
+    }
+    
     // Add incremental commits if any
     if !metadata.incremental_commits.is_empty() {
         body.push_str("## Updates\n\n");
@@ -65,16 +394,349 @@ pub fn generate_pr_body(metadata: &CommitMetadata, commit_message: &str) -> Stri
     body
 }
 
+/// Trailers gitx understands, parsed from the end of a commit message
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitTrailers {
+    /// Issues from `Closes #N`-style lines (`#N` or `owner/repo#N`)
+    pub closes: Vec<String>,
+    /// `Co-authored-by:` values
+    pub co_authors: Vec<String>,
+    /// `Signed-off-by:` values
+    pub signed_off_by: Vec<String>,
+    /// `Gitx-Group:` name; adjacent commits in the same group share one PR
+    pub group: Option<String>,
+}
+
+/// Split a commit message into its description (body without subject) and trailers
+///
+/// Trailers are only recognized in the last paragraph, and only when every line of it
+/// looks like a trailer, matching git's own rules. Trailers gitx doesn't understand
+/// stay in the description.
+pub fn split_trailers(commit_message: &str) -> (String, CommitTrailers) {
+    static CLOSES: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    static TRAILER: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let closes = CLOSES.get_or_init(|| {
+        regex::Regex::new(r"(?i)^(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?):?\s+((?:[\w.-]+/[\w.-]+)?#\d+)$").unwrap()
+    });
+    let trailer = TRAILER.get_or_init(|| regex::Regex::new(r"^([A-Za-z0-9-]+):\s*(.+)$").unwrap());
+    
+    let body: Vec<&str> = commit_message.lines().skip(1).collect();
+    let body = body.join("\n");
+    let body = body.trim();
+    
+    let (text, last_paragraph) = match body.rfind("\n\n") {
+        Some(index) => (&body[..index], &body[index + 2..]),
+        None => ("", body),
+    };
+    let is_trailer_block = !last_paragraph.is_empty()
+        && last_paragraph.lines().all(|line| closes.is_match(line.trim()) || trailer.is_match(line.trim()));
+    if !is_trailer_block {
+        return (body.to_string(), CommitTrailers::default());
+    }
+    
+    let mut trailers = CommitTrailers::default();
+    let mut kept = Vec::new();
+    for line in last_paragraph.lines().map(str::trim) {
+        if let Some(captures) = closes.captures(line) {
+            trailers.closes.push(captures[1].to_string());
+        } else if let Some(captures) = trailer.captures(line) {
+            let value = captures[2].trim().to_string();
+            match captures[1].to_lowercase().as_str() {
+                "co-authored-by" => trailers.co_authors.push(value),
+                "signed-off-by" => trailers.signed_off_by.push(value),
+                "gitx-group" => trailers.group = Some(value),
+                _ => kept.push(line),
+            }
+        }
+    }
+    
+    let mut description = text.trim().to_string();
+    if !kept.is_empty() {
+        if !description.is_empty() {
+            description.push_str("\n\n");
+        }
+        description.push_str(&kept.join("\n"));
+    }
+    
+    (description, trailers)
+}
+
+/// Render `git diff --stat` output for a commit against its first parent
+pub fn format_diffstat(repo: &git2::Repository, oid: git2::Oid) -> Result<String, git2::Error> {
+    let commit = repo.find_commit(oid)?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let stats = diff.stats()?.to_buf(git2::DiffStatsFormat::FULL, 80)?;
+    Ok(stats.as_str().unwrap_or_default().to_string())
+}
+
+/// Render the "Stack" section listing every PR in a stack, bottom first, marking `current`
+pub fn format_stack_section(stack: &[u64], current: u64) -> String {
+    let mut section = String::from("## Stack\n\n");
+    for (i, pr_number) in stack.iter().enumerate() {
+        let marker = if *pr_number == current { " (this)" } else { "" };
+        section.push_str(&format!("{}. #{}{}\n", i + 1, pr_number, marker));
+    }
+    section
+}
+
+/// Render the "Commits" section of a PR that groups several commits, oldest first
+pub fn format_commits_section(repo: &git2::Repository, commits: &[git2::Oid]) -> String {
+    let mut section = String::from("## Commits\n\n");
+    for oid in commits {
+        let subject = repo
+            .find_commit(*oid)
+            .ok()
+            .and_then(|commit| commit.summary().map(str::to_string))
+            .unwrap_or_default();
+        section.push_str(&format!("- {} {}\n", &oid.to_string()[..8], subject));
+    }
+    section
+}
+
+/// Insert a section into a generated PR body just above the gitx footer
+pub fn insert_before_footer(body: &str, section: &str) -> String {
+    match body.rfind("---\n*Generated by gitx") {
+        Some(index) => format!("{}{}\n{}", &body[..index], section, &body[index..]),
+        None => format!("{}\n\n{}", body.trim_end(), section),
+    }
+}
+
+/// Swap the "Stack" section of an existing PR body for `section`, keeping everything else
+///
+/// Bodies without a Stack section get it inserted above the gitx footer.
+pub fn replace_stack_section(body: &str, section: &str) -> String {
+    let heading = "## Stack\n";
+    let start = if body.starts_with(heading) {
+        Some(0)
+    } else {
+        body.find(&format!("\n{}", heading)).map(|index| index + 1)
+    };
+    let Some(start) = start else {
+        return insert_before_footer(body, section);
+    };
+    
+    // The section runs until the next heading or the footer, whichever comes first
+    let after = start + heading.len();
+    let end = [body[after..].find("\n## "), body[after..].find("---\n*Generated by gitx")]
+        .into_iter()
+        .flatten()
+        .min()
+        .map(|index| after + index + usize::from(body[after + index..].starts_with('\n')))
+        .unwrap_or(body.len());
+    let separator = if end < body.len() { "\n" } else { "" };
+    format!("{}{}{}{}", &body[..start], section, separator, &body[end..])
+}
+
+/// Check if a commit subject carries a work-in-progress marker (`WIP:` or `[WIP]`)
+pub fn is_wip_title(title: &str) -> bool {
+    strip_wip_prefix(title).len() != title.trim_start().len()
+}
+
+/// Strip a leading work-in-progress marker from a commit subject
+pub fn strip_wip_prefix(title: &str) -> &str {
+    let trimmed = title.trim_start();
+    for marker in ["WIP:", "[WIP]"] {
+        if let Some(prefix) = trimmed.get(..marker.len())
+            && prefix.eq_ignore_ascii_case(marker)
+        {
+            return trimmed[marker.len()..].trim_start();
+        }
+    }
+    trimmed
+}
+
+/// PR title for a commit subject, rendered with `gitx.pr.titleFormat` (WIP markers are dropped)
+/// and shortened to `gitx.pr.titleMaxLength`; the PR description keeps the full subject
+pub fn pr_title_for_subject(subject: &str) -> String {
+    let ticket = crate::branch_naming::extract_ticket(subject);
+    let title = render_pr_title(&crate::config::get_pr_title_format(), strip_wip_prefix(subject), ticket.as_deref());
+    truncate_title(&title, crate::config::get_pr_title_max_length())
+}
+
+/// Shorten a title to at most `max_length` characters at a word boundary, ending it with `…`
+///
+/// A `max_length` of 0 means no limit. A single word longer than the limit is cut mid-word.
+pub fn truncate_title(title: &str, max_length: usize) -> String {
+    if max_length == 0 || title.chars().count() <= max_length {
+        return title.to_string();
+    }
+    
+    // Leave room for the ellipsis
+    let cut: String = title.chars().take(max_length.saturating_sub(1)).collect();
+    // Drop the partial word at the end unless the cut already falls between words
+    let ends_on_word = title.chars().nth(cut.chars().count()).is_some_and(char::is_whitespace);
+    let shortened = match cut.rfind(char::is_whitespace) {
+        Some(index) if !ends_on_word => &cut[..index],
+        _ => cut.as_str(),
+    };
+    format!("{}…", shortened.trim_end_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':' | '-')))
+}
+
+/// Fill `{subject}` and `{ticket}` into a PR title template
+///
+/// When the template places the ticket itself, a ticket leading the subject (`JIRA-1: ...`)
+/// is dropped from `{subject}`. Without a ticket, its brackets are removed from the template.
+pub fn render_pr_title(format: &str, subject: &str, ticket: Option<&str>) -> String {
+    let subject = subject.trim();
+    let subject = match ticket {
+        Some(ticket) if format.contains("{ticket}") => subject
+            .strip_prefix(ticket)
+            .map(|rest| rest.trim_start_matches([':', '-', ' ']))
+            .filter(|rest| !rest.is_empty())
+            .unwrap_or(subject),
+        _ => subject,
+    };
+    
+    // Tidy the template before filling it in, so brackets in the subject itself are kept
+    let template = match ticket {
+        Some(_) => format.to_string(),
+        None => format.replace("[{ticket}]", "").replace("({ticket})", "").replace("{ticket}", ""),
+    };
+    template
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("{ticket}", ticket.unwrap_or(""))
+        .replace("{subject}", subject)
+}
+
+/// Labels applied to conventional commit types unless `gitx.pr.labelMap` overrides them
+pub const DEFAULT_LABEL_MAP: &[(&str, &str)] = &[("feat", "enhancement"), ("fix", "bug")];
+
+/// Extract the type of a conventional commit subject (`feat` from `feat(api)!: Add search`)
+pub fn conventional_type(subject: &str) -> Option<&str> {
+    let (prefix, _) = strip_wip_prefix(subject).split_once(": ")?;
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    let commit_type = match prefix.split_once('(') {
+        Some((commit_type, scope)) if scope.ends_with(')') => commit_type,
+        Some(_) => return None,
+        None => prefix,
+    };
+    
+    let is_word = !commit_type.is_empty() && commit_type.chars().all(|c| c.is_ascii_alphanumeric());
+    is_word.then_some(commit_type)
+}
+
+/// Label for a conventional commit subject, looked up by type in `label_map`
+pub fn conventional_label(subject: &str, label_map: &[(String, String)]) -> Option<String> {
+    let commit_type = conventional_type(subject)?;
+    label_map
+        .iter()
+        .find(|(mapped_type, _)| mapped_type.eq_ignore_ascii_case(commit_type))
+        .map(|(_, label)| label.clone())
+}
+
 /// Get GitHub repository info from git remote
+///
+/// This is the repository PRs are opened against: the `gitx.github.upstream` remote when
+/// working from a fork, otherwise the `gitx.remote` branches are pushed to.
 pub fn get_github_repo_from_remote() -> Result<GitHubRepo, Box<dyn std::error::Error>> {
-    let remote_url = GitUtils::get_remote_url()?;
+    let remote_name = crate::config::get_github_upstream_remote();
+    let remote_url = GitUtils::get_remote_url_for(&remote_name)?;
+    // A remote on another server than gitx.github.host would otherwise fail with a vague parse error
+    if let Some(mismatch) = GitUtils::host_mismatch(&remote_name, &remote_url, &crate::config::get_github_host()) {
+        return Err(mismatch.into());
+    }
     let (owner, name) = GitUtils::parse_github_url(&remote_url)?;
     Ok(GitHubRepo { owner, name })
 }
 
+/// Repository dry runs describe requests against, with placeholders when the remote can't be resolved
+pub fn planned_repo() -> GitHubRepo {
+    get_github_repo_from_remote().unwrap_or_else(|_| GitHubRepo {
+        owner: "<owner>".to_string(),
+        name: "<repo>".to_string(),
+    })
+}
+
+/// A GitHub API request a dry run would have made, printed at `--verbose`
+///
+/// Displays as `POST /repos/owner/repo/pulls {head: ..., base: ..., title: ...}`. PRs that
+/// don't exist yet are numbered `{number}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedRequest {
+    pub method: &'static str,
+    pub route: String,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl PlannedRequest {
+    /// Open a PR
+    pub fn create_pr(repo: &GitHubRepo, head: &str, base: &str, title: &str, draft: bool) -> Self {
+        Self {
+            method: "POST",
+            route: format!("/repos/{}/{}/pulls", repo.owner, repo.name),
+            fields: vec![
+                ("head", head.to_string()),
+                ("base", base.to_string()),
+                ("title", title.to_string()),
+                ("draft", draft.to_string()),
+            ],
+        }
+    }
+    
+    /// Change fields of an existing PR, such as its `body` or `base`
+    pub fn update_pr(repo: &GitHubRepo, pr_number: u64, fields: Vec<(&'static str, String)>) -> Self {
+        Self {
+            method: "PATCH",
+            route: format!("/repos/{}/{}/pulls/{}", repo.owner, repo.name, pr_number),
+            fields,
+        }
+    }
+    
+    /// Add labels, reviewers or assignees to a PR about to be created
+    pub fn add_to_new_pr(repo: &GitHubRepo, field: &'static str, values: &[String]) -> Self {
+        let route = match field {
+            "reviewers" => "pulls/{number}/requested_reviewers",
+            "labels" => "issues/{number}/labels",
+            _ => "issues/{number}/assignees",
+        };
+        Self {
+            method: "POST",
+            route: format!("/repos/{}/{}/{}", repo.owner, repo.name, route),
+            fields: vec![(field, format!("[{}]", values.join(", ")))],
+        }
+    }
+}
+
+impl std::fmt::Display for PlannedRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields: Vec<String> = self.fields.iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+        write!(f, "{} {} {{{}}}", self.method, self.route, fields.join(", "))
+    }
+}
+
+/// Head reference for a PR from `branch_name`, namespaced as `owner:branch` when `gitx.github.fork` is set
+pub fn pr_head_ref(branch_name: &str) -> String {
+    format_head_ref(crate::config::get_github_fork().as_deref(), branch_name)
+}
+
+/// Format a PR head reference, prefixing the fork owner if there is one
+fn format_head_ref(fork_owner: Option<&str>, branch_name: &str) -> String {
+    match fork_owner {
+        Some(owner) => format!("{}:{}", owner, branch_name),
+        None => branch_name.to_string(),
+    }
+}
+
 /// Check if GitHub token is available
 pub fn check_github_token() -> bool {
-    crate::config::get_github_token().is_some()
+    // Offline mode behaves as if no token were configured
+    !crate::config::is_offline() && crate::config::get_github_token().is_some()
+}
+
+/// Format a ticket reference line: GitHub issues are closed, other trackers are linked
+fn format_ticket_reference(ticket: &str) -> String {
+    if ticket.starts_with('#') {
+        format!("Closes {}", ticket)
+    } else {
+        format!("Relates to {}", ticket)
+    }
 }
 
 /// Format commit type for display
@@ -122,6 +784,14 @@ mod tests {
             url: "https://github.com/owner/repo/pull/123".to_string(),
             mergeable: Some(true),
             draft: false,
+            additions: None,
+            deletions: None,
+            changed_files: None,
+            review_decision: ReviewDecision::None,
+            head_branch: String::new(),
+            head_sha: String::new(),
+            base_branch: String::new(),
+            merged_at: None,
         };
         
         assert_eq!(status.number, 123);
@@ -145,10 +815,293 @@ mod tests {
         
         let commit_message = "Add new feature\n\nThis adds a really cool feature\nthat does amazing things.";
         
-        let body = generate_pr_body(&metadata, commit_message);
+        let body = generate_pr_body(&metadata, commit_message, None);
         assert!(body.contains("## Description"));
         assert!(body.contains("## Updates"));
         assert!(body.contains("Generated by gitx"));
         assert!(!metadata.incremental_commits.is_empty());
     }
+
+    #[test]
+    fn test_find_pr_template() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(find_pr_template(root.path(), None, PRTemplateMode::Prepend), None);
+        
+        std::fs::create_dir(root.path().join("docs")).unwrap();
+        std::fs::write(root.path().join("docs/pull_request_template.md"), "## Checklist\n- [ ] Tests\n").unwrap();
+        let template = find_pr_template(root.path(), None, PRTemplateMode::Prepend).unwrap();
+        assert_eq!(template.content, "## Checklist\n- [ ] Tests");
+        
+        // .github/ wins over docs/
+        std::fs::create_dir(root.path().join(".github")).unwrap();
+        std::fs::write(root.path().join(".github/PULL_REQUEST_TEMPLATE.md"), "From .github").unwrap();
+        let template = find_pr_template(root.path(), None, PRTemplateMode::Prepend).unwrap();
+        assert_eq!(template.content, "From .github");
+    }
+
+    #[test]
+    fn test_find_pr_template_custom_path() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join(".github")).unwrap();
+        std::fs::write(root.path().join(".github/PULL_REQUEST_TEMPLATE.md"), "Default").unwrap();
+        std::fs::write(root.path().join("team-template.md"), "Custom").unwrap();
+        
+        let template = find_pr_template(root.path(), Some("team-template.md"), PRTemplateMode::ReplaceDescription).unwrap();
+        assert_eq!(template, PRTemplate { content: "Custom".to_string(), mode: PRTemplateMode::ReplaceDescription });
+        assert_eq!(find_pr_template(root.path(), Some("missing.md"), PRTemplateMode::Prepend), None);
+    }
+
+    #[test]
+    fn test_pr_body_with_template() {
+        let metadata = CommitMetadata::new_branch_created("gitx/test/feature".to_string(), "abc123".to_string());
+        let commit_message = "Add feature\n\nCommit description";
+        
+        let body = generate_pr_body_with_template(&metadata, commit_message, None, None);
+        assert!(body.starts_with("## Description"));
+        
+        let prepend = PRTemplate { content: "## Checklist".to_string(), mode: PRTemplateMode::Prepend };
+        let body = generate_pr_body_with_template(&metadata, commit_message, None, Some(&prepend));
+        assert!(body.starts_with("## Checklist\n\n## Description"));
+        assert!(body.contains("Commit description"));
+        assert!(body.contains("Generated by gitx"));
+        
+        let replace = PRTemplate { content: "## Checklist".to_string(), mode: PRTemplateMode::ReplaceDescription };
+        let body = generate_pr_body_with_template(&metadata, commit_message, None, Some(&replace));
+        assert!(body.starts_with("## Checklist"));
+        assert!(!body.contains("Commit description"));
+        assert!(body.contains("Generated by gitx"));
+    }
+
+    #[test]
+    fn test_split_trailers() {
+        let message = "Add login\n\nAdds the login form.\n\nCloses #123\nFixes octo/repo#7\nCo-authored-by: Ada <ada@example.com>\nSigned-off-by: Bob <bob@example.com>\nReviewed-on: https://example.com/1";
+        let (description, trailers) = split_trailers(message);
+        
+        assert_eq!(description, "Adds the login form.\n\nReviewed-on: https://example.com/1");
+        assert_eq!(trailers.closes, vec!["#123", "octo/repo#7"]);
+        assert_eq!(trailers.co_authors, vec!["Ada <ada@example.com>"]);
+        assert_eq!(trailers.signed_off_by, vec!["Bob <bob@example.com>"]);
+        
+        let metadata = CommitMetadata::new_branch_created("gitx/test/add-login".to_string(), "abc123".to_string());
+        let body = generate_pr_body_with_template(&metadata, message, None, None);
+        assert!(body.contains("Closes #123\nCloses octo/repo#7\n"));
+        assert_eq!(body.matches("Closes #123").count(), 1);
+        assert!(body.contains("## Co-authors\n\n- Ada <ada@example.com>"));
+        assert!(!body.contains("Signed-off-by"));
+    }
+
+    #[test]
+    fn test_split_trailers_without_trailers() {
+        let message = "Add login\n\nAdds the login form.\n\nNote: the form is not styled yet\nand needs design review.";
+        let (description, trailers) = split_trailers(message);
+        
+        assert_eq!(description, "Adds the login form.\n\nNote: the form is not styled yet\nand needs design review.");
+        assert_eq!(trailers, CommitTrailers::default());
+        assert_eq!(split_trailers("Subject only"), (String::new(), CommitTrailers::default()));
+    }
+
+    #[test]
+    fn test_stack_section_before_footer() {
+        let metadata = CommitMetadata::new_branch_created("gitx/test/top".to_string(), "abc123".to_string());
+        let body = generate_pr_body_with_template(&metadata, "Add top\n\nDetails", None, None);
+        
+        let body = insert_before_footer(&body, &format_stack_section(&[4, 7, 9], 7));
+        
+        assert!(body.contains("## Stack\n\n1. #4\n2. #7 (this)\n3. #9\n\n---\n*Generated by gitx"));
+        assert!(body.find("Details").unwrap() < body.find("## Stack").unwrap());
+    }
+
+    #[test]
+    fn test_replace_stack_section_keeps_rest_of_body() {
+        let body = "Hand-written summary\n\n## Stack\n\n1. #4 (this)\n2. #7\n\n## Commits\n\n- abc12345 Add top\n\n---\n*Generated by gitx*";
+        
+        let replaced = replace_stack_section(body, &format_stack_section(&[4, 7, 9], 4));
+        
+        assert_eq!(
+            replaced,
+            "Hand-written summary\n\n## Stack\n\n1. #4 (this)\n2. #7\n3. #9\n\n## Commits\n\n- abc12345 Add top\n\n---\n*Generated by gitx*"
+        );
+        assert_eq!(
+            replace_stack_section("Edited body", &format_stack_section(&[4, 7], 7)),
+            "Edited body\n\n## Stack\n\n1. #4\n2. #7 (this)\n"
+        );
+    }
+
+    #[test]
+    fn test_pr_body_includes_diffstat() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        
+        let mut builder = repo.treebuilder(None).unwrap();
+        for (name, content) in [("one.txt", "one\n"), ("two.txt", "two\nlines\n")] {
+            builder.insert(name, repo.blob(content.as_bytes()).unwrap(), 0o100644).unwrap();
+        }
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let oid = repo.commit(None, &signature, &signature, "Add two files", &tree, &[]).unwrap();
+        
+        let metadata = CommitMetadata::new_branch_created("gitx/test/add-two-files".to_string(), oid.to_string());
+        let body = generate_pr_body_with_template(&metadata, "Add two files", Some((&repo, oid)), None);
+        
+        assert!(body.contains("## Changes"));
+        assert!(body.contains("2 files changed, 3 insertions(+)"), "{}", body);
+        assert!(body.contains("one.txt"));
+    }
+
+    #[test]
+    fn test_wip_detection() {
+        assert!(is_wip_title("WIP: Add new feature"));
+        assert!(is_wip_title("[WIP] Add new feature"));
+        assert!(is_wip_title("wip: lowercase marker"));
+        assert!(!is_wip_title("Add new feature"));
+        assert!(!is_wip_title("Wipe stale cache entries"));
+    }
+
+    #[test]
+    fn test_strip_wip_prefix() {
+        assert_eq!(strip_wip_prefix("WIP: Add new feature"), "Add new feature");
+        assert_eq!(strip_wip_prefix("[WIP] Add new feature"), "Add new feature");
+        assert_eq!(strip_wip_prefix("[wip]Add new feature"), "Add new feature");
+        assert_eq!(strip_wip_prefix("Add new feature"), "Add new feature");
+    }
+
+    #[test]
+    fn test_conventional_type() {
+        assert_eq!(conventional_type("feat: Add search"), Some("feat"));
+        assert_eq!(conventional_type("fix(parser): Handle empty input"), Some("fix"));
+        assert_eq!(conventional_type("refactor!: Drop the v1 API"), Some("refactor"));
+        assert_eq!(conventional_type("WIP: chore(deps): Bump serde"), Some("chore"));
+        assert_eq!(conventional_type("Add search"), None);
+        assert_eq!(conventional_type("feat:no space"), None);
+        assert_eq!(conventional_type("fix(parser: Unclosed scope"), None);
+        assert_eq!(conventional_type("Note to self: tidy up"), None);
+    }
+
+    #[test]
+    fn test_conventional_label() {
+        let label_map = vec![
+            ("feat".to_string(), "enhancement".to_string()),
+            ("docs".to_string(), "documentation".to_string()),
+        ];
+        assert_eq!(conventional_label("feat(ui): Dark mode", &label_map), Some("enhancement".to_string()));
+        assert_eq!(conventional_label("Docs: Explain stacking", &label_map), Some("documentation".to_string()));
+        assert_eq!(conventional_label("fix: Off by one", &label_map), None);
+        assert_eq!(conventional_label("Add search", &label_map), None);
+    }
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_codeowners_pattern_matching() {
+        let matches = |pattern: &str, path: &str| codeowners_pattern_regex(pattern).unwrap().is_match(path);
+        
+        assert!(matches("*", "src/main.rs"));
+        assert!(matches("*.rs", "src/deep/lib.rs"));
+        assert!(!matches("*.rs", "src/lib.rsx"));
+        assert!(matches("docs/", "docs/guide/intro.md"));
+        assert!(matches("docs/", "src/docs/notes.md"));
+        assert!(!matches("/docs/", "src/docs/notes.md"));
+        assert!(matches("src/api", "src/api/routes.rs"));
+        assert!(!matches("src/api", "vendor/src/api/routes.rs"));
+        assert!(matches("src/*.rs", "src/main.rs"));
+        assert!(!matches("src/*.rs", "src/github/client.rs"));
+        assert!(matches("**/tests/*.rs", "crates/core/tests/smoke.rs"));
+        assert!(matches("/build/**", "build/logs/today.log"));
+    }
+
+    #[test]
+    fn test_codeowners_last_match_wins() {
+        let content = "\
+# Everything defaults to the core team
+*                 @acme/core
+*.md              @writer          # docs reviewers
+/src/github/      @octo @acme/api
+/src/github/generated.rs
+";
+        // The later, more specific rules override `*`
+        assert_eq!(codeowners_from_content(content, &paths(&["README.md"])), CodeOwners {
+            users: vec!["writer".to_string()],
+            teams: vec![],
+        });
+        assert_eq!(codeowners_from_content(content, &paths(&["src/github/client.rs"])), CodeOwners {
+            users: vec!["octo".to_string()],
+            teams: vec!["acme/api".to_string()],
+        });
+        // A rule without owners still wins, leaving the file unowned
+        assert_eq!(codeowners_from_content(content, &paths(&["src/github/generated.rs"])), CodeOwners::default());
+        // Owners across several paths are collected once each
+        assert_eq!(codeowners_from_content(content, &paths(&["src/main.rs", "Cargo.toml", "docs/a.md"])), CodeOwners {
+            users: vec!["writer".to_string()],
+            teams: vec!["acme/core".to_string()],
+        });
+    }
+
+    #[test]
+    fn test_format_head_ref() {
+        assert_eq!(format_head_ref(Some("forker"), "gitx/forker/add-login"), "forker:gitx/forker/add-login");
+        assert_eq!(format_head_ref(None, "gitx/octo/add-login"), "gitx/octo/add-login");
+    }
+
+    #[test]
+    fn test_truncate_long_title_at_word_boundary() {
+        let subject = "Refactor the payment reconciliation job to batch ledger writes and retry transient failures";
+        let title = truncate_title(subject, 72);
+        
+        assert_eq!(title, "Refactor the payment reconciliation job to batch ledger writes and…");
+        assert!(title.chars().count() <= 72);
+        assert_eq!(truncate_title("Averyveryverylongword", 10), "Averyvery…");
+    }
+
+    #[test]
+    fn test_short_title_is_unchanged() {
+        assert_eq!(truncate_title("Fix rounding in invoices", 72), "Fix rounding in invoices");
+        assert_eq!(truncate_title("Fix rounding in invoices", 0), "Fix rounding in invoices");
+    }
+
+    #[test]
+    fn test_planned_request_display() {
+        let repo = GitHubRepo { owner: "octo".to_string(), name: "repo".to_string() };
+        
+        let create = PlannedRequest::create_pr(&repo, "gitx/octo/add-login", "main", "Add login", false);
+        assert_eq!(create.to_string(), "POST /repos/octo/repo/pulls {head: gitx/octo/add-login, base: main, title: Add login, draft: false}");
+        let retarget = PlannedRequest::update_pr(&repo, 7, vec![("base", "main".to_string())]);
+        assert_eq!(retarget.to_string(), "PATCH /repos/octo/repo/pulls/7 {base: main}");
+        let labels = PlannedRequest::add_to_new_pr(&repo, "labels", &["bug".to_string(), "ui".to_string()]);
+        assert_eq!(labels.to_string(), "POST /repos/octo/repo/issues/{number}/labels {labels: [bug, ui]}");
+    }
+
+    #[test]
+    fn test_render_pr_title() {
+        assert_eq!(render_pr_title("{subject}", "JIRA-123: Add login", Some("JIRA-123")), "JIRA-123: Add login");
+        assert_eq!(render_pr_title("[payments] {subject}", "Fix rounding", None), "[payments] Fix rounding");
+        // A ticket placed by the template isn't repeated from the subject
+        assert_eq!(render_pr_title("[{ticket}] {subject}", "JIRA-123: Add login", Some("JIRA-123")), "[JIRA-123] Add login");
+        assert_eq!(render_pr_title("{subject} ({ticket})", "Fix crash on startup #456", Some("#456")), "Fix crash on startup #456 (#456)");
+        assert_eq!(render_pr_title("[{ticket}] {subject}", "Add login", None), "Add login");
+        // Brackets that are part of the subject are kept
+        assert_eq!(render_pr_title("{subject}", "Call init() early", None), "Call init() early");
+        assert_eq!(render_pr_title("{subject} ({ticket})", "Handle [] in paths", None), "Handle [] in paths");
+    }
+
+    #[test]
+    fn test_pr_body_ticket_reference() {
+        use crate::metadata::CommitMetadata;
+        
+        let metadata = CommitMetadata::new_branch_created(
+            "gitx/test/feature".to_string(),
+            "abc123".to_string()
+        );
+        
+        let body = generate_pr_body(&metadata, "Fix crash on startup (#456)", None);
+        assert!(body.contains("Closes #456"));
+        
+        let body = generate_pr_body(&metadata, "JIRA-123: Add user authentication", None);
+        assert!(body.contains("Relates to JIRA-123"));
+        
+        let body = generate_pr_body(&metadata, "Add user authentication", None);
+        assert!(!body.contains("Closes"));
+        assert!(!body.contains("Relates to"));
+    }
 }
\ No newline at end of file
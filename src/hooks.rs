@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Marks hook scripts written by gitx so reinstalling doesn't chain them to themselves
+const HOOK_MARKER: &str = "# Installed by gitx";
+
+/// Where an existing post-commit hook is moved so the gitx hook can run it first
+pub const CHAINED_HOOK_NAME: &str = "post-commit.pre-gitx";
+
+/// Outcome of installing the post-commit hook
+#[derive(Debug, PartialEq)]
+pub enum HookInstall {
+    Installed,
+    /// A hook that wasn't ours was kept and now runs before gitx's
+    Chained,
+    /// The gitx hook was already in place and has been rewritten
+    Updated,
+}
+
+/// Build the post-commit hook script
+fn post_commit_script() -> String {
+    format!(
+        r#"#!/bin/sh
+{marker}: keeps PRs in sync by running 'gitx diff' for each new commit
+hooks_dir=$(dirname "$0")
+if [ -x "$hooks_dir/{chained}" ]; then
+    "$hooks_dir/{chained}" "$@" || exit $?
+fi
+
+# 'gitx commit --amend' moves the PR to the amended commit and syncs it itself
+if [ -n "$GITX_AMENDING" ]; then
+    exit 0
+fi
+
+# Commits replayed by a rebase are diffed once it finishes
+git_dir=$(git rev-parse --git-dir)
+if [ -d "$git_dir/rebase-merge" ] || [ -d "$git_dir/rebase-apply" ]; then
+    exit 0
+fi
+
+command -v gitx >/dev/null 2>&1 || exit 0
+gitx diff --commit HEAD
+"#,
+        marker = HOOK_MARKER,
+        chained = CHAINED_HOOK_NAME,
+    )
+}
+
+/// The hooks directory git uses for this repository (honours `core.hooksPath`)
+pub fn hooks_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let output = Command::new("git").args(["rev-parse", "--git-path", "hooks"]).output()?;
+    if !output.status.success() {
+        return Err(format!("Could not find the hooks directory: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// Install a post-commit hook that runs `gitx diff` for the new commit
+///
+/// An existing hook that gitx didn't write is moved to `post-commit.pre-gitx` and
+/// called from the new hook, so its behavior is kept.
+pub fn install_post_commit_hook(hooks_dir: &Path) -> Result<HookInstall, Box<dyn std::error::Error>> {
+    fs::create_dir_all(hooks_dir)?;
+    let hook_path = hooks_dir.join("post-commit");
+    
+    let result = match fs::read_to_string(&hook_path) {
+        Ok(existing) if existing.contains(HOOK_MARKER) => HookInstall::Updated,
+        Ok(_) => {
+            let chained_path = hooks_dir.join(CHAINED_HOOK_NAME);
+            if chained_path.exists() {
+                return Err(format!("Both post-commit and {} already exist; merge them by hand first", CHAINED_HOOK_NAME).into());
+            }
+            fs::rename(&hook_path, &chained_path)?;
+            HookInstall::Chained
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => HookInstall::Installed,
+        Err(e) => return Err(e.into()),
+    };
+    
+    fs::write(&hook_path, post_commit_script())?;
+    make_executable(&hook_path)?;
+    
+    Ok(result)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_install_post_commit_hook_is_idempotent() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        
+        assert_eq!(install_post_commit_hook(temp_dir.path()).unwrap(), HookInstall::Installed);
+        assert_eq!(install_post_commit_hook(temp_dir.path()).unwrap(), HookInstall::Updated);
+        
+        // Reinstalling must not chain the gitx hook to itself
+        assert!(!temp_dir.path().join(CHAINED_HOOK_NAME).exists());
+    }
+}
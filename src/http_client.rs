@@ -0,0 +1,213 @@
+//! Building the HTTP client gitx talks to the GitHub API with
+//!
+//! Which API is used, highest precedence first:
+//! 1. `GITHUB_API_BASE_URL`
+//! 2. `https://<host>/api/v3` when `gitx.github.host` names a GitHub Enterprise Server
+//! 3. `https://api.github.com`
+//!
+//! Requests are routed through `HTTPS_PROXY` (or `HTTP_PROXY` for an `http://` API URL, with
+//! `ALL_PROXY` as the fallback for both) unless `NO_PROXY` matches the host of the URL chosen
+//! above, and certificates from `gitx.github.caBundle` are trusted alongside the system roots.
+
+use http::header::USER_AGENT;
+use http::{HeaderValue, Uri};
+use hyper_util::client::legacy::connect::proxy::Tunnel;
+use hyper_util::client::legacy::connect::{Connect, HttpConnector};
+use hyper_util::client::proxy::matcher::Matcher;
+use hyper_util::rt::TokioExecutor;
+use octocrab::service::middleware::auth_header::AuthHeaderLayer;
+use octocrab::service::middleware::base_uri::BaseUriLayer;
+use octocrab::service::middleware::extra_headers::ExtraHeadersLayer;
+use octocrab::{AuthState, Octocrab, OctocrabBuilder};
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::pem::PemObject;
+use std::sync::Arc;
+use tower_http::follow_redirect::FollowRedirectLayer;
+
+/// The github.com REST API
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Base URL of the GitHub API (see the module docs for precedence)
+pub fn github_api_base_url() -> String {
+    if let Ok(url) = std::env::var("GITHUB_API_BASE_URL")
+        && !url.is_empty()
+    {
+        return url;
+    }
+
+    let host = crate::config::get_github_host();
+    if host == "github.com" {
+        GITHUB_API_URL.to_string()
+    } else {
+        format!("https://{}/api/v3", host)
+    }
+}
+
+/// TLS settings trusting the system roots plus any certificates in `ca_bundle`
+fn tls_config(ca_bundle: Option<&str>) -> Result<rustls::ClientConfig, Box<dyn std::error::Error>> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+
+    if let Some(path) = ca_bundle {
+        let certs = CertificateDer::pem_file_iter(path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("Could not read gitx.github.caBundle '{}': {}", path, e))?;
+        if certs.is_empty() {
+            return Err(format!("gitx.github.caBundle '{}' contains no certificates", path).into());
+        }
+        for cert in certs {
+            roots.add(cert)?;
+        }
+    }
+
+    Ok(rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth())
+}
+
+/// Build an Octocrab client for `base_url`, honouring the proxy variables and `gitx.github.caBundle`
+pub fn build_octocrab(base_url: &str, token: Option<String>) -> Result<Octocrab, Box<dyn std::error::Error>> {
+    let ca_bundle = crate::config::get_github_ca_bundle();
+    build_octocrab_with(base_url, token, &Matcher::from_env(), ca_bundle.as_deref())
+}
+
+/// Build an Octocrab client for `base_url` with explicit proxy and certificate settings
+pub fn build_octocrab_with(
+    base_url: &str,
+    token: Option<String>,
+    proxies: &Matcher,
+    ca_bundle: Option<&str>,
+) -> Result<Octocrab, Box<dyn std::error::Error>> {
+    let base_uri: Uri = base_url.parse().map_err(|e| format!("Invalid GitHub API URL '{}': {}", base_url, e))?;
+    // Every request gitx makes goes to the API host, so the proxy decision is made once for it
+    let proxy = proxies.intercept(&base_uri);
+
+    // Octocrab's own client is enough when nothing needs customising
+    if proxy.is_none() && ca_bundle.is_none() {
+        let mut builder = Octocrab::builder().base_uri(base_url)?;
+        if let Some(token) = token {
+            builder = builder.personal_token(token);
+        }
+        return Ok(builder.build()?);
+    }
+
+    let mut http = HttpConnector::new();
+    // hyper-rustls hands https:// URLs to the inner connector and wraps the stream in TLS itself
+    http.enforce_http(false);
+    let https = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_tls_config(tls_config(ca_bundle)?)
+        .https_or_http()
+        .enable_http1();
+
+    match proxy {
+        Some(proxy) => {
+            if proxy.uri().scheme_str() != Some("http") {
+                return Err(format!("Unsupported proxy '{}': only http:// proxies are supported", proxy.uri()).into());
+            }
+            tracing::debug!("Connecting to {} through proxy {}", base_uri, proxy.uri().host().unwrap_or_default());
+
+            let mut tunnel = Tunnel::new(proxy.uri().clone(), http);
+            if let Some(auth) = proxy.basic_auth() {
+                tunnel = tunnel.with_auth(auth.clone());
+            }
+            octocrab_over(https.wrap_connector(tunnel), base_uri, token)
+        }
+        None => octocrab_over(https.wrap_connector(http), base_uri, token),
+    }
+}
+
+/// Build an Octocrab client on top of `connector`, with the layers Octocrab::builder() sets up
+fn octocrab_over<C>(connector: C, base_uri: Uri, token: Option<String>) -> Result<Octocrab, Box<dyn std::error::Error>>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    let client = hyper_util::client::legacy::Client::builder(TokioExecutor::new()).build(connector);
+    let auth_header = token
+        .map(|token| HeaderValue::from_str(&format!("Bearer {}", token)))
+        .transpose()?;
+
+    let octocrab = OctocrabBuilder::new_empty()
+        .with_service(client)
+        .with_layer(&FollowRedirectLayer::new())
+        .with_layer(&ExtraHeadersLayer::new(Arc::new(vec![(USER_AGENT, HeaderValue::from_static("octocrab"))])))
+        .with_layer(&BaseUriLayer::new(base_uri.clone()))
+        .with_layer(&AuthHeaderLayer::new(auth_header, base_uri, Uri::from_static("https://uploads.github.com")))
+        .with_auth(AuthState::None)
+        .build()?;
+
+    Ok(octocrab)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Accept CONNECT requests, record their targets and tunnel them to `upstream`
+    async fn start_proxy_stub(upstream: String) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut client, _)) = listener.accept().await {
+                let mut head = Vec::new();
+                while !head.ends_with(b"\r\n\r\n") {
+                    head.push(client.read_u8().await.unwrap());
+                }
+                let head = String::from_utf8_lossy(&head).to_string();
+                seen.lock().unwrap().push(head.lines().next().unwrap_or_default().to_string());
+
+                let mut server = TcpStream::connect(&upstream).await.unwrap();
+                client.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n").await.unwrap();
+                tokio::spawn(async move {
+                    let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+                });
+            }
+        });
+
+        (address, requests)
+    }
+
+    #[tokio::test]
+    async fn test_proxy_scheme_is_checked_only_for_proxied_hosts() {
+        let proxies = Matcher::builder().https("socks5://127.0.0.1:1080").no("ghe.internal.corp").build();
+
+        let error = build_octocrab_with(GITHUB_API_URL, None, &proxies, None).unwrap_err();
+        assert!(error.to_string().contains("only http:// proxies are supported"));
+        // NO_PROXY hosts never touch the proxy, so its scheme doesn't matter for them
+        assert!(build_octocrab_with("https://ghe.internal.corp/api/v3", None, &proxies, None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_client_routes_through_proxy() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rate_limit"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "via": "proxy" })))
+            .mount(&server)
+            .await;
+        let (proxy_address, requests) = start_proxy_stub(server.address().to_string()).await;
+        let proxies = Matcher::builder().http(format!("http://user:secret@{}", proxy_address)).build();
+
+        // The API host doesn't resolve, so the request can only succeed through the proxy
+        let octocrab = build_octocrab_with("http://api.github.invalid:80", Some("token".to_string()), &proxies, None).unwrap();
+        let response: serde_json::Value = octocrab.get("/rate_limit", None::<&()>).await.unwrap();
+
+        assert_eq!(response["via"], "proxy");
+        assert_eq!(*requests.lock().unwrap(), vec!["CONNECT api.github.invalid:80 HTTP/1.1".to_string()]);
+    }
+
+    #[test]
+    fn test_missing_ca_bundle_is_reported() {
+        let error = tls_config(Some("/nonexistent/ca.pem")).unwrap_err();
+
+        assert!(error.to_string().contains("gitx.github.caBundle"));
+    }
+}
@@ -4,8 +4,16 @@ pub mod github_utils;
 pub mod branch_naming;
 pub mod metadata;
 pub mod github;
+pub mod http_client;
 pub mod status_display;
+pub mod status_cache;
+pub mod stats;
+pub mod self_update;
+pub mod pr_editor;
+pub mod progress;
+pub mod logging;
 pub mod config;
+pub mod hooks;
 pub mod cli;
 pub mod commands;
 pub mod client_factory;
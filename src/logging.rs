@@ -0,0 +1,47 @@
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use crate::config::Verbosity;
+
+/// Route gitx's progress and debug output to stdout at the given verbosity
+///
+/// Lines are printed bare (no timestamp, level or target), so `tracing::info!`
+/// reads exactly like the `println!` it replaces. `RUST_LOG` overrides the level;
+/// at debug, the spans around GitHub calls and pushes print with their timing when they close.
+pub fn init(verbosity: Verbosity) {
+    crate::config::set_verbosity(verbosity);
+
+    let filter = EnvFilter::builder()
+        .with_default_directive(level_filter(verbosity).into())
+        .from_env_lossy();
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stdout)
+        .with_ansi(false)
+        .without_time()
+        .with_level(false)
+        .with_target(false)
+        .try_init();
+}
+
+/// Most detailed level printed at `verbosity`
+fn level_filter(verbosity: Verbosity) -> LevelFilter {
+    match verbosity {
+        Verbosity::Quiet => LevelFilter::WARN,
+        Verbosity::Normal => LevelFilter::INFO,
+        Verbosity::Verbose => LevelFilter::DEBUG,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_filter() {
+        assert_eq!(level_filter(Verbosity::Quiet), LevelFilter::WARN);
+        assert_eq!(level_filter(Verbosity::Normal), LevelFilter::INFO);
+        assert_eq!(level_filter(Verbosity::Verbose), LevelFilter::DEBUG);
+    }
+}
@@ -1,23 +1,101 @@
 use clap::Parser;
-use gitx::{cli::{Cli, Commands}, commands};
+use gitx::{cli::{Cli, Commands}, commands, config, github::PRCreateOptions, logging, status_display};
 
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    if cli.offline {
+        config::set_offline(true);
+    }
+    logging::init(if cli.quiet {
+        config::Verbosity::Quiet
+    } else if cli.verbose {
+        config::Verbosity::Verbose
+    } else {
+        config::Verbosity::Normal
+    });
 
     let result = match &cli.command {
-        Commands::Branch => commands::branch::handle_branch(),
-        Commands::Commit { args } => commands::commit::handle_commit(args),
-        Commands::Diff { all, dry_run } => commands::diff::handle_diff(*all, *dry_run).await,
-        Commands::Init => commands::init::handle_init(),
-        Commands::Land { all, dry_run } => commands::land::handle_land(*all, *dry_run).await,
-        Commands::Prs => commands::prs::handle_prs().await,
-        Commands::Status { args } => commands::status::handle_status(args),
+        Commands::Branch { force, new, from, delete } => {
+            commands::branch::handle_branch(*force, new.as_deref(), from.as_deref(), delete.as_deref())
+        }
+        Commands::Adopt { pr } => commands::adopt::handle_adopt(*pr).await,
+        Commands::Checkout { pr, force } => commands::checkout::handle_checkout(pr, *force).await,
+        Commands::Close { branch, delete_branch } => commands::close::handle_close(branch, *delete_branch).await,
+        Commands::Config { action } => commands::config::handle_config(action),
+        Commands::Commit { args } => commands::commit::handle_commit(args).await,
+        Commands::Completions { shell } => commands::completions::handle_completions(*shell),
+        Commands::Diff { all, yes, full_sha, commit, since, stdin, message, dry_run, draft, labels, reviewers, assignees, edit, skip_conflicts, base, force, allow_merges, push_only } => {
+            let pr_options = PRCreateOptions {
+                draft: *draft,
+                labels: labels.clone(),
+                reviewers: reviewers.clone(),
+                assignees: assignees.clone(),
+                edit: *edit,
+                skip_conflicts: *skip_conflicts,
+                base: base.clone(),
+                force: *force,
+                allow_merges: *allow_merges,
+                push_only: *push_only,
+            };
+            match message {
+                Some(message) => commands::diff::handle_commit_and_diff(message, pr_options).await,
+                None => {
+                    let commits = if *stdin { commands::diff::read_commits_from_stdin() } else { Ok(commit.iter().cloned().collect()) };
+                    match commits {
+                        Ok(commits) => commands::diff::handle_diff(*all, *yes, *full_sha, &commits, since.as_deref(), *dry_run, pr_options).await,
+                        Err(e) => Err(e),
+                    }
+                }
+            }
+        }
+        Commands::Doctor => commands::doctor::handle_doctor().await,
+        Commands::Drop { sha, delete_branch } => commands::drop::handle_drop(sha, *delete_branch).await,
+        Commands::Init { token, base_branch, enable_github, auto_cleanup, share_notes, hooks, no_hooks, keyring, yes } => {
+            let options = config::InitOptions {
+                token: token.clone(),
+                base_branch: base_branch.clone(),
+                enable_github: *enable_github,
+                auto_cleanup: *auto_cleanup,
+                share_notes: *share_notes,
+                hooks: if *no_hooks { Some(false) } else { *hooks },
+                keyring: *keyring,
+            };
+            commands::init::handle_init(&options, *yes)
+        }
+        Commands::Insert { after, message } => commands::insert::handle_insert(after.as_deref(), message).await,
+        Commands::Land { number, all, dry_run, force, keep_remote, include_closed } => {
+            commands::land::handle_land(*number, *all, *dry_run, *force, *keep_remote, *include_closed).await
+        }
+        Commands::Log => commands::log::handle_log(),
+        Commands::Prune { dry_run } => commands::prune::handle_prune(*dry_run).await,
+        Commands::PushNotes => commands::push_notes::handle_push_notes().await,
+        Commands::FetchNotes { strategy } => commands::fetch_notes::handle_fetch_notes(*strategy).await,
+        Commands::Prs { color, porcelain, csv, sort, filter, refresh, remote, stale } => {
+            let options = status_display::StatusDisplayOptions {
+                color: *color,
+                porcelain: *porcelain,
+                csv: *csv,
+                sort: *sort,
+                filter: *filter,
+                refresh: *refresh,
+                remote: *remote,
+                stale: *stale,
+            };
+            commands::prs::handle_prs(&options).await
+        }
+        Commands::Ready { branch } => commands::ready::handle_ready(branch).await,
+        Commands::Reorder { commits } => commands::reorder::handle_reorder(commits).await,
+        Commands::Reword { message, sha } => commands::reword::handle_reword(sha.as_deref(), message).await,
+        Commands::Stats { json } => commands::stats::handle_stats(*json).await,
+        Commands::Status { stack, args } => commands::status::handle_status(*stack, args),
+        Commands::Update { check_only } => commands::update::handle_update(*check_only).await,
+        Commands::Version => commands::version::handle_version(),
     };
 
     if let Err(e) = result {
-        eprintln!("Error: {}", e);
+        eprintln!("Error: {}", config::format_error_chain(e.as_ref()));
         std::process::exit(1);
     }
 }
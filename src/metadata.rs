@@ -1,9 +1,10 @@
-use git2::{Repository, Oid};
+use git2::Oid;
+use crate::git_utils::open_repo;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
 /// Type of incremental commit
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum IncrementalCommitType {
     /// Original commit was amended (git commit --amend)
     AmendedCommit,
@@ -19,8 +20,46 @@ pub enum PRStatus {
     Cancelled,
 }
 
-/// Git notes namespace for storing gitx metadata
-const GITX_NOTES_REF: &str = "refs/notes/gitx-metadata";
+impl PRStatus {
+    /// Stable lowercase name used in machine-readable output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PRStatus::BranchCreated => "branch-created",
+            PRStatus::PRCreated => "pr-created",
+            PRStatus::PRMerged => "merged",
+            PRStatus::Cancelled => "cancelled",
+        }
+    }
+    
+    /// How far along the PR lifecycle a status is, used to pick the most advanced one
+    fn progress(&self) -> u8 {
+        match self {
+            PRStatus::BranchCreated => 0,
+            PRStatus::PRCreated => 1,
+            PRStatus::Cancelled => 2,
+            PRStatus::PRMerged => 3,
+        }
+    }
+}
+
+/// How to resolve metadata notes that diverged between this machine and the remote
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum NotesMergeStrategy {
+    /// Keep the fetched metadata on conflict
+    Theirs,
+    /// Keep the local metadata on conflict
+    Ours,
+    /// Merge conflicting metadata field by field
+    Merge,
+}
+
+/// Default git notes namespace for storing gitx metadata
+pub const GITX_NOTES_REF: &str = "refs/notes/gitx-metadata";
+
+/// Get the notes ref metadata is read from and written to (`gitx.notesRef`, defaulting to `GITX_NOTES_REF`)
+pub fn notes_ref() -> String {
+    crate::config::get_notes_ref()
+}
 
 /// Metadata about a commit and its associated PR
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -28,12 +67,48 @@ pub struct CommitMetadata {
     pub pr_branch_name: String,
     #[serde(default)]
     pub github_pr_number: Option<u64>,
+    /// Branch the PR targets (the PR below it in the stack, or the default branch)
+    #[serde(default)]
+    pub base_branch: Option<String>,
     pub status: PRStatus,
     pub created_at: DateTime<Utc>,
     pub last_updated: DateTime<Utc>,
     pub original_commit_id: String,
     #[serde(default)]
     pub incremental_commits: Vec<IncrementalCommit>,
+    /// Every commit in the PR, oldest first, when it groups several (empty for one commit)
+    #[serde(default)]
+    pub commits: Vec<String>,
+    /// GitHub's view of the PR when gitx last reached it, for showing while offline
+    #[serde(default)]
+    pub last_known_status: Option<GitHubPRStatusSnapshot>,
+}
+
+/// The parts of a PR's GitHub status worth remembering between runs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GitHubPRStatusSnapshot {
+    pub state: String,
+    pub mergeable: Option<bool>,
+    pub draft: bool,
+    /// When gitx first saw GitHub report this state
+    pub as_of: DateTime<Utc>,
+}
+
+impl GitHubPRStatusSnapshot {
+    /// Take a snapshot of a status fetched at `as_of`
+    pub fn from_status(status: &crate::github::GitHubPRStatus, as_of: DateTime<Utc>) -> Self {
+        Self {
+            state: status.state.clone(),
+            mergeable: status.mergeable,
+            draft: status.draft,
+            as_of,
+        }
+    }
+    
+    /// Check whether two snapshots describe the same state, whenever they were taken
+    pub fn same_state(&self, other: &Self) -> bool {
+        self.state == other.state && self.mergeable == other.mergeable && self.draft == other.draft
+    }
 }
 
 /// Information about an incremental commit
@@ -52,14 +127,23 @@ impl CommitMetadata {
         Self {
             pr_branch_name,
             github_pr_number: None,
+            base_branch: None,
             status: PRStatus::BranchCreated,
             created_at: now,
             last_updated: now,
             original_commit_id,
             incremental_commits: Vec::new(),
+            commits: Vec::new(),
+            last_known_status: None,
         }
     }
     
+    /// Record the commits grouped into this PR, oldest first
+    pub fn with_commits(mut self, commits: Vec<String>) -> Self {
+        self.commits = commits;
+        self
+    }
+    
     /// Update metadata with GitHub PR number
     pub fn with_pr_number(mut self, pr_number: u64) -> Self {
         self.github_pr_number = Some(pr_number);
@@ -68,8 +152,20 @@ impl CommitMetadata {
         self
     }
     
+    /// Record the branch the PR targets
+    pub fn with_base_branch(mut self, base_branch: String) -> Self {
+        self.base_branch = Some(base_branch);
+        self.last_updated = Utc::now();
+        self
+    }
+    
     /// Add an incremental commit
+    /// Re-adding a commit that is already recorded is a no-op, so repeated runs don't pile up duplicates
     pub fn add_incremental_commit(mut self, commit_id: String, message: String, commit_type: IncrementalCommitType) -> Self {
+        if self.incremental_commits.iter().any(|existing| existing.commit_id == commit_id) {
+            return self;
+        }
+        
         let incremental_commit = IncrementalCommit {
             commit_id,
             message,
@@ -90,6 +186,13 @@ impl CommitMetadata {
         self
     }
     
+    /// Mark as cancelled (PR closed without merging)
+    pub fn mark_cancelled(mut self) -> Self {
+        self.status = PRStatus::Cancelled;
+        self.last_updated = Utc::now();
+        self
+    }
+    
     /// Check if the current commit ID differs from the original stored commit ID
     pub fn is_commit_changed(&self, current_commit_id: &str) -> bool {
         self.original_commit_id != current_commit_id
@@ -98,35 +201,38 @@ impl CommitMetadata {
 
 /// Store metadata for a commit using git notes
 pub fn store_commit_metadata(commit_id: &Oid, metadata: &CommitMetadata) -> Result<(), Box<dyn std::error::Error>> {
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
+    let notes_ref = notes_ref();
     let signature = repo.signature()?;
     
     let json = serde_json::to_string_pretty(metadata)?;
     
     // Store as a git note
-    repo.note(&signature, &signature, Some(GITX_NOTES_REF), *commit_id, &json, false)?;
+    repo.note(&signature, &signature, Some(&notes_ref), *commit_id, &json, false)?;
     
     Ok(())
 }
 
 /// Update existing metadata for a commit
 pub fn update_commit_metadata(commit_id: &Oid, metadata: &CommitMetadata) -> Result<(), Box<dyn std::error::Error>> {
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
+    let notes_ref = notes_ref();
     let signature = repo.signature()?;
     
     let json = serde_json::to_string_pretty(metadata)?;
     
     // Update the git note (force overwrite)
-    repo.note(&signature, &signature, Some(GITX_NOTES_REF), *commit_id, &json, true)?;
+    repo.note(&signature, &signature, Some(&notes_ref), *commit_id, &json, true)?;
     
     Ok(())
 }
 
 /// Get metadata for a commit
 pub fn get_commit_metadata(commit_id: &Oid) -> Result<Option<CommitMetadata>, Box<dyn std::error::Error>> {
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
+    let notes_ref = notes_ref();
     
-    match repo.find_note(Some(GITX_NOTES_REF), *commit_id) {
+    match repo.find_note(Some(&notes_ref), *commit_id) {
         Ok(note) => {
             if let Some(content) = note.message() {
                 let metadata: CommitMetadata = serde_json::from_str(content)?;
@@ -147,6 +253,75 @@ pub fn has_pr_metadata(commit_id: &Oid) -> bool {
     }
 }
 
+/// Merge two diverging metadata blobs for the same commit field by field
+/// - incremental commits are unioned by `commit_id`
+/// - the latest `last_updated` and earliest `created_at` win
+/// - a present `github_pr_number` is preferred over a missing one
+/// - the most advanced `status` is kept
+pub fn merge_metadata(ours: &CommitMetadata, theirs: &CommitMetadata) -> CommitMetadata {
+    let mut merged = ours.clone();
+    
+    for commit in &theirs.incremental_commits {
+        if !merged.incremental_commits.iter().any(|c| c.commit_id == commit.commit_id) {
+            merged.incremental_commits.push(commit.clone());
+        }
+    }
+    merged.incremental_commits.sort_by_key(|c| c.created_at);
+    
+    merged.github_pr_number = ours.github_pr_number.or(theirs.github_pr_number);
+    merged.base_branch = ours.base_branch.clone().or_else(|| theirs.base_branch.clone());
+    if merged.commits.is_empty() {
+        merged.commits = theirs.commits.clone();
+    }
+    merged.created_at = ours.created_at.min(theirs.created_at);
+    merged.last_updated = ours.last_updated.max(theirs.last_updated);
+    if theirs.status.progress() > ours.status.progress() {
+        merged.status = theirs.status.clone();
+    }
+    merged.last_known_status = [&ours.last_known_status, &theirs.last_known_status]
+        .into_iter()
+        .flatten()
+        .max_by_key(|snapshot| snapshot.as_of)
+        .cloned();
+    
+    merged
+}
+
+/// Compute merged metadata for every commit annotated differently in the local
+/// notes and in `other_notes_ref` (e.g. notes fetched from a remote)
+pub fn merge_notes(other_notes_ref: &str) -> Result<Vec<(Oid, CommitMetadata)>, Box<dyn std::error::Error>> {
+    let repo = open_repo()?;
+    let notes_ref = notes_ref();
+    let mut merged = Vec::new();
+    
+    let notes = match repo.notes(Some(other_notes_ref)) {
+        Ok(notes) => notes,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(merged),
+        Err(e) => return Err(Box::new(e)),
+    };
+    
+    for note in notes {
+        let (_note_id, commit_oid) = note?;
+        let theirs_note = repo.find_note(Some(other_notes_ref), commit_oid)?;
+        let Ok(ours_note) = repo.find_note(Some(&notes_ref), commit_oid) else {
+            continue; // Only annotated remotely, git's merge takes it as-is
+        };
+        
+        let (Some(theirs_json), Some(ours_json)) = (theirs_note.message(), ours_note.message()) else {
+            continue;
+        };
+        if theirs_json == ours_json {
+            continue;
+        }
+        
+        let theirs: CommitMetadata = serde_json::from_str(theirs_json)?;
+        let ours: CommitMetadata = serde_json::from_str(ours_json)?;
+        merged.push((commit_oid, merge_metadata(&ours, &theirs)));
+    }
+    
+    Ok(merged)
+}
+
 /// Information needed to display PR status
 #[derive(Debug, Clone)]
 pub struct PRStatusInfo {
@@ -159,11 +334,12 @@ pub struct PRStatusInfo {
     pub last_updated: DateTime<Utc>,
     pub incremental_count: usize,
     pub latest_incremental: Option<IncrementalCommit>,
+    pub base_branch: Option<String>,
+    pub last_known_status: Option<GitHubPRStatusSnapshot>,
 }
 
 impl PRStatusInfo {
     /// Create from commit metadata and message
-    #[allow(dead_code)]
     pub fn from_commit_and_metadata(commit_id: String, commit_message: String, metadata: &CommitMetadata) -> Self {
         let latest_incremental = metadata.incremental_commits.last().cloned();
         
@@ -177,32 +353,145 @@ impl PRStatusInfo {
             last_updated: metadata.last_updated,
             incremental_count: metadata.incremental_commits.len(),
             latest_incremental,
+            base_branch: metadata.base_branch.clone(),
+            last_known_status: metadata.last_known_status.clone(),
         }
     }
 }
 
 /// Get status information for all PRs
 pub fn get_all_pr_status() -> Result<Vec<PRStatusInfo>, Box<dyn std::error::Error>> {
-    // TODO: Fix git2 notes API usage
-    // For now return empty list to allow compilation
-    Ok(Vec::new())
+    let repo = open_repo()?;
+    
+    let mut statuses: Vec<PRStatusInfo> = list_all_pr_commits()?
+        .into_iter()
+        .map(|(commit_oid, metadata)| {
+            let commit_message = repo
+                .find_commit(commit_oid)
+                .ok()
+                .and_then(|commit| commit.message().map(str::to_string))
+                .unwrap_or_default();
+            PRStatusInfo::from_commit_and_metadata(commit_oid.to_string(), commit_message, &metadata)
+        })
+        .collect();
+    
+    // Creation order, so the stack reads bottom to top
+    statuses.sort_by_key(|status| status.created_at);
+    
+    Ok(statuses)
+}
+
+/// Remember GitHub's view of a commit's PR in its metadata
+pub fn record_last_known_status(commit_id: &Oid, snapshot: GitHubPRStatusSnapshot) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(mut metadata) = get_commit_metadata(commit_id)? {
+        metadata.last_known_status = Some(snapshot);
+        update_commit_metadata(commit_id, &metadata)?;
+    }
+    Ok(())
+}
+
+/// Move a commit's metadata to the commit that replaced it (e.g. after rewording)
+pub fn move_commit_metadata(old_id: &Oid, new_id: &Oid) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(metadata) = get_commit_metadata(old_id)? {
+        update_commit_metadata(new_id, &metadata)?;
+        remove_commit_metadata(old_id)?;
+    }
+    Ok(())
+}
+
+/// Order the open PRs in the stack containing `branch_name`, bottom first
+///
+/// PRs are linked through their base branch. Where several PRs sit on the same one,
+/// the older PR and everything above it come first.
+pub fn compute_stack<'a>(prs: &'a [PRStatusInfo], branch_name: &str) -> Vec<&'a PRStatusInfo> {
+    let open: Vec<&PRStatusInfo> = prs
+        .iter()
+        .filter(|pr| pr.pr_number.is_some() && !matches!(pr.status, PRStatus::PRMerged | PRStatus::Cancelled))
+        .collect();
+    let by_branch = |branch: &str| open.iter().copied().find(|pr| pr.branch_name == branch);
+    
+    // Walk down to the bottom of the stack (guarding against cycles in bad metadata)
+    let Some(mut root) = by_branch(branch_name) else {
+        return Vec::new();
+    };
+    let mut seen = vec![root.branch_name.as_str()];
+    while let Some(parent) = root.base_branch.as_deref().and_then(by_branch) {
+        if seen.contains(&parent.branch_name.as_str()) {
+            break;
+        }
+        seen.push(parent.branch_name.as_str());
+        root = parent;
+    }
+    
+    // Then collect everything above it, depth first
+    let mut stack = Vec::new();
+    let mut pending = vec![root];
+    while let Some(pr) = pending.pop() {
+        if stack.iter().any(|p: &&PRStatusInfo| p.branch_name == pr.branch_name) {
+            continue;
+        }
+        stack.push(pr);
+        
+        let mut children: Vec<&PRStatusInfo> = open
+            .iter()
+            .copied()
+            .filter(|child| child.base_branch.as_deref() == Some(pr.branch_name.as_str()))
+            .collect();
+        children.sort_by_key(|child| std::cmp::Reverse(child.created_at));
+        pending.extend(children);
+    }
+    
+    stack
 }
 
 /// Remove metadata for a commit (cleanup)
-#[allow(dead_code)]
 pub fn remove_commit_metadata(commit_id: &Oid) -> Result<(), git2::Error> {
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
+    let notes_ref = notes_ref();
     let signature = repo.signature()?;
-    repo.note_delete(*commit_id, Some(GITX_NOTES_REF), &signature, &signature)?;
+    repo.note_delete(*commit_id, Some(&notes_ref), &signature, &signature)?;
     Ok(())
 }
 
 /// List all commits that have PR metadata
-#[allow(dead_code)]
 pub fn list_all_pr_commits() -> Result<Vec<(Oid, CommitMetadata)>, git2::Error> {
-    // TODO: Fix git2 notes API usage
-    // For now return empty list to allow compilation
-    Ok(Vec::new())
+    let repo = open_repo()?;
+    let notes_ref = notes_ref();
+    let mut pr_commits = Vec::new();
+    
+    let notes = match repo.notes(Some(&notes_ref)) {
+        Ok(notes) => notes,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(pr_commits), // No notes yet
+        Err(e) => return Err(e),
+    };
+    
+    for note in notes {
+        let (_note_id, commit_oid) = note?;
+        if let Ok(Some(metadata)) = get_commit_metadata(&commit_oid) {
+            pr_commits.push((commit_oid, metadata));
+        }
+    }
+    
+    Ok(pr_commits)
+}
+
+/// Find the commit and metadata tracking a given PR branch
+pub fn find_metadata_by_branch(branch_name: &str) -> Result<Option<(Oid, CommitMetadata)>, git2::Error> {
+    let pr_commits = list_all_pr_commits()?;
+    Ok(pr_commits.into_iter().find(|(_, metadata)| metadata.pr_branch_name == branch_name))
+}
+
+/// Find the open PRs stacked directly on top of a branch
+pub fn find_dependent_prs(branch_name: &str) -> Result<Vec<(Oid, CommitMetadata)>, git2::Error> {
+    let pr_commits = list_all_pr_commits()?;
+    Ok(pr_commits
+        .into_iter()
+        .filter(|(_, metadata)| {
+            metadata.base_branch.as_deref() == Some(branch_name)
+                && metadata.github_pr_number.is_some()
+                && !matches!(metadata.status, PRStatus::PRMerged | PRStatus::Cancelled)
+        })
+        .collect())
 }
 
 /// Check if a commit at the current position differs from its stored metadata
@@ -226,7 +515,7 @@ pub fn find_commits_needing_updates() -> Result<Vec<(Oid, CommitMetadata)>, git2
     let all_pr_commits = list_all_pr_commits()?;
     let mut needs_updates = Vec::new();
     
-    let repo = Repository::open(".")?;
+    let repo = open_repo()?;
     
     for (commit_oid, metadata) in all_pr_commits {
         let current_commit = repo.find_commit(commit_oid)?;
@@ -331,6 +620,18 @@ mod tests {
         assert_eq!(latest.commit_id, "def456");
     }
 
+    #[test]
+    fn test_add_incremental_commit_ignores_duplicates() {
+        let metadata = CommitMetadata::new_branch_created(
+            "gitx/test/feature".to_string(),
+            "abc123".to_string()
+        )
+        .add_incremental_commit("def456".to_string(), "Fix issue".to_string(), IncrementalCommitType::AmendedCommit)
+        .add_incremental_commit("def456".to_string(), "Fix issue".to_string(), IncrementalCommitType::AmendedCommit);
+        
+        assert_eq!(metadata.incremental_commits.len(), 1);
+    }
+
     #[test]
     fn test_commit_changed_detection() {
         let metadata = CommitMetadata::new_branch_created(
@@ -407,6 +708,71 @@ mod tests {
         std::env::set_current_dir(original_dir).unwrap();
     }
 
+    #[test]
+    fn test_custom_notes_ref() {
+        let (repo, _temp_dir) = create_test_repo().expect("Failed to create test repo");
+        repo.config().unwrap().set_str("gitx.notesRef", "refs/notes/gitx-custom").unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(_temp_dir.path()).unwrap();
+        crate::config::invalidate_cache();
+        
+        let commit_id = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let metadata = CommitMetadata::new_branch_created(
+            "gitx/test/custom".to_string(),
+            commit_id.to_string()
+        );
+        
+        assert_eq!(notes_ref(), "refs/notes/gitx-custom");
+        store_commit_metadata(&commit_id, &metadata).expect("Failed to store metadata");
+        
+        // Stored under the custom ref and retrievable through it
+        let retrieved = get_commit_metadata(&commit_id).expect("Failed to get metadata");
+        assert_eq!(retrieved.unwrap().pr_branch_name, "gitx/test/custom");
+        assert_eq!(list_all_pr_commits().unwrap().len(), 1);
+        assert!(repo.find_note(Some("refs/notes/gitx-custom"), commit_id).is_ok());
+        
+        // The default ref is left untouched
+        assert!(repo.find_reference(GITX_NOTES_REF).is_err());
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        crate::config::invalidate_cache();
+    }
+
+    #[test]
+    fn test_merge_divergent_metadata() {
+        let base = CommitMetadata::new_branch_created(
+            "gitx/test/feature".to_string(),
+            "abc123".to_string()
+        );
+        
+        // This machine created the PR and recorded one update
+        let ours = base.clone()
+            .with_pr_number(42)
+            .add_incremental_commit("def456".to_string(), "First fix".to_string(), IncrementalCommitType::AmendedCommit);
+        
+        // Another machine recorded the same update plus a newer one, and saw the PR merge
+        let mut theirs = base.clone()
+            .add_incremental_commit("def456".to_string(), "First fix".to_string(), IncrementalCommitType::AmendedCommit)
+            .add_incremental_commit("789abc".to_string(), "Second fix".to_string(), IncrementalCommitType::AdditionalCommit);
+        theirs.status = PRStatus::PRMerged;
+        theirs.last_updated = ours.last_updated + chrono::Duration::hours(1);
+        
+        let merged = merge_metadata(&ours, &theirs);
+        
+        assert_eq!(merged.github_pr_number, Some(42));
+        assert_eq!(merged.status, PRStatus::PRMerged);
+        assert_eq!(merged.last_updated, theirs.last_updated);
+        let ids: Vec<&str> = merged.incremental_commits.iter().map(|c| c.commit_id.as_str()).collect();
+        assert_eq!(ids, vec!["def456", "789abc"]);
+        
+        // Merging is symmetric for the fields that matter
+        let reversed = merge_metadata(&theirs, &ours);
+        assert_eq!(reversed.github_pr_number, Some(42));
+        assert_eq!(reversed.status, PRStatus::PRMerged);
+        assert_eq!(reversed.incremental_commits.len(), 2);
+    }
+
     #[test]
     fn test_backward_compatibility() {
         // Test that old metadata format (without new fields) can still be deserialized
@@ -425,5 +791,6 @@ mod tests {
         // New fields should have default values
         assert_eq!(metadata.incremental_commits.len(), 0);
         assert!(metadata.github_pr_number.is_none());
+        assert!(metadata.base_branch.is_none());
     }
 }
\ No newline at end of file
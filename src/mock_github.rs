@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use crate::github::{GitHubClientTrait, PRInfo, GitHubPRStatus};
+use crate::github::{GitHubClientTrait, OpenPRInfo, PRInfo, GitHubPRStatus, RateLimit, ReviewDecision};
 use crate::github_utils::generate_pr_body;
 use crate::metadata::CommitMetadata;
 
@@ -16,6 +16,24 @@ pub struct MockGitHubClient {
     next_pr_number: Arc<Mutex<u64>>,
     /// Storage for PR updates: (pr_number -> (title, body))
     pr_updates: Arc<Mutex<HashMap<u64, (Option<String>, Option<String>)>>>,
+    /// Storage for labels added to PRs: (pr_number -> labels)
+    pr_labels: Arc<Mutex<HashMap<u64, Vec<String>>>>,
+    /// Storage for reviewers requested on PRs: (pr_number -> reviewers)
+    pr_reviewers: Arc<Mutex<HashMap<u64, Vec<String>>>>,
+    /// Storage for teams requested to review PRs: (pr_number -> teams)
+    pr_team_reviewers: Arc<Mutex<HashMap<u64, Vec<String>>>>,
+    /// Storage for assignees added to PRs: (pr_number -> assignees)
+    pr_assignees: Arc<Mutex<HashMap<u64, Vec<String>>>>,
+    /// Storage for the branch each PR targets: (pr_number -> base branch)
+    pr_bases: Arc<Mutex<HashMap<u64, String>>>,
+    /// Storage for the body each PR was created with: (branch_name -> body)
+    pr_bodies: Arc<Mutex<HashMap<String, String>>>,
+    /// Storage for review decisions: (pr_number -> decision)
+    review_decisions: Arc<Mutex<HashMap<u64, ReviewDecision>>>,
+    /// Error `create_pr` fails with, if set
+    create_pr_error: Arc<Mutex<Option<String>>>,
+    /// Rate limit reported by `rate_limit`
+    rate_limit: Arc<Mutex<RateLimit>>,
 }
 
 impl MockGitHubClient {
@@ -26,6 +44,19 @@ impl MockGitHubClient {
             pr_statuses: Arc::new(Mutex::new(HashMap::new())),
             next_pr_number: Arc::new(Mutex::new(1)),
             pr_updates: Arc::new(Mutex::new(HashMap::new())),
+            pr_labels: Arc::new(Mutex::new(HashMap::new())),
+            pr_reviewers: Arc::new(Mutex::new(HashMap::new())),
+            pr_team_reviewers: Arc::new(Mutex::new(HashMap::new())),
+            pr_assignees: Arc::new(Mutex::new(HashMap::new())),
+            pr_bases: Arc::new(Mutex::new(HashMap::new())),
+            pr_bodies: Arc::new(Mutex::new(HashMap::new())),
+            review_decisions: Arc::new(Mutex::new(HashMap::new())),
+            create_pr_error: Arc::new(Mutex::new(None)),
+            rate_limit: Arc::new(Mutex::new(RateLimit {
+                remaining: 5000,
+                limit: 5000,
+                reset: chrono::Utc::now() + chrono::Duration::hours(1),
+            })),
         }
     }
 
@@ -35,6 +66,37 @@ impl MockGitHubClient {
         statuses.insert(pr_number, status);
     }
 
+    /// Set the size reported for an existing PR
+    pub fn set_pr_size(&self, pr_number: u64, additions: u64, deletions: u64, changed_files: u64) {
+        if let Some(status) = self.pr_statuses.lock().unwrap().get_mut(&pr_number) {
+            status.additions = Some(additions);
+            status.deletions = Some(deletions);
+            status.changed_files = Some(changed_files);
+        }
+    }
+
+    /// Set the review decision reported for a PR
+    pub fn set_review_decision(&self, pr_number: u64, decision: ReviewDecision) {
+        self.review_decisions.lock().unwrap().insert(pr_number, decision);
+    }
+
+    /// Set the commit a PR's head branch points at
+    pub fn set_pr_head_sha(&self, pr_number: u64, sha: &str) {
+        if let Some(status) = self.pr_statuses.lock().unwrap().get_mut(&pr_number) {
+            status.head_sha = sha.to_string();
+        }
+    }
+
+    /// Set the requests left in the reported rate limit
+    pub fn set_rate_limit_remaining(&self, remaining: u64) {
+        self.rate_limit.lock().unwrap().remaining = remaining;
+    }
+
+    /// Make every later `create_pr` call fail with this message
+    pub fn fail_create_pr(&self, message: &str) {
+        *self.create_pr_error.lock().unwrap() = Some(message.to_string());
+    }
+
     /// Get all created PRs for testing verification
     pub fn get_created_prs(&self) -> HashMap<String, PRInfo> {
         self.created_prs.lock().unwrap().clone()
@@ -45,6 +107,31 @@ impl MockGitHubClient {
         self.pr_updates.lock().unwrap().clone()
     }
 
+    /// Get the labels added to a PR for testing verification
+    pub fn get_pr_labels(&self, pr_number: u64) -> Vec<String> {
+        self.pr_labels.lock().unwrap().get(&pr_number).cloned().unwrap_or_default()
+    }
+
+    /// Get the reviewers requested on a PR for testing verification
+    pub fn get_requested_reviewers(&self, pr_number: u64) -> Vec<String> {
+        self.pr_reviewers.lock().unwrap().get(&pr_number).cloned().unwrap_or_default()
+    }
+
+    /// Get the teams requested to review a PR for testing verification
+    pub fn get_requested_team_reviewers(&self, pr_number: u64) -> Vec<String> {
+        self.pr_team_reviewers.lock().unwrap().get(&pr_number).cloned().unwrap_or_default()
+    }
+
+    /// Get the assignees added to a PR for testing verification
+    pub fn get_pr_assignees(&self, pr_number: u64) -> Vec<String> {
+        self.pr_assignees.lock().unwrap().get(&pr_number).cloned().unwrap_or_default()
+    }
+
+    /// Get the branch a PR currently targets for testing verification
+    pub fn get_pr_base(&self, pr_number: u64) -> Option<String> {
+        self.pr_bases.lock().unwrap().get(&pr_number).cloned()
+    }
+
     /// Check if a PR was created for a specific branch
     pub fn was_pr_created_for_branch(&self, branch_name: &str) -> bool {
         self.created_prs.lock().unwrap().contains_key(branch_name)
@@ -57,13 +144,7 @@ impl MockGitHubClient {
 
     /// Get the body of a created PR
     pub fn get_pr_body(&self, branch_name: &str) -> Option<String> {
-        // In a real implementation, we'd store the body
-        // For now, return a placeholder
-        if self.was_pr_created_for_branch(branch_name) {
-            Some(format!("Mock PR body for branch: {}", branch_name))
-        } else {
-            None
-        }
+        self.pr_bodies.lock().unwrap().get(branch_name).cloned()
     }
 }
 
@@ -75,8 +156,13 @@ impl GitHubClientTrait for MockGitHubClient {
         title: &str,
         body: &str,
         base_branch: &str,
+        draft: bool,
     ) -> Result<PRInfo, Box<dyn std::error::Error>> {
-        println!("Mock: Creating PR: {} -> {} with title: {}", branch_name, base_branch, title);
+        tracing::info!("Mock: Creating PR: {} -> {} with title: {}", branch_name, base_branch, title);
+        
+        if let Some(message) = self.create_pr_error.lock().unwrap().clone() {
+            return Err(message.into());
+        }
         
         // Generate a new PR number
         let pr_number = {
@@ -97,6 +183,7 @@ impl GitHubClientTrait for MockGitHubClient {
             let mut prs = self.created_prs.lock().unwrap();
             prs.insert(branch_name.to_string(), pr_info.clone());
         }
+        self.pr_bodies.lock().unwrap().insert(branch_name.to_string(), body.to_string());
         
         // Create a default PR status as "open"
         let status = GitHubPRStatus {
@@ -105,7 +192,15 @@ impl GitHubClientTrait for MockGitHubClient {
             title: title.to_string(),
             url: pr_info.url.clone(),
             mergeable: Some(true),
-            draft: false,
+            draft,
+            additions: None,
+            deletions: None,
+            changed_files: None,
+            review_decision: ReviewDecision::None,
+            head_branch: branch_name.to_string(),
+            head_sha: String::new(),
+            base_branch: base_branch.to_string(),
+            merged_at: None,
         };
         
         {
@@ -113,6 +208,8 @@ impl GitHubClientTrait for MockGitHubClient {
             statuses.insert(pr_number, status);
         }
         
+        self.pr_bases.lock().unwrap().insert(pr_number, base_branch.to_string());
+        
         Ok(pr_info)
     }
     
@@ -122,7 +219,7 @@ impl GitHubClientTrait for MockGitHubClient {
         title: Option<&str>,
         body: Option<&str>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("Mock: Updating PR #{}", pr_number);
+        tracing::info!("Mock: Updating PR #{}", pr_number);
         
         // Store the update
         {
@@ -146,6 +243,20 @@ impl GitHubClientTrait for MockGitHubClient {
         Ok(())
     }
     
+    async fn fetch_pr_body(&self, pr_number: u64) -> Result<String, Box<dyn std::error::Error>> {
+        // The latest update wins over the body the PR was created with
+        if let Some((_, Some(body))) = self.pr_updates.lock().unwrap().get(&pr_number) {
+            return Ok(body.clone());
+        }
+        let prs = self.created_prs.lock().unwrap();
+        let branch_name = prs
+            .iter()
+            .find(|(_, pr)| pr.number == pr_number)
+            .map(|(branch_name, _)| branch_name)
+            .ok_or_else(|| format!("PR #{} not found", pr_number))?;
+        Ok(self.pr_bodies.lock().unwrap().get(branch_name).cloned().unwrap_or_default())
+    }
+    
     async fn get_pr_status(&self, pr_number: u64) -> Result<GitHubPRStatus, Box<dyn std::error::Error>> {
         let statuses = self.pr_statuses.lock().unwrap();
         if let Some(status) = statuses.get(&pr_number) {
@@ -169,6 +280,111 @@ impl GitHubClientTrait for MockGitHubClient {
         
         Ok(statuses)
     }
+    
+    async fn set_draft(&self, pr_number: u64, draft: bool) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("Mock: Setting draft={} on PR #{}", draft, pr_number);
+        
+        let mut statuses = self.pr_statuses.lock().unwrap();
+        match statuses.get_mut(&pr_number) {
+            Some(status) => {
+                status.draft = draft;
+                Ok(())
+            }
+            None => Err(format!("PR #{} not found", pr_number).into()),
+        }
+    }
+    
+    async fn add_labels(&self, pr_number: u64, labels: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("Mock: Adding labels to PR #{}: {}", pr_number, labels.join(", "));
+        
+        let mut pr_labels = self.pr_labels.lock().unwrap();
+        pr_labels.entry(pr_number).or_default().extend(labels.iter().cloned());
+        Ok(())
+    }
+    
+    async fn request_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("Mock: Requesting reviewers on PR #{}: {}", pr_number, reviewers.join(", "));
+        
+        let mut pr_reviewers = self.pr_reviewers.lock().unwrap();
+        pr_reviewers.entry(pr_number).or_default().extend(reviewers.iter().cloned());
+        Ok(())
+    }
+    
+    async fn request_team_reviewers(&self, pr_number: u64, teams: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("Mock: Requesting team reviewers on PR #{}: {}", pr_number, teams.join(", "));
+        
+        let mut pr_team_reviewers = self.pr_team_reviewers.lock().unwrap();
+        pr_team_reviewers.entry(pr_number).or_default().extend(teams.iter().cloned());
+        Ok(())
+    }
+    
+    async fn add_assignees(&self, pr_number: u64, assignees: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("Mock: Adding assignees to PR #{}: {}", pr_number, assignees.join(", "));
+        
+        let mut pr_assignees = self.pr_assignees.lock().unwrap();
+        pr_assignees.entry(pr_number).or_default().extend(assignees.iter().cloned());
+        Ok(())
+    }
+    
+    async fn close_pr(&self, pr_number: u64) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("Mock: Closing PR #{}", pr_number);
+        
+        let mut statuses = self.pr_statuses.lock().unwrap();
+        match statuses.get_mut(&pr_number) {
+            Some(status) => {
+                status.state = "closed".to_string();
+                Ok(())
+            }
+            None => Err(format!("PR #{} not found", pr_number).into()),
+        }
+    }
+    
+    async fn set_base(&self, pr_number: u64, base_branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("Mock: Retargeting PR #{} onto {}", pr_number, base_branch);
+        
+        let mut bases = self.pr_bases.lock().unwrap();
+        match bases.get_mut(&pr_number) {
+            Some(base) => {
+                *base = base_branch.to_string();
+                Ok(())
+            }
+            None => Err(format!("PR #{} not found", pr_number).into()),
+        }
+    }
+    
+    async fn get_current_user(&self) -> Result<String, Box<dyn std::error::Error>> {
+        Ok("mock-user".to_string())
+    }
+    
+    async fn get_review_decision(&self, pr_number: u64) -> Result<ReviewDecision, Box<dyn std::error::Error>> {
+        // PRs without a recorded decision behave like repos without branch protection
+        Ok(self.review_decisions.lock().unwrap().get(&pr_number).copied().unwrap_or_default())
+    }
+
+    async fn find_open_pr_for_branch(&self, branch_name: &str) -> Result<Option<PRInfo>, Box<dyn std::error::Error>> {
+        let prs = self.created_prs.lock().unwrap();
+        let statuses = self.pr_statuses.lock().unwrap();
+        Ok(prs
+            .get(branch_name)
+            .filter(|pr| statuses.get(&pr.number).is_some_and(|status| status.state == "open"))
+            .cloned())
+    }
+
+    async fn list_open_prs(&self) -> Result<Vec<OpenPRInfo>, Box<dyn std::error::Error>> {
+        let prs = self.created_prs.lock().unwrap();
+        let statuses = self.pr_statuses.lock().unwrap();
+        let mut open_prs: Vec<OpenPRInfo> = prs
+            .iter()
+            .filter(|(_, pr)| statuses.get(&pr.number).is_some_and(|status| status.state == "open"))
+            .map(|(branch_name, pr)| OpenPRInfo { branch_name: branch_name.clone(), pr: pr.clone() })
+            .collect();
+        open_prs.sort_by_key(|open_pr| open_pr.pr.number);
+        Ok(open_prs)
+    }
+
+    async fn rate_limit(&self) -> Result<RateLimit, Box<dyn std::error::Error>> {
+        Ok(*self.rate_limit.lock().unwrap())
+    }
 }
 
 #[cfg(test)]
@@ -184,7 +400,8 @@ mod tests {
             "feature-branch",
             "Add new feature",
             "This adds a cool feature",
-            "main"
+            "main",
+            false
         ).await.unwrap();
         
         assert_eq!(pr_info.number, 1);
@@ -202,7 +419,8 @@ mod tests {
             "feature-branch",
             "Add new feature",
             "This adds a cool feature",
-            "main"
+            "main",
+            false
         ).await.unwrap();
         
         // Update the PR
@@ -224,7 +442,8 @@ mod tests {
             "feature-branch",
             "Add new feature",
             "This adds a cool feature",
-            "main"
+            "main",
+            false
         ).await.unwrap();
         
         // Get PR status
@@ -239,8 +458,8 @@ mod tests {
         let mock = MockGitHubClient::new();
         
         // Create two PRs
-        let pr1 = mock.create_pr("branch1", "Feature 1", "Body 1", "main").await.unwrap();
-        let pr2 = mock.create_pr("branch2", "Feature 2", "Body 2", "main").await.unwrap();
+        let pr1 = mock.create_pr("branch1", "Feature 1", "Body 1", "main", false).await.unwrap();
+        let pr2 = mock.create_pr("branch2", "Feature 2", "Body 2", "main", false).await.unwrap();
         
         // Get statuses for both
         let statuses = mock.get_multiple_pr_statuses(&[pr1.number, pr2.number]).await.unwrap();
@@ -249,6 +468,42 @@ mod tests {
         assert_eq!(statuses[1].number, pr2.number);
     }
 
+    #[tokio::test]
+    async fn test_mock_set_draft() {
+        let mock = MockGitHubClient::new();
+        
+        // Create a draft PR
+        let pr_info = mock.create_pr("feature-branch", "Add new feature", "Body", "main", true).await.unwrap();
+        assert!(mock.get_pr_status(pr_info.number).await.unwrap().draft);
+        
+        // Mark it ready for review
+        mock.set_draft(pr_info.number, false).await.unwrap();
+        assert!(!mock.get_pr_status(pr_info.number).await.unwrap().draft);
+        
+        // Convert it back to draft
+        mock.set_draft(pr_info.number, true).await.unwrap();
+        assert!(mock.get_pr_status(pr_info.number).await.unwrap().draft);
+        
+        // Unknown PRs are an error
+        assert!(mock.set_draft(999, false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_pr_labels_reviewers_assignees() {
+        let mock = MockGitHubClient::new();
+        
+        let pr_info = mock.create_pr("feature-branch", "Add new feature", "Body", "main", false).await.unwrap();
+        
+        mock.add_labels(pr_info.number, &["bug".to_string()]).await.unwrap();
+        mock.request_reviewers(pr_info.number, &["alice".to_string(), "bob".to_string()]).await.unwrap();
+        mock.add_assignees(pr_info.number, &["carol".to_string()]).await.unwrap();
+        
+        assert_eq!(mock.get_pr_labels(pr_info.number), vec!["bug"]);
+        assert_eq!(mock.get_requested_reviewers(pr_info.number), vec!["alice", "bob"]);
+        assert_eq!(mock.get_pr_assignees(pr_info.number), vec!["carol"]);
+        assert!(mock.get_requested_reviewers(999).is_empty());
+    }
+
     #[test]
     fn test_mock_generate_pr_body() {
         let metadata = CommitMetadata::new_branch_created(
@@ -256,7 +511,7 @@ mod tests {
             "abc123".to_string()
         );
         
-        let body = generate_pr_body(&metadata, "Add feature\n\nThis is a test feature");
+        let body = generate_pr_body(&metadata, "Add feature\n\nThis is a test feature", None);
         assert!(body.contains("## Description"));
         assert!(body.contains("Generated by gitx"));
     }
@@ -273,6 +528,14 @@ mod tests {
             url: "https://github.com/test/repo/pull/42".to_string(),
             mergeable: None,
             draft: false,
+            additions: None,
+            deletions: None,
+            changed_files: None,
+            review_decision: ReviewDecision::None,
+            head_branch: String::new(),
+            head_sha: String::new(),
+            base_branch: String::new(),
+            merged_at: None,
         };
         
         mock.add_pr_status(42, status);
@@ -0,0 +1,95 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Everything below this line in the edit file is ignored, so Markdown headings survive
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// Let the user edit a PR's title and body in their git editor
+///
+/// The file is laid out like a commit message: the first line is the title and
+/// the rest is the body. Returns an error if the message is left unchanged or emptied.
+pub fn edit_pr_message(title: &str, body: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let repo = crate::git_utils::open_repo()?;
+    let path = repo.path().join("GITX_PR_EDITMSG");
+    
+    let original = format_pr_message(title, body);
+    std::fs::write(&path, format!(
+        "{}\n\n{}\n# Edit the PR title (first line) and body above.\n# Leave it unchanged or empty it to skip creating this PR.\n",
+        original, SCISSORS
+    ))?;
+    
+    let result = run_editor(&path).and_then(|()| Ok(std::fs::read_to_string(&path)?));
+    let _ = std::fs::remove_file(&path);
+    let edited = result?;
+    
+    let message = strip_instructions(&edited);
+    if message.is_empty() {
+        return Err("PR message is empty; not creating this PR".into());
+    }
+    if message == original {
+        return Err("PR message left unchanged; not creating this PR".into());
+    }
+    
+    Ok(parse_pr_message(&message))
+}
+
+/// Open `path` in the editor git would use (`GIT_EDITOR`, `core.editor`, `VISUAL`, `EDITOR`)
+fn run_editor(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new("git").args(["var", "GIT_EDITOR"]).output()?;
+    let editor = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || editor.is_empty() {
+        return Err("No editor configured; set GIT_EDITOR or core.editor".into());
+    }
+    
+    // Run through the shell like git does, so editors with arguments work
+    let status = Command::new("sh")
+        .args(["-c", &format!("{} \"$@\"", editor), &editor])
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        return Err(format!("Editor '{}' exited with {}", editor, status).into());
+    }
+    
+    Ok(())
+}
+
+/// Lay out a title and body as a single message
+fn format_pr_message(title: &str, body: &str) -> String {
+    format!("{}\n\n{}", title.trim(), body.trim()).trim().to_string()
+}
+
+/// Drop the scissors line and everything after it
+fn strip_instructions(edited: &str) -> String {
+    let kept = match edited.find(SCISSORS) {
+        Some(index) => &edited[..index],
+        None => edited,
+    };
+    kept.trim().to_string()
+}
+
+/// Split an edited message into the title (first line) and body (the rest)
+pub fn parse_pr_message(message: &str) -> (String, String) {
+    let message = message.trim();
+    let (title, body) = message.split_once('\n').unwrap_or((message, ""));
+    (title.trim().to_string(), body.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pr_message() {
+        assert_eq!(
+            parse_pr_message("Add feature\n\n## Description\n\nDetails\n"),
+            ("Add feature".to_string(), "## Description\n\nDetails".to_string())
+        );
+        assert_eq!(parse_pr_message("Title only"), ("Title only".to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_strip_instructions_keeps_markdown_headings() {
+        let edited = format!("Title\n\n## Heading\n\n{}\n# Edit the PR title\n", SCISSORS);
+        assert_eq!(strip_instructions(&edited), "Title\n\n## Heading");
+    }
+}
\ No newline at end of file
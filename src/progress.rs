@@ -0,0 +1,128 @@
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// The indicator being drawn, so output printed while a step runs can clear it first
+static ACTIVE: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Progress for an operation that works through several PRs, e.g. "Creating PR 2/5: <title>"
+///
+/// Drawn on stderr only when it is a terminal and `--quiet` isn't set; otherwise every
+/// method is a no-op. Output while it is drawn should go through [`println`] and [`eprintln`].
+pub struct Progress {
+    bar: Option<ProgressBar>,
+    total: usize,
+}
+
+impl Progress {
+    /// Start tracking `total` steps
+    pub fn new(total: usize) -> Self {
+        Self::with_enabled(total, should_show(std::io::stderr().is_terminal(), crate::config::is_quiet()))
+    }
+    
+    /// Start tracking `total` steps, drawing only if `enabled`
+    pub fn with_enabled(total: usize, enabled: bool) -> Self {
+        let bar = enabled.then(|| {
+            let bar = ProgressBar::with_draw_target(Some(total as u64), ProgressDrawTarget::stderr());
+            bar.set_style(ProgressStyle::with_template("{spinner} {msg}").unwrap_or_else(|_| ProgressStyle::default_spinner()));
+            *active_bar() = Some(bar.clone());
+            bar
+        });
+        Self { bar, total }
+    }
+    
+    /// Whether anything is drawn
+    pub fn is_enabled(&self) -> bool {
+        self.bar.is_some()
+    }
+    
+    /// Show the step about to run; `step` is zero-based
+    pub fn start(&self, step: usize, action: &str, title: &str) {
+        if let Some(bar) = &self.bar {
+            bar.set_message(format_step(step, self.total, action, title));
+            bar.tick();
+        }
+    }
+    
+    /// Mark the current step as done
+    pub fn inc(&self) {
+        if let Some(bar) = &self.bar {
+            bar.inc(1);
+        }
+    }
+    
+    /// Remove the indicator, leaving room for the final summary
+    pub fn finish(&self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+            active_bar().take();
+        }
+    }
+}
+
+fn active_bar() -> std::sync::MutexGuard<'static, Option<ProgressBar>> {
+    ACTIVE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Run `f` with the progress indicator, if any, cleared from the terminal
+fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    let bar = active_bar().clone();
+    match bar {
+        Some(bar) => bar.suspend(f),
+        None => f(),
+    }
+}
+
+/// Print a line to stdout without garbling the progress indicator
+pub fn println(line: impl std::fmt::Display) {
+    suspend(|| println!("{}", line));
+}
+
+/// Print a line to stderr without garbling the progress indicator
+pub fn eprintln(line: impl std::fmt::Display) {
+    suspend(|| eprintln!("{}", line));
+}
+
+/// Progress is only worth drawing for a person watching a terminal
+fn should_show(is_terminal: bool, quiet: bool) -> bool {
+    is_terminal && !quiet
+}
+
+/// Message for one step, e.g. "Creating PR 2/5: Add login"
+fn format_step(step: usize, total: usize, action: &str, title: &str) -> String {
+    format!("{} {}/{}: {}", action, step + 1, total, title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_hidden_without_terminal_or_when_quiet() {
+        assert!(should_show(true, false));
+        assert!(!should_show(false, false));
+        assert!(!should_show(true, true));
+        
+        let progress = Progress::with_enabled(3, false);
+        assert!(!progress.is_enabled());
+        progress.start(0, "Creating PR", "Add login");
+        progress.inc();
+        progress.finish();
+    }
+
+    #[test]
+    fn test_output_is_routed_around_drawn_indicator() {
+        let progress = Progress::with_enabled(2, true);
+        assert!(active_bar().is_some());
+        println("Created GitHub PR #1");
+        
+        progress.finish();
+        assert!(active_bar().is_none());
+        println("Cleanup complete!");
+    }
+
+    #[test]
+    fn test_format_step() {
+        assert_eq!(format_step(1, 5, "Creating PR", "Add login"), "Creating PR 2/5: Add login");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,225 @@
+use octocrab::Octocrab;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Repository gitx releases are published to
+const RELEASES_ROUTE: &str = "/repos/LiXuanqi/gitx/releases/latest";
+
+/// The fields of a GitHub release that updating needs
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a release
+#[derive(Debug, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    /// API URL; downloading it with `Accept: application/octet-stream` returns the file
+    pub url: String,
+}
+
+impl Release {
+    /// The release's version without the `v` tag prefix
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// Name of the release binary for the platform gitx was built for, e.g. `gitx-x86_64-unknown-linux-gnu`
+///
+/// Each binary is published next to a `<name>.sha256` file holding its checksum.
+pub fn asset_name() -> String {
+    format!("gitx-{}", env!("GITX_TARGET"))
+}
+
+/// Parse `1.2.3` (or `v1.2.3`) into its numeric parts, ignoring pre-release and build suffixes
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()?
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Check whether `latest` is a newer version than `current`
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// Client for the public GitHub API, authenticated when a github.com token is configured
+pub fn release_client() -> Result<Octocrab, Box<dyn std::error::Error>> {
+    // Releases always live on github.com, so an Enterprise token would be rejected
+    let token = crate::config::get_github_token().filter(|_| crate::config::get_github_host() == "github.com");
+    crate::http_client::build_octocrab(crate::http_client::GITHUB_API_URL, token)
+}
+
+/// Fetch the latest release, returning it only if it is newer than `current_version`
+pub async fn check_for_update_with_client(
+    octocrab: &Octocrab,
+    current_version: &str,
+) -> Result<Option<Release>, Box<dyn std::error::Error>> {
+    let release: Release = octocrab.get(RELEASES_ROUTE, None::<&()>).await?;
+
+    Ok(is_newer(release.version(), current_version).then_some(release))
+}
+
+/// Hex-encoded SHA-256 of `bytes`
+fn sha256_hex(bytes: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Check `bytes` against a `sha256sum`-style checksum file (`<hex>  <file name>`)
+pub fn verify_checksum(bytes: &[u8], checksum_file: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let expected = checksum_file
+        .split_whitespace()
+        .next()
+        .ok_or("Checksum file is empty")?
+        .to_lowercase();
+    let actual = sha256_hex(bytes);
+
+    if actual != expected {
+        return Err(format!("Checksum mismatch: expected {}, got {}", expected, actual).into());
+    }
+    Ok(())
+}
+
+/// Download this platform's binary from a release and verify its checksum
+pub async fn download_verified_binary_with_client(
+    octocrab: &Octocrab,
+    release: &Release,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let name = asset_name();
+    let binary = release
+        .asset(&name)
+        .ok_or_else(|| format!("Release {} has no binary for this platform ({})", release.tag_name, name))?;
+    let checksum = release
+        .asset(&format!("{}.sha256", name))
+        .ok_or_else(|| format!("Release {} has no checksum for {}", release.tag_name, name))?;
+
+    let checksum_file = octocrab.download(checksum.url.as_str(), "application/octet-stream").await?;
+    let bytes = octocrab.download(binary.url.as_str(), "application/octet-stream").await?;
+    verify_checksum(&bytes, &String::from_utf8_lossy(&checksum_file))?;
+
+    Ok(bytes)
+}
+
+/// Replace the running executable with `bytes`
+///
+/// The new binary is written next to the current one and renamed over it, so a failure
+/// part way leaves the installed gitx untouched.
+pub fn replace_current_exe(bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    let current = std::env::current_exe()?;
+    let staged = current.with_extension("new");
+    std::fs::write(&staged, bytes)?;
+    make_executable(&staged)?;
+
+    // Windows can't overwrite a running executable, but it can rename it out of the way
+    if cfg!(windows) {
+        std::fs::rename(&current, current.with_extension("old"))?;
+    }
+    std::fs::rename(&staged, &current)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client_for(server: &MockServer) -> Octocrab {
+        Octocrab::builder().base_uri(server.uri()).unwrap().build().unwrap()
+    }
+
+    async fn serve_latest_release(server: &MockServer, tag: &str, assets: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(path(RELEASES_ROUTE))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tag_name": tag,
+                "html_url": format!("https://github.com/LiXuanqi/gitx/releases/tag/{}", tag),
+                "assets": assets,
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.1.0"));
+        assert!(is_newer("v1.0.0", "0.9.9"));
+        assert!(is_newer("0.1.10", "0.1.9"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0-rc.1", "0.1.0"));
+        assert!(!is_newer("nightly", "0.1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_check_for_update_compares_versions() {
+        let server = MockServer::start().await;
+        serve_latest_release(&server, "v0.2.0", serde_json::json!([])).await;
+        let octocrab = client_for(&server);
+
+        let older = check_for_update_with_client(&octocrab, "0.1.0").await.unwrap();
+        let same = check_for_update_with_client(&octocrab, "0.2.0").await.unwrap();
+
+        assert_eq!(older.map(|release| release.version().to_string()), Some("0.2.0".to_string()));
+        assert!(same.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_download_verifies_checksum() {
+        let server = MockServer::start().await;
+        let name = asset_name();
+        serve_latest_release(&server, "v0.2.0", serde_json::json!([
+            { "name": name, "url": format!("{}/assets/1", server.uri()) },
+            { "name": format!("{}.sha256", name), "url": format!("{}/assets/2", server.uri()) },
+        ])).await;
+        Mock::given(method("GET"))
+            .and(path("/assets/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"new gitx".to_vec()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/assets/2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("{}  {}\n", sha256_hex(b"new gitx"), name)))
+            .mount(&server)
+            .await;
+        let octocrab = client_for(&server);
+
+        let release = check_for_update_with_client(&octocrab, "0.1.0").await.unwrap().unwrap();
+        let bytes = download_verified_binary_with_client(&octocrab, &release).await.unwrap();
+
+        assert_eq!(bytes, b"new gitx");
+        assert!(verify_checksum(b"tampered", &format!("{}  {}", sha256_hex(b"new gitx"), name)).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::github::GitHubPRStatus;
+use crate::metadata::{PRStatus, PRStatusInfo};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Aggregate numbers about the PRs gitx tracks, as printed by `gitx stats`
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct StackStats {
+    pub total: usize,
+    /// Branches pushed without a PR yet
+    pub local: usize,
+    pub open: usize,
+    pub merged: usize,
+    pub closed: usize,
+    /// Incremental updates per PR, across every tracked PR
+    pub average_incremental_updates: f64,
+    /// Hours from creation to merge, across merged PRs
+    pub average_hours_to_merge: Option<f64>,
+}
+
+/// Where a PR stands, preferring GitHub's view over stored metadata
+fn lifecycle_state(pr_status: &PRStatusInfo, github_statuses: Option<&HashMap<u64, GitHubPRStatus>>) -> PRStatus {
+    let github_state = pr_status
+        .pr_number
+        .and_then(|number| github_statuses?.get(&number))
+        .map(|status| status.state.as_str());
+
+    match github_state {
+        Some("merged") => PRStatus::PRMerged,
+        Some("closed") => PRStatus::Cancelled,
+        Some("open") => PRStatus::PRCreated,
+        _ => pr_status.status.clone(),
+    }
+}
+
+/// When a merged PR was merged: GitHub's timestamp if known, else when gitx last updated it
+fn merge_time(pr_status: &PRStatusInfo, github_statuses: Option<&HashMap<u64, GitHubPRStatus>>) -> DateTime<Utc> {
+    pr_status
+        .pr_number
+        .and_then(|number| github_statuses?.get(&number)?.merged_at)
+        .unwrap_or(pr_status.last_updated)
+}
+
+/// Aggregate the tracked PRs, using GitHub statuses when they were fetched
+pub fn compute_stats(
+    pr_statuses: &[PRStatusInfo],
+    github_statuses: Option<&HashMap<u64, GitHubPRStatus>>,
+) -> StackStats {
+    let mut stats = StackStats { total: pr_statuses.len(), ..StackStats::default() };
+    let mut merge_hours = Vec::new();
+
+    for pr_status in pr_statuses {
+        match lifecycle_state(pr_status, github_statuses) {
+            PRStatus::BranchCreated => stats.local += 1,
+            PRStatus::PRCreated => stats.open += 1,
+            PRStatus::Cancelled => stats.closed += 1,
+            PRStatus::PRMerged => {
+                stats.merged += 1;
+                let elapsed = merge_time(pr_status, github_statuses) - pr_status.created_at;
+                merge_hours.push(elapsed.num_minutes() as f64 / 60.0);
+            }
+        }
+    }
+
+    if !pr_statuses.is_empty() {
+        let updates: usize = pr_statuses.iter().map(|pr| pr.incremental_count).sum();
+        stats.average_incremental_updates = updates as f64 / pr_statuses.len() as f64;
+    }
+    if !merge_hours.is_empty() {
+        stats.average_hours_to_merge = Some(merge_hours.iter().sum::<f64>() / merge_hours.len() as f64);
+    }
+
+    stats
+}
+
+/// Render a duration in hours as `3.5h`, or `2.1d` once it passes two days
+fn format_hours(hours: f64) -> String {
+    if hours >= 48.0 {
+        format!("{:.1}d", hours / 24.0)
+    } else {
+        format!("{:.1}h", hours)
+    }
+}
+
+/// Human-readable summary of the stats
+pub fn format_stats(stats: &StackStats) -> String {
+    let mut lines = vec![
+        format!("PRs tracked:          {}", stats.total),
+        format!("  Open:               {}", stats.open),
+        format!("  Merged:             {}", stats.merged),
+        format!("  Closed:             {}", stats.closed),
+        format!("  Local only:         {}", stats.local),
+        format!("Avg. updates per PR:  {:.1}", stats.average_incremental_updates),
+    ];
+    if let Some(hours) = stats.average_hours_to_merge {
+        lines.push(format!("Avg. time to merge:   {}", format_hours(hours)));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::ReviewDecision;
+
+    fn pr(pr_number: Option<u64>, status: PRStatus, incremental_count: usize, age_hours: i64, updated_hours: i64) -> PRStatusInfo {
+        let now = Utc::now();
+        PRStatusInfo {
+            commit_id: format!("{:?}", pr_number),
+            commit_message: "Commit".to_string(),
+            branch_name: "gitx/test/commit".to_string(),
+            pr_number,
+            status,
+            created_at: now - chrono::Duration::hours(age_hours),
+            last_updated: now - chrono::Duration::hours(updated_hours),
+            incremental_count,
+            latest_incremental: None,
+            base_branch: None,
+            last_known_status: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_stats_from_metadata() {
+        let prs = vec![
+            pr(Some(1), PRStatus::PRMerged, 2, 30, 20),
+            pr(Some(2), PRStatus::PRMerged, 0, 10, 6),
+            pr(Some(3), PRStatus::PRCreated, 1, 5, 1),
+            pr(None, PRStatus::BranchCreated, 0, 1, 1),
+        ];
+
+        let stats = compute_stats(&prs, None);
+
+        assert_eq!(stats.total, 4);
+        assert_eq!((stats.open, stats.merged, stats.closed, stats.local), (1, 2, 0, 1));
+        assert_eq!(stats.average_incremental_updates, 0.75);
+        assert_eq!(stats.average_hours_to_merge, Some(7.0));
+    }
+
+    #[test]
+    fn test_compute_stats_prefers_github() {
+        let prs = vec![pr(Some(1), PRStatus::PRCreated, 0, 30, 20)];
+        let merged_at = prs[0].created_at + chrono::Duration::hours(3);
+        let github_statuses = HashMap::from([(1, GitHubPRStatus {
+            number: 1,
+            state: "merged".to_string(),
+            title: String::new(),
+            url: String::new(),
+            mergeable: None,
+            draft: false,
+            additions: None,
+            deletions: None,
+            changed_files: None,
+            review_decision: ReviewDecision::None,
+            head_branch: String::new(),
+            head_sha: String::new(),
+            base_branch: String::new(),
+            merged_at: Some(merged_at),
+        })]);
+
+        let stats = compute_stats(&prs, Some(&github_statuses));
+
+        assert_eq!((stats.open, stats.merged), (0, 1));
+        assert_eq!(stats.average_hours_to_merge, Some(3.0));
+    }
+
+    #[test]
+    fn test_format_hours() {
+        assert_eq!(format_hours(3.5), "3.5h");
+        assert_eq!(format_hours(60.0), "2.5d");
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Duration, Utc};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use crate::github::GitHubPRStatus;
+
+/// A PR status and when it was fetched from GitHub
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedStatus {
+    fetched_at: DateTime<Utc>,
+    status: GitHubPRStatus,
+}
+
+/// On-disk cache of GitHub PR statuses, keyed by PR number
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StatusCache {
+    entries: HashMap<u64, CachedStatus>,
+}
+
+impl StatusCache {
+    /// Load the cache from disk; a missing or unreadable cache starts empty
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+    
+    /// Write the cache to disk, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+    
+    /// Get a cached status if it was fetched less than `ttl` before `now`
+    pub fn get_fresh(&self, pr_number: u64, ttl: Duration, now: DateTime<Utc>) -> Option<&GitHubPRStatus> {
+        self.entries
+            .get(&pr_number)
+            .filter(|entry| now.signed_duration_since(entry.fetched_at) < ttl)
+            .map(|entry| &entry.status)
+    }
+    
+    /// Record a status fetched at `now`
+    pub fn insert(&mut self, status: GitHubPRStatus, now: DateTime<Utc>) {
+        self.entries.insert(status.number, CachedStatus { fetched_at: now, status });
+    }
+}
+
+/// Location of the status cache for the current repository (`.git/gitx/status-cache.json`)
+pub fn cache_path() -> Option<PathBuf> {
+    let repo = Repository::open(".").ok()?;
+    Some(repo.path().join("gitx").join("status-cache.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::ReviewDecision;
+
+    fn status(number: u64, state: &str) -> GitHubPRStatus {
+        GitHubPRStatus {
+            number,
+            state: state.to_string(),
+            title: format!("PR {}", number),
+            url: format!("https://github.com/owner/repo/pull/{}", number),
+            mergeable: Some(true),
+            draft: false,
+            additions: None,
+            deletions: None,
+            changed_files: None,
+            review_decision: ReviewDecision::None,
+            head_branch: String::new(),
+            head_sha: String::new(),
+            base_branch: String::new(),
+            merged_at: None,
+        }
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let now = Utc::now();
+        let mut cache = StatusCache::default();
+        cache.insert(status(1, "open"), now - Duration::seconds(30));
+        
+        let hit = cache.get_fresh(1, Duration::seconds(60), now).expect("Entry should be fresh");
+        assert_eq!(hit.state, "open");
+    }
+
+    #[test]
+    fn test_cache_miss() {
+        let now = Utc::now();
+        let mut cache = StatusCache::default();
+        cache.insert(status(1, "open"), now);
+        
+        assert!(cache.get_fresh(2, Duration::seconds(60), now).is_none());
+    }
+
+    #[test]
+    fn test_cache_expiry() {
+        let now = Utc::now();
+        let mut cache = StatusCache::default();
+        cache.insert(status(1, "open"), now - Duration::seconds(61));
+        
+        assert!(cache.get_fresh(1, Duration::seconds(60), now).is_none());
+        
+        // Refetching replaces the stale entry
+        cache.insert(status(1, "merged"), now);
+        assert_eq!(cache.get_fresh(1, Duration::seconds(60), now).unwrap().state, "merged");
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("gitx").join("status-cache.json");
+        let now = Utc::now();
+        
+        let mut cache = StatusCache::default();
+        cache.insert(status(7, "merged"), now);
+        cache.save(&path).expect("Failed to save cache");
+        
+        let loaded = StatusCache::load(&path);
+        assert_eq!(loaded.get_fresh(7, Duration::seconds(60), now).unwrap().state, "merged");
+        
+        // Missing files load as an empty cache
+        assert!(StatusCache::load(&temp_dir.path().join("missing.json")).entries.is_empty());
+    }
+}
\ No newline at end of file
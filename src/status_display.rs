@@ -1,23 +1,220 @@
-use crate::metadata::{PRStatusInfo, PRStatus};
-use crate::github::{GitHubClient, GitHubPRStatus, GitHubClientTrait};
+use crate::metadata::{GitHubPRStatusSnapshot, PRStatusInfo, PRStatus};
+use crate::github::{GitHubClient, GitHubPRStatus, GitHubClientTrait, OpenPRInfo, RateLimit, ReviewDecision};
 use crate::client_factory;
+use crate::status_cache::{self, StatusCache};
 use std::collections::HashMap;
+use std::io::IsTerminal;
+use inquire::Confirm;
 use chrono::{DateTime, Utc};
+use owo_colors::{AnsiColors, OwoColorize};
+
+/// When to colorize status output
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Colorize when stdout is a terminal and NO_COLOR is unset
+    Auto,
+    /// Always colorize
+    Always,
+    /// Never colorize
+    Never,
+}
+
+impl ColorChoice {
+    /// Decide whether to emit ANSI colors for stdout
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+                !no_color && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Paint text with one of the color names from `get_status_display`
+fn paint(text: &str, color: &str, enabled: bool) -> String {
+    if !enabled {
+        return text.to_string();
+    }
+    
+    let ansi = match color {
+        "green" => AnsiColors::Green,
+        "yellow" => AnsiColors::Yellow,
+        "red" => AnsiColors::Red,
+        "purple" => AnsiColors::Magenta,
+        "blue" => AnsiColors::Blue,
+        _ => AnsiColors::BrightBlack,
+    };
+    text.color(ansi).to_string()
+}
+
+/// Order in which `gitx prs` lists the stack
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Creation order, bottom of the stack first
+    Created,
+    /// Most recently updated first
+    Updated,
+    /// Ascending PR number, local-only entries last
+    Number,
+}
+
+/// Subset of the stack shown by `gitx prs`
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum StatusFilter {
+    /// PRs open on GitHub (including drafts)
+    Open,
+    /// PRs that have been merged
+    Merged,
+    /// Draft PRs
+    Draft,
+    /// Branches without a GitHub PR
+    Local,
+}
+
+impl StatusFilter {
+    /// Check whether a PR matches, preferring GitHub state over local metadata
+    fn matches(self, pr_status: &PRStatusInfo, github_statuses: Option<&HashMap<u64, GitHubPRStatus>>) -> bool {
+        let github_status = pr_status
+            .pr_number
+            .and_then(|number| github_statuses.and_then(|statuses| statuses.get(&number)));
+        
+        match (self, github_status) {
+            (StatusFilter::Local, _) => pr_status.pr_number.is_none(),
+            (StatusFilter::Open, Some(github)) => github.state == "open",
+            (StatusFilter::Open, None) => pr_status.status == PRStatus::PRCreated,
+            (StatusFilter::Merged, Some(github)) => github.state == "merged",
+            (StatusFilter::Merged, None) => pr_status.status == PRStatus::PRMerged,
+            (StatusFilter::Draft, Some(github)) => github.state == "open" && github.draft,
+            (StatusFilter::Draft, None) => false, // Draft state is only known from GitHub
+        }
+    }
+    
+    /// Whether this filter depends on PR state fetched from GitHub
+    fn needs_github(self) -> bool {
+        self != StatusFilter::Local
+    }
+}
+
+/// Options controlling how `gitx prs` renders the stack
+#[derive(Debug, Clone)]
+pub struct StatusDisplayOptions {
+    pub color: ColorChoice,
+    /// Print one tab-separated line per PR instead of the decorated view
+    pub porcelain: bool,
+    /// Print the tracked PRs as CSV rows with a header
+    pub csv: bool,
+    pub sort: SortKey,
+    pub filter: Option<StatusFilter>,
+    /// Ignore cached GitHub statuses and fetch fresh ones
+    pub refresh: bool,
+    /// Also list open PRs on GitHub that have no local metadata
+    pub remote: bool,
+    /// Only show PRs that have gone `gitx.prs.staleDays` without updates
+    pub stale: bool,
+}
+
+/// How long cached GitHub statuses are reused; lifetimes too long to represent never expire
+fn status_cache_ttl(seconds: u64) -> chrono::TimeDelta {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(chrono::TimeDelta::try_seconds)
+        .unwrap_or(chrono::TimeDelta::MAX)
+}
+
+/// Check whether a PR is still open but hasn't been updated for `stale_days`
+///
+/// Merged and closed PRs are never stale; GitHub's state is preferred over the last known one.
+fn is_stale(
+    pr_status: &PRStatusInfo,
+    github_statuses: Option<&HashMap<u64, GitHubPRStatus>>,
+    stale_days: u64,
+    now: DateTime<Utc>,
+) -> bool {
+    let github_state = pr_status
+        .pr_number
+        .and_then(|number| github_statuses.and_then(|statuses| statuses.get(&number)))
+        .map(|github| github.state.as_str())
+        .or(pr_status.last_known_status.as_ref().map(|snapshot| snapshot.state.as_str()));
+    let open = match github_state {
+        Some(state) => state.eq_ignore_ascii_case("open"),
+        None => pr_status.status != PRStatus::PRMerged,
+    };
+    
+    // A threshold too long to represent is never reached
+    let Some(threshold) = i64::try_from(stale_days).ok().and_then(chrono::TimeDelta::try_days) else {
+        return false;
+    };
+    open && now.signed_duration_since(pr_status.last_updated) > threshold
+}
+
+/// Filter and order the stack before rendering
+fn sort_and_filter(
+    mut pr_statuses: Vec<PRStatusInfo>,
+    github_statuses: Option<&HashMap<u64, GitHubPRStatus>>,
+    sort: SortKey,
+    filter: Option<StatusFilter>,
+) -> Vec<PRStatusInfo> {
+    if let Some(filter) = filter {
+        pr_statuses.retain(|pr_status| filter.matches(pr_status, github_statuses));
+    }
+    
+    match sort {
+        SortKey::Created => pr_statuses.sort_by_key(|pr| pr.created_at),
+        SortKey::Updated => pr_statuses.sort_by_key(|pr| std::cmp::Reverse(pr.last_updated)),
+        SortKey::Number => pr_statuses.sort_by_key(|pr| (pr.pr_number.is_none(), pr.pr_number, pr.created_at)),
+    }
+    
+    pr_statuses
+}
 
 /// Display the status of all stacked PRs
-pub async fn display_status() -> Result<(), Box<dyn std::error::Error>> {
+pub async fn display_status(options: &StatusDisplayOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let use_color = options.color.enabled();
     let pr_statuses = crate::metadata::get_all_pr_status()?;
     
+    let untracked = if options.remote {
+        match get_untracked_prs(&pr_statuses).await {
+            Ok(untracked) => untracked,
+            Err(e) => {
+                eprintln!("Warning: Could not list open PRs on GitHub: {}", e);
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    
+    if pr_statuses.is_empty() && !untracked.is_empty() {
+        if options.porcelain {
+            untracked.iter().for_each(|open_pr| println!("{}", format_untracked_porcelain_line(open_pr)));
+        } else {
+            display_untracked_prs(&untracked).await;
+        }
+        return Ok(());
+    }
+    
     if pr_statuses.is_empty() {
-        println!("No stacked PRs found.");
-        println!("Run 'gitx diff' to create PR branches from your commits.");
+        if options.csv {
+            println!("{}", CSV_HEADER);
+        } else if !options.porcelain {
+            println!("No stacked PRs found.");
+            println!("Run 'gitx diff' to create PR branches from your commits.");
+        }
         return Ok(());
     }
     
-    // Try to get GitHub status if token is available
-    let github_statuses = if crate::github::check_github_token() {
-        match get_github_statuses(&pr_statuses).await {
-            Ok(statuses) => Some(statuses),
+    // Try to get GitHub status if token is available (machine-readable output only needs it to filter)
+    let machine_readable = options.porcelain || options.csv;
+    let wants_github = !machine_readable || options.filter.is_some_and(StatusFilter::needs_github);
+    let github_statuses = if wants_github && crate::github::check_github_token() {
+        match get_github_statuses(&pr_statuses, options.refresh).await {
+            Ok(statuses) => {
+                snapshot_github_statuses(&pr_statuses, &statuses, Utc::now());
+                Some(statuses)
+            }
             Err(e) => {
                 eprintln!("Warning: Could not fetch GitHub PR statuses: {}", e);
                 None
@@ -27,10 +224,46 @@ pub async fn display_status() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
     
+    let stale_days = crate::config::get_prs_stale_days();
+    let now = Utc::now();
+    let mut pr_statuses = sort_and_filter(pr_statuses, github_statuses.as_ref(), options.sort, options.filter);
+    if options.stale {
+        pr_statuses.retain(|pr_status| is_stale(pr_status, github_statuses.as_ref(), stale_days, now));
+    }
+    
+    if options.csv {
+        println!("{}", CSV_HEADER);
+        for pr_status in &pr_statuses {
+            println!("{}", format_csv_row(pr_status));
+        }
+        return Ok(());
+    }
+    
+    if options.porcelain {
+        for pr_status in &pr_statuses {
+            println!("{}", format_porcelain_line(pr_status));
+        }
+        untracked.iter().for_each(|open_pr| println!("{}", format_untracked_porcelain_line(open_pr)));
+        return Ok(());
+    }
+    
+    if pr_statuses.is_empty() && options.stale && options.filter.is_none() {
+        println!("No stale PRs (none without updates for over {} days).", stale_days);
+        return Ok(());
+    }
+    if pr_statuses.is_empty() {
+        println!("No stacked PRs match the filter.");
+        return Ok(());
+    }
+    
     println!("📋 Stacked PR Status\n");
+    if crate::config::is_offline() {
+        println!("📴 Offline: showing stored metadata only\n");
+    }
     
     for (i, pr_status) in pr_statuses.iter().enumerate() {
-        display_pr_status(pr_status, github_statuses.as_ref(), i == 0)?;
+        let stale = is_stale(pr_status, github_statuses.as_ref(), stale_days, now);
+        display_pr_status(pr_status, github_statuses.as_ref(), stale, use_color)?;
         
         if i < pr_statuses.len() - 1 {
             println!(); // Add spacing between PRs
@@ -39,14 +272,128 @@ pub async fn display_status() -> Result<(), Box<dyn std::error::Error>> {
     
     // Show summary
     println!("\n{}", "─".repeat(60));
-    display_summary(&pr_statuses, github_statuses.as_ref());
+    display_summary(&pr_statuses, github_statuses.as_ref(), stale_days, now);
+    
+    if !untracked.is_empty() {
+        println!();
+        display_untracked_prs(&untracked).await;
+    }
+    
+    // Only worth an extra request when GitHub was reachable in the first place
+    if crate::config::verbosity() == crate::config::Verbosity::Verbose && github_statuses.is_some() {
+        display_rate_limit().await;
+    }
     
     Ok(())
 }
 
+/// Warn once fewer than this many GitHub API requests are left
+const RATE_LIMIT_WARNING_THRESHOLD: u64 = 100;
+
+/// Summarize the rate limit as `GitHub API: 4200/5000 requests left, resets in 42m`
+fn format_rate_limit(rate_limit: &RateLimit, now: DateTime<Utc>) -> String {
+    let minutes = (rate_limit.reset - now).num_minutes().max(0);
+    format!(
+        "GitHub API: {}/{} requests left, resets in {}m",
+        rate_limit.remaining, rate_limit.limit, minutes
+    )
+}
+
+/// Warning to show when the rate limit is close to running out
+fn rate_limit_warning(rate_limit: &RateLimit) -> Option<String> {
+    (rate_limit.remaining < RATE_LIMIT_WARNING_THRESHOLD).then(|| {
+        format!(
+            "Only {} GitHub API requests left; gitx commands may fail until the limit resets",
+            rate_limit.remaining
+        )
+    })
+}
+
+async fn display_rate_limit() {
+    let rate_limit = match client_factory::create_github_client().await {
+        Ok(github_client) => github_client.rate_limit().await,
+        Err(e) => Err(e),
+    };
+    match rate_limit {
+        Ok(rate_limit) => {
+            println!("\n⏱️  {}", format_rate_limit(&rate_limit, Utc::now()));
+            if let Some(warning) = rate_limit_warning(&rate_limit) {
+                eprintln!("Warning: {}", warning);
+            }
+        }
+        Err(e) => eprintln!("Warning: Could not check the GitHub rate limit: {}", e),
+    }
+}
+
+/// Open PRs on GitHub whose branch no local metadata tracks
+pub async fn find_untracked_prs(
+    pr_statuses: &[PRStatusInfo],
+    github_client: &dyn GitHubClientTrait,
+) -> Result<Vec<OpenPRInfo>, Box<dyn std::error::Error>> {
+    let open_prs = github_client.list_open_prs().await?;
+    Ok(open_prs
+        .into_iter()
+        .filter(|open_pr| !pr_statuses.iter().any(|pr_status| pr_status.branch_name == open_pr.branch_name))
+        .collect())
+}
+
+async fn get_untracked_prs(pr_statuses: &[PRStatusInfo]) -> Result<Vec<OpenPRInfo>, Box<dyn std::error::Error>> {
+    let github_client = client_factory::create_github_client().await?;
+    find_untracked_prs(pr_statuses, &*github_client).await
+}
+
+/// List PRs gitx isn't tracking and, when run interactively, offer to adopt them
+async fn display_untracked_prs(untracked: &[OpenPRInfo]) {
+    println!("🌐 Open PRs not tracked by gitx\n");
+    for open_pr in untracked {
+        println!("❔ #{} {}", open_pr.pr.number, open_pr.pr.title);
+        println!("   Branch: {}", open_pr.branch_name);
+    }
+    
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return;
+    }
+    let adopt = Confirm::new(&format!("Adopt {} untracked PR(s) into gitx metadata?", untracked.len()))
+        .with_default(false)
+        .prompt()
+        .unwrap_or(false);
+    if !adopt {
+        return;
+    }
+    
+    for open_pr in untracked {
+        match crate::git_ops::adopt_pr(open_pr.pr.number).await {
+            Ok(_) => println!("📥 Adopted PR #{} ({})", open_pr.pr.number, open_pr.branch_name),
+            Err(e) => eprintln!("Warning: Could not adopt PR #{}: {}", open_pr.pr.number, e),
+        }
+    }
+}
+
+/// Get the review decision for each PR; failures are reported and treated as no decision
+async fn get_review_decisions(
+    github_client: &dyn GitHubClientTrait,
+    pr_numbers: &[u64],
+) -> HashMap<u64, ReviewDecision> {
+    let mut decisions = HashMap::new();
+    for &pr_number in pr_numbers {
+        match github_client.get_review_decision(pr_number).await {
+            Ok(decision) => {
+                decisions.insert(pr_number, decision);
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to get review decision for PR #{}: {}", pr_number, e);
+            }
+        }
+    }
+    decisions
+}
+
 /// Get GitHub PR statuses for all PRs that have numbers
-async fn get_github_statuses(
+/// Statuses fetched within `gitx.status.cacheSeconds` are served from the on-disk cache
+/// unless `refresh` is set
+pub(crate) async fn get_github_statuses(
     pr_statuses: &[PRStatusInfo],
+    refresh: bool,
 ) -> Result<HashMap<u64, GitHubPRStatus>, Box<dyn std::error::Error>> {
     let pr_numbers: Vec<u64> = pr_statuses
         .iter()
@@ -57,35 +404,129 @@ async fn get_github_statuses(
         return Ok(HashMap::new());
     }
     
-    let github_client = client_factory::create_github_client().await?;
-    let statuses = github_client.get_multiple_pr_statuses(&pr_numbers).await?;
+    let cache_path = status_cache::cache_path();
+    let mut cache = cache_path.as_deref().map(StatusCache::load).unwrap_or_default();
+    let ttl = status_cache_ttl(crate::config::get_status_cache_seconds());
+    let now = Utc::now();
     
     let mut status_map = HashMap::new();
-    for status in statuses {
-        status_map.insert(status.number, status);
+    let mut to_fetch = Vec::new();
+    for pr_number in pr_numbers {
+        match cache.get_fresh(pr_number, ttl, now) {
+            Some(status) if !refresh => {
+                status_map.insert(pr_number, status.clone());
+            }
+            _ => to_fetch.push(pr_number),
+        }
+    }
+    
+    if !to_fetch.is_empty() {
+        let github_client = client_factory::create_github_client().await?;
+        let (statuses, decisions) = tokio::join!(
+            github_client.get_multiple_pr_statuses(&to_fetch),
+            get_review_decisions(&*github_client, &to_fetch),
+        );
+        
+        for mut status in statuses? {
+            status.review_decision = decisions.get(&status.number).copied().unwrap_or_default();
+            cache.insert(status.clone(), now);
+            status_map.insert(status.number, status);
+        }
+        
+        if let Some(path) = &cache_path
+            && let Err(e) = cache.save(path)
+        {
+            eprintln!("Warning: Could not write status cache: {}", e);
+        }
     }
     
     Ok(status_map)
 }
 
-/// Display status for a single PR
-fn display_pr_status(
+/// Remember each PR's GitHub state in its metadata, so offline runs can still show it
+///
+/// This is the only note write `gitx prs` makes, and it only happens when a PR's state
+/// changed, so repeated runs leave the notes alone. The snapshot's time is therefore when
+/// the state was first seen, not the latest fetch.
+fn snapshot_github_statuses(
+    pr_statuses: &[PRStatusInfo],
+    github_statuses: &HashMap<u64, GitHubPRStatus>,
+    now: DateTime<Utc>,
+) {
+    for pr_status in pr_statuses {
+        let Some(github_status) = pr_status.pr_number.and_then(|number| github_statuses.get(&number)) else {
+            continue;
+        };
+        let snapshot = GitHubPRStatusSnapshot::from_status(github_status, now);
+        if pr_status.last_known_status.as_ref().is_some_and(|known| known.same_state(&snapshot)) {
+            continue;
+        }
+        
+        let recorded = git2::Oid::from_str(&pr_status.commit_id)
+            .map_err(|e| e.into())
+            .and_then(|oid| crate::metadata::record_last_known_status(&oid, snapshot));
+        if let Err(e) = recorded {
+            eprintln!("Warning: Could not save the status of PR #{}: {}", github_status.number, e);
+        }
+    }
+}
+
+/// Summarize a PR's size as `(+120 -30, 4 files)`, if GitHub reported it
+fn format_pr_size(github_status: &GitHubPRStatus) -> Option<String> {
+    let (additions, deletions) = (github_status.additions?, github_status.deletions?);
+    match github_status.changed_files {
+        Some(1) => Some(format!("(+{} -{}, 1 file)", additions, deletions)),
+        Some(files) => Some(format!("(+{} -{}, {} files)", additions, deletions, files)),
+        None => Some(format!("(+{} -{})", additions, deletions)),
+    }
+}
+
+/// Icon and label for a PR's review decision, or `None` when there isn't one
+fn format_review_decision(decision: ReviewDecision) -> Option<&'static str> {
+    match decision {
+        ReviewDecision::Approved => Some("✅ Approved"),
+        ReviewDecision::ChangesRequested => Some("❌ Changes requested"),
+        ReviewDecision::ReviewRequired => Some("👀 Review required"),
+        ReviewDecision::None => None,
+    }
+}
+
+/// Marker appended to PRs that have gone `gitx.prs.staleDays` without updates
+const STALE_MARKER: &str = "⏳ stale";
+
+/// The first line shown for a PR: state icon, short commit, title and stale marker
+fn format_pr_line(
     pr_status: &PRStatusInfo,
     github_statuses: Option<&HashMap<u64, GitHubPRStatus>>,
-    _is_first: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+    stale: bool,
+    use_color: bool,
+) -> String {
     let commit_short = &pr_status.commit_id[..8];
     let title = pr_status.commit_message.lines().next().unwrap_or("Untitled");
     
-    // Status indicator
-    let (status_icon, _status_color) = get_status_display(&pr_status.status, github_statuses, pr_status.pr_number);
+    // Status indicator (GitHub's last known state when it can't be reached now)
+    let (status_icon, status_color) = match (&pr_status.last_known_status, github_statuses) {
+        (Some(snapshot), None) => github_state_display(&snapshot.state, snapshot.draft),
+        _ => get_status_display(&pr_status.status, github_statuses, pr_status.pr_number),
+    };
     
+    let mut line = format!("🔗 {} {} {}", status_icon, paint(commit_short, status_color, use_color), title);
+    if stale {
+        line.push(' ');
+        line.push_str(&paint(STALE_MARKER, "yellow", use_color));
+    }
+    line
+}
+
+/// Display status for a single PR
+fn display_pr_status(
+    pr_status: &PRStatusInfo,
+    github_statuses: Option<&HashMap<u64, GitHubPRStatus>>,
+    stale: bool,
+    use_color: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Main PR line
-    println!("🔗 {} {} {}", 
-        status_icon,
-        commit_short,
-        title
-    );
+    println!("{}", format_pr_line(pr_status, github_statuses, stale, use_color));
     
     // Branch info (remote-only for GitHub PRs)
     if pr_status.pr_number.is_some() {
@@ -95,32 +536,8 @@ fn display_pr_status(
     }
     
     // GitHub PR info if available
-    if let Some(pr_number) = pr_status.pr_number {
-        if let Some(github_statuses) = github_statuses {
-            if let Some(github_status) = github_statuses.get(&pr_number) {
-                println!("   🐙 PR #{}: {} ({})", 
-                    pr_number, 
-                    github_status.state.to_uppercase(),
-                    github_status.url
-                );
-                
-                if github_status.draft {
-                    println!("   📝 Draft PR");
-                }
-                
-                if let Some(mergeable) = github_status.mergeable {
-                    if !mergeable {
-                        println!("   ⚠️  Has merge conflicts");
-                    }
-                }
-            } else {
-                println!("   🐙 PR #{}: Status unknown", pr_number);
-            }
-        } else {
-            println!("   🐙 PR #{}", pr_number);
-        }
-    } else {
-        println!("   📋 Local only (no GitHub PR)");
+    for line in github_status_lines(pr_status, github_statuses) {
+        println!("   {}", line);
     }
     
     // Timing info
@@ -153,8 +570,71 @@ fn display_pr_status(
     Ok(())
 }
 
+/// Lines describing a PR on GitHub, falling back to its last known state when GitHub wasn't reached
+fn github_status_lines(pr_status: &PRStatusInfo, github_statuses: Option<&HashMap<u64, GitHubPRStatus>>) -> Vec<String> {
+    let Some(pr_number) = pr_status.pr_number else {
+        return vec!["📋 Local only (no GitHub PR)".to_string()];
+    };
+    
+    let Some(github_statuses) = github_statuses else {
+        return match &pr_status.last_known_status {
+            Some(snapshot) => {
+                let mut lines = vec![format!(
+                    "🐙 PR #{}: {} (last known, since {})",
+                    pr_number,
+                    snapshot.state.to_uppercase(),
+                    snapshot.as_of.format("%Y-%m-%d %H:%M UTC")
+                )];
+                if snapshot.draft {
+                    lines.push("📝 Draft PR".to_string());
+                }
+                if snapshot.mergeable == Some(false) {
+                    lines.push("⚠️  Has merge conflicts".to_string());
+                }
+                lines
+            }
+            None => vec![format!("🐙 PR #{}", pr_number)],
+        };
+    };
+    
+    let Some(github_status) = github_statuses.get(&pr_number) else {
+        return vec![format!("🐙 PR #{}: Status unknown", pr_number)];
+    };
+    
+    let size = format_pr_size(github_status)
+        .map(|size| format!(" {}", size))
+        .unwrap_or_default();
+    let mut lines = vec![format!(
+        "🐙 PR #{}: {} ({}){}",
+        pr_number,
+        github_status.state.to_uppercase(),
+        github_status.url,
+        size
+    )];
+    if github_status.draft {
+        lines.push("📝 Draft PR".to_string());
+    }
+    if let Some(review) = format_review_decision(github_status.review_decision) {
+        lines.push(review.to_string());
+    }
+    if github_status.mergeable == Some(false) {
+        lines.push("⚠️  Has merge conflicts".to_string());
+    }
+    lines
+}
+
+/// Icon and color for a PR state reported by GitHub
+fn github_state_display(state: &str, draft: bool) -> (&'static str, &'static str) {
+    match state {
+        "open" => if draft { ("🚧", "yellow") } else { ("🟢", "green") },
+        "closed" => ("🔴", "red"),
+        "merged" => ("🟣", "purple"),
+        _ => ("❓", "gray"),
+    }
+}
+
 /// Get status display information
-fn get_status_display(
+pub(crate) fn get_status_display(
     local_status: &PRStatus,
     github_statuses: Option<&HashMap<u64, GitHubPRStatus>>,
     pr_number: Option<u64>,
@@ -162,12 +642,7 @@ fn get_status_display(
     // Check GitHub status first if available
     if let (Some(github_statuses), Some(pr_num)) = (github_statuses, pr_number) {
         if let Some(github_status) = github_statuses.get(&pr_num) {
-            return match github_status.state.as_str() {
-                "open" => if github_status.draft { ("🚧", "yellow") } else { ("🟢", "green") },
-                "closed" => ("🔴", "red"),
-                "merged" => ("🟣", "purple"),
-                _ => ("❓", "gray"),
-            };
+            return github_state_display(&github_status.state, github_status.draft);
         }
     }
     
@@ -180,10 +655,67 @@ fn get_status_display(
     }
 }
 
+/// Format a PR as `commit_sha<TAB>status<TAB>pr_number<TAB>branch<TAB>title`
+/// The column order is part of the porcelain contract and must not change;
+/// new fields may only be appended
+fn format_porcelain_line(pr_status: &PRStatusInfo) -> String {
+    let title = pr_status.commit_message.lines().next().unwrap_or("").replace('\t', " ");
+    let pr_number = pr_status.pr_number.map(|n| n.to_string()).unwrap_or_default();
+    
+    format!(
+        "{}\t{}\t{}\t{}\t{}",
+        pr_status.commit_id,
+        pr_status.status.as_str(),
+        pr_number,
+        pr_status.branch_name,
+        title
+    )
+}
+
+/// Columns of `gitx prs --csv`
+const CSV_HEADER: &str = "commit,branch,pr_number,status,created_at,incremental_count";
+
+/// Quote a CSV field if it contains a comma, quote or line break, doubling any quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format a PR as a row under `CSV_HEADER`
+fn format_csv_row(pr_status: &PRStatusInfo) -> String {
+    [
+        pr_status.commit_id.clone(),
+        pr_status.branch_name.clone(),
+        pr_status.pr_number.map(|n| n.to_string()).unwrap_or_default(),
+        pr_status.status.as_str().to_string(),
+        pr_status.created_at.to_rfc3339(),
+        pr_status.incremental_count.to_string(),
+    ]
+    .iter()
+    .map(|field| csv_field(field))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Format an untracked PR like `format_porcelain_line`, with no commit and an `untracked` status
+fn format_untracked_porcelain_line(open_pr: &OpenPRInfo) -> String {
+    format!(
+        "\tuntracked\t{}\t{}\t{}",
+        open_pr.pr.number,
+        open_pr.branch_name,
+        open_pr.pr.title.replace('\t', " ")
+    )
+}
+
 /// Display summary statistics
 fn display_summary(
     pr_statuses: &[PRStatusInfo],
     github_statuses: Option<&HashMap<u64, GitHubPRStatus>>,
+    stale_days: u64,
+    now: DateTime<Utc>,
 ) {
     let total = pr_statuses.len();
     let with_github_pr = pr_statuses.iter().filter(|pr| pr.pr_number.is_some()).count();
@@ -227,6 +759,11 @@ fn display_summary(
         println!("   📋 {} local only", local_only);
     }
     
+    let stale = pr_statuses.iter().filter(|pr| is_stale(pr, github_statuses, stale_days, now)).count();
+    if stale > 0 {
+        println!("   ⏳ {} stale (no updates for over {} days)", stale, stale_days);
+    }
+    
     let total_incremental: usize = pr_statuses.iter().map(|pr| pr.incremental_count).sum();
     if total_incremental > 0 {
         println!("   🔄 {} total incremental update{}", total_incremental, if total_incremental == 1 { "" } else { "s" });
@@ -256,4 +793,266 @@ fn format_relative_time(timestamp: &DateTime<Utc>) -> String {
         let years = duration.num_days() / 365;
         format!("{} year{} ago", years, if years == 1 { "" } else { "s" })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_respects_color_choice() {
+        let plain = paint("abc12345", "green", ColorChoice::Never.enabled());
+        assert_eq!(plain, "abc12345");
+        assert!(!plain.contains('\x1b'));
+        
+        let colored = paint("abc12345", "green", ColorChoice::Always.enabled());
+        assert!(colored.contains('\x1b'));
+        assert!(colored.contains("abc12345"));
+    }
+
+    /// Build a status entry created `age_hours` ago and updated `updated_hours` ago
+    fn pr_info(commit: &str, pr_number: Option<u64>, status: PRStatus, age_hours: i64, updated_hours: i64) -> PRStatusInfo {
+        let now = Utc::now();
+        PRStatusInfo {
+            commit_id: commit.to_string(),
+            commit_message: format!("Commit {}", commit),
+            branch_name: format!("gitx/test/{}", commit),
+            pr_number,
+            status,
+            created_at: now - chrono::Duration::hours(age_hours),
+            last_updated: now - chrono::Duration::hours(updated_hours),
+            incremental_count: 0,
+            latest_incremental: None,
+            base_branch: None,
+            last_known_status: None,
+        }
+    }
+
+    fn stack() -> Vec<PRStatusInfo> {
+        vec![
+            pr_info("b", Some(20), PRStatus::PRCreated, 20, 1),
+            pr_info("a", Some(30), PRStatus::PRMerged, 30, 10),
+            pr_info("c", None, PRStatus::BranchCreated, 10, 5),
+            pr_info("d", Some(5), PRStatus::PRCreated, 5, 3),
+        ]
+    }
+
+    fn commit_ids(pr_statuses: &[PRStatusInfo]) -> Vec<&str> {
+        pr_statuses.iter().map(|pr| pr.commit_id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_find_untracked_prs() {
+        let mock = crate::mock_github::MockGitHubClient::new();
+        mock.create_pr("gitx/test/b", "Commit b", "Body", "main", false).await.unwrap();
+        let outside = mock.create_pr("feature/from-web", "Made on github.com", "Body", "main", false).await.unwrap();
+        
+        let untracked = find_untracked_prs(&stack(), &mock).await.unwrap();
+        
+        assert_eq!(untracked.len(), 1);
+        assert_eq!(untracked[0].branch_name, "feature/from-web");
+        assert_eq!(untracked[0].pr.number, outside.number);
+        assert_eq!(
+            format_untracked_porcelain_line(&untracked[0]),
+            format!("\tuntracked\t{}\tfeature/from-web\tMade on github.com", outside.number)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_warning_when_low() {
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let plenty = mock.rate_limit().await.unwrap();
+        mock.set_rate_limit_remaining(42);
+        let low = mock.rate_limit().await.unwrap();
+        
+        assert_eq!(rate_limit_warning(&plenty), None);
+        assert!(rate_limit_warning(&low).unwrap().contains("Only 42 GitHub API requests left"));
+        assert_eq!(
+            format_rate_limit(&low, low.reset - chrono::Duration::minutes(30)),
+            "GitHub API: 42/5000 requests left, resets in 30m"
+        );
+    }
+
+    #[test]
+    fn test_sort_by_created() {
+        let sorted = sort_and_filter(stack(), None, SortKey::Created, None);
+        assert_eq!(commit_ids(&sorted), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_sort_by_updated() {
+        let sorted = sort_and_filter(stack(), None, SortKey::Updated, None);
+        assert_eq!(commit_ids(&sorted), vec!["b", "d", "c", "a"]);
+    }
+
+    #[test]
+    fn test_sort_by_number() {
+        let sorted = sort_and_filter(stack(), None, SortKey::Number, None);
+        assert_eq!(commit_ids(&sorted), vec!["d", "b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_filter_merged() {
+        // Without GitHub data the local status decides
+        let merged = sort_and_filter(stack(), None, SortKey::Created, Some(StatusFilter::Merged));
+        assert_eq!(commit_ids(&merged), vec!["a"]);
+        
+        // GitHub state wins over stale local metadata
+        let github_statuses: HashMap<u64, GitHubPRStatus> = [(20, "merged"), (30, "closed"), (5, "open")]
+            .into_iter()
+            .map(|(number, state)| (number, GitHubPRStatus {
+                number,
+                state: state.to_string(),
+                title: String::new(),
+                url: String::new(),
+                mergeable: None,
+                draft: false,
+                additions: None,
+                deletions: None,
+                changed_files: None,
+                review_decision: ReviewDecision::None,
+                head_branch: String::new(),
+                head_sha: String::new(),
+                base_branch: String::new(),
+                merged_at: None,
+            }))
+            .collect();
+        let merged = sort_and_filter(stack(), Some(&github_statuses), SortKey::Created, Some(StatusFilter::Merged));
+        assert_eq!(commit_ids(&merged), vec!["b"]);
+    }
+
+    #[test]
+    fn test_filter_local() {
+        let local = sort_and_filter(stack(), None, SortKey::Created, Some(StatusFilter::Local));
+        assert_eq!(commit_ids(&local), vec!["c"]);
+    }
+
+    #[test]
+    fn test_csv_field_escaping() {
+        assert_eq!(csv_field("gitx/test/plain"), "gitx/test/plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_format_porcelain_line() {
+        let metadata = crate::metadata::CommitMetadata::new_branch_created(
+            "gitx/test/feature".to_string(),
+            "abc123".to_string()
+        ).with_pr_number(7);
+        let info = PRStatusInfo::from_commit_and_metadata(
+            "abc123".to_string(),
+            "Add\tfeature\n\nLonger body".to_string(),
+            &metadata
+        );
+        
+        assert_eq!(format_porcelain_line(&info), "abc123\tpr-created\t7\tgitx/test/feature\tAdd feature");
+    }
+
+    #[tokio::test]
+    async fn test_pr_size_renders_from_status() {
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let pr = mock.create_pr("gitx/test/a", "A", "Body", "main", false).await.unwrap();
+        
+        let status = mock.get_pr_status(pr.number).await.unwrap();
+        assert_eq!(format_pr_size(&status), None);
+        
+        mock.set_pr_size(pr.number, 120, 30, 4);
+        let status = mock.get_pr_status(pr.number).await.unwrap();
+        assert_eq!(format_pr_size(&status), Some("(+120 -30, 4 files)".to_string()));
+        
+        mock.set_pr_size(pr.number, 1, 0, 1);
+        let status = mock.get_pr_status(pr.number).await.unwrap();
+        assert_eq!(format_pr_size(&status), Some("(+1 -0, 1 file)".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_review_decisions_from_mock() {
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let approved = mock.create_pr("gitx/test/a", "A", "Body", "main", false).await.unwrap();
+        let unprotected = mock.create_pr("gitx/test/b", "B", "Body", "main", false).await.unwrap();
+        mock.set_review_decision(approved.number, ReviewDecision::Approved);
+        
+        let decisions = get_review_decisions(&mock, &[approved.number, unprotected.number]).await;
+        assert_eq!(decisions[&approved.number], ReviewDecision::Approved);
+        assert_eq!(decisions[&unprotected.number], ReviewDecision::None);
+        
+        assert_eq!(format_review_decision(ReviewDecision::Approved), Some("✅ Approved"));
+        assert_eq!(format_review_decision(ReviewDecision::ChangesRequested), Some("❌ Changes requested"));
+        assert_eq!(format_review_decision(ReviewDecision::None), None);
+    }
+
+    #[tokio::test]
+    async fn test_offline_display_shows_last_known_status() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(&temp_dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let signature = repo.signature().unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let oid = repo.commit(Some("HEAD"), &signature, &signature, "Add feature", &tree, &[]).unwrap();
+        
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // Online: statuses fetched from GitHub are remembered in the metadata
+        let mock = crate::mock_github::MockGitHubClient::new();
+        let pr = mock.create_pr("gitx/test/feature", "Add feature", "Body", "main", true).await.unwrap();
+        let metadata = crate::metadata::CommitMetadata::new_branch_created("gitx/test/feature".to_string(), oid.to_string())
+            .with_pr_number(pr.number);
+        crate::metadata::store_commit_metadata(&oid, &metadata).unwrap();
+        let fetched: HashMap<u64, GitHubPRStatus> = mock
+            .get_multiple_pr_statuses(&[pr.number])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|status| (status.number, status))
+            .collect();
+        let as_of = DateTime::parse_from_rfc3339("2026-01-02T03:04:00Z").unwrap().with_timezone(&Utc);
+        snapshot_github_statuses(&crate::metadata::get_all_pr_status().unwrap(), &fetched, as_of);
+        // A later fetch of the same state leaves the note, and its time, alone
+        let notes_ref = crate::config::get_notes_ref();
+        let note_before = repo.find_reference(&notes_ref).unwrap().target();
+        snapshot_github_statuses(&crate::metadata::get_all_pr_status().unwrap(), &fetched, as_of + chrono::Duration::days(1));
+        let note_after = repo.find_reference(&notes_ref).unwrap().target();
+        
+        // Offline: nothing is fetched, so the stored state is shown instead
+        let offline = crate::metadata::get_all_pr_status().unwrap();
+        
+        std::env::set_current_dir(original_dir).unwrap();
+        
+        assert_eq!(
+            github_status_lines(&offline[0], None),
+            vec![format!("🐙 PR #{}: OPEN (last known, since 2026-01-02 03:04 UTC)", pr.number), "📝 Draft PR".to_string()]
+        );
+        assert_eq!(note_before, note_after);
+        let snapshot = offline[0].last_known_status.as_ref().unwrap();
+        assert_eq!(github_state_display(&snapshot.state, snapshot.draft), ("🚧", "yellow"));
+    }
+
+    #[test]
+    fn test_status_cache_ttl_saturates() {
+        assert_eq!(status_cache_ttl(60), chrono::TimeDelta::seconds(60));
+        assert_eq!(status_cache_ttl(u64::MAX), chrono::TimeDelta::MAX);
+    }
+
+    #[test]
+    fn test_stale_marker_for_old_prs() {
+        let now = Utc::now();
+        // 20 days and 2 days without updates; merged PRs are never stale
+        let old = pr_info("a1b2c3d4e5", Some(1), PRStatus::PRCreated, 24 * 30, 24 * 20);
+        let fresh = pr_info("f6e5d4c3b2", Some(2), PRStatus::PRCreated, 24 * 30, 24 * 2);
+        let merged = pr_info("0a1b2c3d4e", Some(3), PRStatus::PRMerged, 24 * 30, 24 * 20);
+        
+        assert!(is_stale(&old, None, 14, now));
+        assert!(!is_stale(&fresh, None, 14, now));
+        assert!(!is_stale(&merged, None, 14, now));
+        assert!(!is_stale(&old, None, 30, now));
+        assert!(!is_stale(&old, None, u64::MAX, now));
+        
+        let line = format_pr_line(&old, None, is_stale(&old, None, 14, now), false);
+        assert!(line.ends_with("Commit a1b2c3d4e5 ⏳ stale"), "{}", line);
+        assert!(!format_pr_line(&fresh, None, is_stale(&fresh, None, 14, now), false).contains(STALE_MARKER));
+    }
+}
@@ -26,4 +26,33 @@ fn test_invalid_command() {
     cmd.arg("invalid-command")
         .assert()
         .failure();
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_completions_bash() {
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_gitx"))
+        .stdout(predicate::str::contains("land"));
+}
+
+#[test]
+fn test_version_command() {
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.arg("version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("gitx {}", env!("CARGO_PKG_VERSION"))))
+        .stdout(predicate::str::contains("libgit2"));
+}
+
+#[test]
+fn test_version_flag() {
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(env!("CARGO_PKG_VERSION")));
+}
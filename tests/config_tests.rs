@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod test_utils;
+use test_utils::TestRepo;
+
+#[test]
+fn test_gitx_config_set_and_get() {
+    let repo = TestRepo::with_git();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["config", "set", "github.baseBranch", "develop"])
+        .assert()
+        .success();
+    
+    assert_eq!(repo.get_git_config("gitx.github.baseBranch"), Some("develop".to_string()));
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["config", "get", "gitx.github.baseBranch"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("develop"));
+}
+
+#[test]
+fn test_gitx_config_rejects_unknown_key() {
+    let repo = TestRepo::with_git();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["config", "set", "github.colour", "blue"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown gitx setting"))
+        .stderr(predicate::str::contains("gitx.github.baseBranch"));
+}
+
+#[test]
+fn test_gitx_config_get_unset_key_fails() {
+    let repo = TestRepo::with_git();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["config", "get", "github.baseBranch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("gitx.github.baseBranch is not set"));
+}
+
+#[test]
+fn test_gitx_config_rejects_invalid_bool() {
+    let repo = TestRepo::with_git();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["config", "set", "github.enabled", "maybe"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("must be 'true' or 'false'"));
+}
+
+#[test]
+fn test_gitx_config_list_redacts_token() {
+    let repo = TestRepo::with_gitx();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["config", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("gitx.github.token = ghp_****"))
+        .stdout(predicate::str::contains("ghp_test_token_123").not())
+        .stdout(predicate::str::contains("gitx.github.baseBranch = main"));
+}
@@ -0,0 +1,53 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod test_utils;
+use test_utils::TestRepo;
+
+#[test]
+fn test_gitx_doctor_outside_git_repo() {
+    let repo = TestRepo::empty();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("❌ Inside a git repository"))
+        .stderr(predicate::str::contains("1 critical check failed"));
+}
+
+#[test]
+fn test_gitx_doctor_reports_missing_remote_and_token() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .env_remove("GITHUB_TOKEN")
+        .env("HOME", repo.path())
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("✅ Inside a git repository"))
+        .stdout(predicate::str::contains("❌ Origin remote is a GitHub repository"))
+        .stdout(predicate::str::contains("❌ GitHub token configured"))
+        .stdout(predicate::str::contains("💡"));
+}
+
+#[test]
+fn test_gitx_doctor_explains_host_mismatch() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    repo.setup_origin_remote("owner", "repo");
+    repo.set_git_config("gitx.github.host", "ghe.corp.com").unwrap();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .env_remove("GITHUB_TOKEN")
+        .env("HOME", repo.path())
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("origin remote points at github.com, but gitx.github.host is ghe.corp.com"));
+}
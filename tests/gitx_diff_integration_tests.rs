@@ -155,6 +155,27 @@ async fn test_gitx_diff_without_github_flag() {
         .stdout(predicate::str::contains("Creating PR branch for"));
 }
 
+#[test]
+fn test_gitx_diff_rejects_detached_head() {
+    let repo = TestRepo::with_commits();
+    repo.add_and_commit("local_feature.txt", "local content", "Add local feature");
+    
+    let detach = std::process::Command::new("git")
+        .args(["checkout", "--detach", "HEAD~1"])
+        .current_dir(&repo.temp_dir)
+        .output()
+        .unwrap();
+    assert!(detach.status.success());
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .arg("diff")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("gitx requires being on a branch (HEAD is detached)"))
+        .stdout(predicate::str::contains("Creating PR branch for").not());
+}
+
 #[tokio::test]
 async fn test_gitx_diff_multiple_commits() {
     let mock_server = MockServer::start().await;
@@ -181,6 +202,31 @@ async fn test_gitx_diff_multiple_commits() {
     unsafe { std::env::remove_var("GITHUB_API_BASE_URL"); }
 }
 
+#[tokio::test]
+async fn test_gitx_diff_verbose_dry_run_prints_planned_requests() {
+    let mock_server = MockServer::start().await;
+    setup_github_api_mocks(&mock_server).await;
+    
+    let repo = TestRepo::with_configured_gitx_and_commits();
+    let _remote_path = repo.setup_mock_remote();
+    repo.add_and_commit("planned.txt", "planned change", "Add planned change");
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .env("GITHUB_API_BASE_URL", mock_server.uri())
+        .args(&["--verbose", "diff", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would create PR branch for: Add planned change"))
+        .stdout(predicate::str::contains("POST /repos/test-owner/test-repo/pulls {head: gitx/"))
+        .stdout(predicate::str::contains("title: Add planned change, draft: false}"))
+        .stdout(predicate::str::contains("Created GitHub PR").not());
+    
+    // The login lookup is the only call a dry run makes
+    let requests = mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|request| request.method.as_str() == "GET"), "dry run sent {:?}", requests);
+}
+
 /// Helper function to set up common GitHub API mocks
 async fn setup_github_api_mocks(mock_server: &MockServer) {
     // Mock repository info endpoint
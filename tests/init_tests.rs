@@ -149,6 +149,99 @@ fn test_builder_style_constructors() {
         .assert_file_exists("bugfix.txt");
 }
 
+#[test]
+fn test_gitx_init_non_interactive_flags() {
+    let repo = TestRepo::with_git();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["init", "--token", "X", "--base-branch", "develop", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("gitx configuration complete"));
+    
+    assert_eq!(repo.get_git_config("gitx.github.token"), Some("X".to_string()));
+    assert_eq!(repo.get_git_config("gitx.github.baseBranch"), Some("develop".to_string()));
+    assert_eq!(repo.get_git_config("gitx.github.enabled"), Some("true".to_string()));
+    assert_eq!(repo.get_git_config("gitx.branch.autoCleanup"), Some("true".to_string()));
+}
+
+#[test]
+fn test_gitx_init_non_interactive_disable_github() {
+    let repo = TestRepo::with_git();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["init", "--enable-github", "false", "--auto-cleanup", "false"])
+        .assert()
+        .success();
+    
+    assert_eq!(repo.get_git_config("gitx.github.enabled"), Some("false".to_string()));
+    assert_eq!(repo.get_git_config("gitx.branch.autoCleanup"), Some("false".to_string()));
+    assert_eq!(repo.get_git_config("gitx.github.token"), None);
+}
+
+#[test]
+fn test_gitx_init_keeps_existing_settings() {
+    let repo = TestRepo::with_git();
+    repo.set_git_config("gitx.github.baseBranch", "develop").unwrap();
+    repo.set_git_config("gitx.branch.autoCleanup", "false").unwrap();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["init", "--token", "X"])
+        .assert()
+        .success();
+    
+    assert_eq!(repo.get_git_config("gitx.github.token"), Some("X".to_string()));
+    assert_eq!(repo.get_git_config("gitx.github.baseBranch"), Some("develop".to_string()));
+    assert_eq!(repo.get_git_config("gitx.branch.autoCleanup"), Some("false".to_string()));
+    assert_eq!(repo.get_git_config("gitx.github.enabled"), Some("true".to_string()));
+}
+
+#[test]
+fn test_gitx_init_installs_post_commit_hook() {
+    let repo = TestRepo::with_git();
+    let hooks_dir = repo.path().join(".git").join("hooks");
+    std::fs::create_dir_all(&hooks_dir).unwrap();
+    std::fs::write(hooks_dir.join("post-commit"), "#!/bin/sh\necho existing hook\n").unwrap();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["init", "--hooks", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("post-commit hook installed"));
+    
+    let hook = std::fs::read_to_string(hooks_dir.join("post-commit")).unwrap();
+    assert!(hook.contains("gitx diff --commit HEAD"));
+    assert!(hook.contains("post-commit.pre-gitx"));
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(hooks_dir.join("post-commit")).unwrap().permissions().mode();
+        assert_ne!(mode & 0o111, 0, "hook is not executable");
+    }
+    
+    // The previous hook is kept and chained
+    let chained = std::fs::read_to_string(hooks_dir.join("post-commit.pre-gitx")).unwrap();
+    assert_eq!(chained, "#!/bin/sh\necho existing hook\n");
+}
+
+#[test]
+fn test_gitx_init_no_hooks() {
+    let repo = TestRepo::with_git();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(&repo.temp_dir)
+        .args(["init", "--no-hooks"])
+        .assert()
+        .success();
+    
+    assert!(!repo.path().join(".git").join("hooks").join("post-commit").exists());
+}
+
 // Note: Testing the full interactive workflow is challenging without more sophisticated
 // input simulation. The tests above verify the core functionality that gitx init
 // depends on: git config management and git repository detection.
\ No newline at end of file
@@ -201,6 +201,71 @@ mod tests {
         assert!(stdout.contains("new branches"));
     }
     
+    #[test]
+    fn test_gitx_diff_without_tty_prints_plain_output() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        let root = test_repo.repo.head().unwrap().peel_to_commit().unwrap().id().to_string();
+        test_repo
+            .add_commit("Add authentication system", "auth code")
+            .expect("Failed to add commit");
+        test_repo
+            .add_commit("Add validation logic", "validation code")
+            .expect("Failed to add commit");
+        
+        // Output is captured, so no progress indicator is drawn
+        let output = run_gitx_command(test_repo.path(), &["--offline", "diff", "--since", &root])
+            .expect("Failed to run gitx diff --since");
+        
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(stdout.matches("Creating PR branch for").count(), 2);
+        assert!(stdout.contains("2 new branches"));
+        assert!(!stderr.contains("Creating PR 1/2"));
+        assert!(!stderr.contains('\r'));
+    }
+    
+    #[test]
+    fn test_gitx_diff_all_yes_processes_every_commit() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        test_repo
+            .add_commit("Add authentication system", "auth code")
+            .expect("Failed to add commit");
+        test_repo
+            .add_commit("Add validation logic", "validation code")
+            .expect("Failed to add commit");
+        test_repo
+            .add_commit("Add password reset", "password reset code")
+            .expect("Failed to add commit");
+        
+        // No input is piped in, so nothing could answer a prompt (the initial commit counts too)
+        let output = run_gitx_command(test_repo.path(), &["--offline", "diff", "--all", "--yes"])
+            .expect("Failed to run gitx diff --all --yes");
+        
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!stdout.contains("Select commits"));
+        assert!(stdout.contains("Processing all 4 commits"));
+        assert!(stdout.contains("4 new branches"));
+    }
+    
+    #[test]
+    fn test_gitx_diff_quiet_hides_progress_lines() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        test_repo
+            .add_commit("Add authentication system", "auth code")
+            .expect("Failed to add commit");
+        
+        let output = run_gitx_command(test_repo.path(), &["--offline", "--quiet", "diff"])
+            .expect("Failed to run gitx --quiet diff");
+        
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!stdout.contains("Created branch"));
+        assert!(!stdout.contains("Creating PR branch for"));
+        assert!(stdout.contains("1 new branches"));
+    }
+    
     #[test]
     fn test_gitx_prs_after_diff() {
         let test_repo = TestRepo::new().expect("Failed to create test repo");
@@ -224,6 +289,25 @@ mod tests {
         assert!(stdout.contains("Branch:"));
     }
     
+    #[test]
+    fn test_gitx_prs_color_never_has_no_ansi_escapes() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        
+        test_repo
+            .add_commit("Add feature Y", "feature Y code")
+            .expect("Failed to add commit");
+        
+        let _output = run_gitx_command(test_repo.path(), &["diff"])
+            .expect("Failed to run gitx diff");
+        
+        let output = run_gitx_command(test_repo.path(), &["prs", "--color", "never"])
+            .expect("Failed to run gitx prs");
+        
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!stdout.contains('\x1b'), "unexpected ANSI escape in: {}", stdout);
+    }
+    
     #[test]
     fn test_gitx_commit_passthrough() {
         let test_repo = TestRepo::new().expect("Failed to create test repo");
@@ -252,6 +336,291 @@ mod tests {
         assert_eq!(commit.message().unwrap(), "Add new file");
     }
     
+    #[test]
+    fn test_gitx_commit_auto_diff() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        test_repo.repo.config().unwrap().set_bool("gitx.commit.autoDiff", true).unwrap();
+        
+        fs::write(test_repo.path().join("new_file.txt"), "test content").expect("Failed to write test file");
+        let output = Command::new("git")
+            .args(&["add", "new_file.txt"])
+            .current_dir(test_repo.path())
+            .output()
+            .expect("Failed to run git add");
+        assert!(output.status.success());
+        
+        let output = run_gitx_command(test_repo.path(), &["commit", "-m", "Add new file"])
+            .expect("Failed to run gitx commit");
+        
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // The chained diff picked up the commit just made
+        assert!(stdout.contains("Creating PR branch for: Add new file"), "diff didn't run after commit: {}", stdout);
+    }
+    
+    #[test]
+    fn test_gitx_commit_failure_skips_auto_diff() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        test_repo.repo.config().unwrap().set_bool("gitx.commit.autoDiff", true).unwrap();
+        
+        // Nothing staged, so git commit fails
+        let output = run_gitx_command(test_repo.path(), &["commit", "-m", "Nothing to commit"])
+            .expect("Failed to run gitx commit");
+        
+        assert!(!output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!stdout.contains("Creating PR branch for"), "diff ran after a failed commit: {}", stdout);
+    }
+
+    #[test]
+    fn test_gitx_commit_no_verify_skips_hooks() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        let hook = test_repo.path().join(".git/hooks/pre-commit");
+        fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        fs::write(&hook, "#!/bin/sh\necho 'pre-commit rejected' >&2\nexit 1\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        fs::write(test_repo.path().join("new_file.txt"), "test content").expect("Failed to write test file");
+        Command::new("git").args(&["add", "new_file.txt"]).current_dir(test_repo.path()).output().unwrap();
+        
+        let rejected = run_gitx_command(test_repo.path(), &["commit", "-m", "Add new file"])
+            .expect("Failed to run gitx commit");
+        let output = run_gitx_command(test_repo.path(), &["commit", "--no-verify", "-m", "Add new file"])
+            .expect("Failed to run gitx commit");
+        
+        assert!(!rejected.status.success(), "The pre-commit hook should block a normal commit");
+        assert!(output.status.success(), "--no-verify should reach git commit: {}", String::from_utf8_lossy(&output.stderr));
+        let head = test_repo.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Add new file"));
+    }
+    
+    #[test]
+    fn test_gitx_commit_amend_syncs_pr() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        fs::write(test_repo.path().join("feature.txt"), "first draft").expect("Failed to write test file");
+        Command::new("git").args(&["add", "feature.txt"]).current_dir(test_repo.path()).output().unwrap();
+        Command::new("git").args(&["commit", "-m", "Add feature"]).current_dir(test_repo.path()).output().unwrap();
+        let diffed = run_gitx_command(test_repo.path(), &["--offline", "diff"]).expect("Failed to run gitx diff");
+        assert!(diffed.status.success());
+        let original = test_repo.repo.head().unwrap().target().unwrap();
+        
+        fs::write(test_repo.path().join("feature.txt"), "second draft").expect("Failed to write test file");
+        Command::new("git").args(&["add", "feature.txt"]).current_dir(test_repo.path()).output().unwrap();
+        let output = run_gitx_command(test_repo.path(), &["--offline", "commit", "--amend", "--no-edit"])
+            .expect("Failed to run gitx commit");
+        
+        assert!(output.status.success(), "gitx commit --amend failed: {}", String::from_utf8_lossy(&output.stderr));
+        let amended = test_repo.repo.head().unwrap().target().unwrap();
+        assert_ne!(amended, original);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Moved PR metadata to the amended commit"), "metadata wasn't moved: {}", stdout);
+        // The amendment lands on the existing PR branch instead of opening a new one
+        assert!(stdout.contains("Added incremental commit to:"), "PR wasn't synced: {}", stdout);
+        assert!(!stdout.contains("Creating PR branch for"), "a second PR was started: {}", stdout);
+    }
+    
+    #[test]
+    fn test_gitx_branch_new_refused_on_dirty_tree_leaves_no_branch() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        fs::write(test_repo.path().join("README.md"), "uncommitted edit").expect("Failed to write test file");
+        
+        let output = run_gitx_command(test_repo.path(), &["branch", "--new", "feature"]).expect("Failed to run gitx branch");
+        
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("uncommitted changes"), "switch wasn't refused: {}", stderr);
+        assert!(test_repo.repo.find_branch("feature", git2::BranchType::Local).is_err(), "refused branch was left behind");
+        
+        // Once the tree is clean the same name works
+        fs::write(test_repo.path().join("README.md"), "# Test Repository\n").expect("Failed to write test file");
+        let output = run_gitx_command(test_repo.path(), &["branch", "--new", "feature"]).expect("Failed to run gitx branch");
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Switched to a new branch: feature"));
+    }
+    
+    #[test]
+    fn test_gitx_branch_and_amend_reject_detached_head() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        let detach = Command::new("git").args(&["checkout", "--detach", "HEAD"]).current_dir(test_repo.path()).output().unwrap();
+        assert!(detach.status.success());
+        let head_before = test_repo.repo.head().unwrap().target();
+        
+        let branch = run_gitx_command(test_repo.path(), &["branch", "--new", "feature"]).expect("Failed to run gitx branch");
+        let amend = run_gitx_command(test_repo.path(), &["commit", "--amend", "-m", "Reworded"]).expect("Failed to run gitx commit");
+        
+        for output in [&branch, &amend] {
+            assert!(!output.status.success());
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(stderr.contains("gitx requires being on a branch (HEAD is detached)"), "unexpected error: {}", stderr);
+        }
+        assert!(test_repo.repo.find_branch("feature", git2::BranchType::Local).is_err());
+        assert_eq!(test_repo.repo.head().unwrap().target(), head_before, "the commit shouldn't have been amended");
+    }
+    
+    #[test]
+    fn test_gitx_diff_opens_one_pr_for_a_group() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        for (file, message) in [("api.txt", "Add search API"), ("index.txt", "Add search index"), ("ui.txt", "Add search UI")] {
+            fs::write(test_repo.path().join(file), message).expect("Failed to write test file");
+            Command::new("git").args(&["add", file]).current_dir(test_repo.path()).output().unwrap();
+            let message = format!("{}\n\nGitx-Group: search", message);
+            Command::new("git").args(&["commit", "-m", &message]).current_dir(test_repo.path()).output().unwrap();
+        }
+        
+        // Plain `gitx diff` only takes the latest commit, which pulls in the rest of its group
+        let output = run_gitx_command(test_repo.path(), &["--offline", "diff"]).expect("Failed to run gitx diff");
+        
+        assert!(output.status.success(), "gitx diff failed: {}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Creating PR branch for: Add search API"), "group wasn't folded: {}", stdout);
+        assert!(stdout.contains("Completed: 1 new branches, 0 incremental updates"), "{}", stdout);
+        let again = run_gitx_command(test_repo.path(), &["--offline", "diff"]).expect("Failed to run gitx diff");
+        let stdout = String::from_utf8_lossy(&again.stdout);
+        assert!(stdout.contains("No new commits or updates to process"), "group was split: {}", stdout);
+    }
+    
+    #[test]
+    fn test_gitx_reorder_swaps_commits_and_updates_prs() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        for (file, message) in [("first.txt", "Add first feature"), ("second.txt", "Add second feature")] {
+            fs::write(test_repo.path().join(file), message).expect("Failed to write test file");
+            Command::new("git").args(&["add", file]).current_dir(test_repo.path()).output().unwrap();
+            Command::new("git").args(&["commit", "-m", message]).current_dir(test_repo.path()).output().unwrap();
+            let diffed = run_gitx_command(test_repo.path(), &["--offline", "diff"]).expect("Failed to run gitx diff");
+            assert!(diffed.status.success());
+        }
+        let second = test_repo.repo.head().unwrap().peel_to_commit().unwrap();
+        let first = second.parent(0).unwrap();
+        
+        let output = run_gitx_command(test_repo.path(), &["--offline", "reorder", &second.id().to_string(), &first.id().to_string()])
+            .expect("Failed to run gitx reorder");
+        
+        assert!(output.status.success(), "gitx reorder failed: {}", String::from_utf8_lossy(&output.stderr));
+        let head = test_repo.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Add first feature"));
+        assert_eq!(head.parent(0).unwrap().summary(), Some("Add second feature"));
+        assert!(test_repo.path().join("first.txt").exists() && test_repo.path().join("second.txt").exists());
+        // Both commits moved, so both PR branches get an incremental update
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.matches("Added incremental commit to:").count(), 2, "PRs weren't synced: {}", stdout);
+    }
+    
+    #[test]
+    fn test_gitx_drop_restacks_commits_above() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        for (file, message) in [("first.txt", "Add first feature"), ("second.txt", "Add second feature"), ("third.txt", "Add third feature")] {
+            fs::write(test_repo.path().join(file), message).expect("Failed to write test file");
+            Command::new("git").args(&["add", file]).current_dir(test_repo.path()).output().unwrap();
+            Command::new("git").args(&["commit", "-m", message]).current_dir(test_repo.path()).output().unwrap();
+            let diffed = run_gitx_command(test_repo.path(), &["--offline", "diff"]).expect("Failed to run gitx diff");
+            assert!(diffed.status.success());
+        }
+        let third = test_repo.repo.head().unwrap().peel_to_commit().unwrap();
+        let second = third.parent(0).unwrap();
+        let first = second.parent(0).unwrap();
+        
+        let output = run_gitx_command(test_repo.path(), &["--offline", "drop", &second.id().to_string()])
+            .expect("Failed to run gitx drop");
+        
+        assert!(output.status.success(), "gitx drop failed: {}", String::from_utf8_lossy(&output.stderr));
+        let head = test_repo.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Add third feature"));
+        assert_eq!(head.parent_id(0).unwrap(), first.id());
+        assert!(!test_repo.path().join("second.txt").exists());
+        assert!(test_repo.path().join("third.txt").exists());
+        // Only the commit above the dropped one moved, so only its PR is updated
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.matches("Added incremental commit to:").count(), 1, "PR wasn't synced: {}", stdout);
+    }
+    
+    #[test]
+    fn test_gitx_insert_splices_commit_into_stack() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        for (file, message) in [("first.txt", "Add first feature"), ("second.txt", "Add second feature")] {
+            fs::write(test_repo.path().join(file), message).expect("Failed to write test file");
+            Command::new("git").args(&["add", file]).current_dir(test_repo.path()).output().unwrap();
+            Command::new("git").args(&["commit", "-m", message]).current_dir(test_repo.path()).output().unwrap();
+            let diffed = run_gitx_command(test_repo.path(), &["--offline", "diff"]).expect("Failed to run gitx diff");
+            assert!(diffed.status.success());
+        }
+        let first = test_repo.repo.head().unwrap().peel_to_commit().unwrap().parent(0).unwrap();
+        fs::write(test_repo.path().join("fix.txt"), "fix").expect("Failed to write test file");
+        Command::new("git").args(&["add", "fix.txt"]).current_dir(test_repo.path()).output().unwrap();
+        
+        let output = run_gitx_command(test_repo.path(), &["--offline", "insert", "-m", "Fix first feature"])
+            .expect("Failed to run gitx insert");
+        
+        assert!(output.status.success(), "gitx insert failed: {}", String::from_utf8_lossy(&output.stderr));
+        let head = test_repo.repo.head().unwrap().peel_to_commit().unwrap();
+        let inserted = head.parent(0).unwrap();
+        assert_eq!(head.summary(), Some("Add second feature"));
+        assert_eq!(inserted.summary(), Some("Fix first feature"));
+        assert_eq!(inserted.parent_id(0).unwrap(), first.id());
+        assert!(inserted.tree().unwrap().get_name("fix.txt").is_some());
+        // The new commit gets its own PR and the one above it is updated
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Creating PR branch for: Fix first feature"), "no PR for the new commit: {}", stdout);
+        assert_eq!(stdout.matches("Added incremental commit to:").count(), 1, "PR above wasn't synced: {}", stdout);
+    }
+    
+    #[test]
+    fn test_gitx_insert_refuses_unstaged_changes() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        for (file, message) in [("first.txt", "Add first feature"), ("second.txt", "Add second feature")] {
+            fs::write(test_repo.path().join(file), message).expect("Failed to write test file");
+            Command::new("git").args(&["add", file]).current_dir(test_repo.path()).output().unwrap();
+            Command::new("git").args(&["commit", "-m", message]).current_dir(test_repo.path()).output().unwrap();
+        }
+        let head_before = test_repo.repo.head().unwrap().peel_to_commit().unwrap().id();
+        fs::write(test_repo.path().join("fix.txt"), "fix").expect("Failed to write test file");
+        Command::new("git").args(&["add", "fix.txt"]).current_dir(test_repo.path()).output().unwrap();
+        fs::write(test_repo.path().join("second.txt"), "work in progress").expect("Failed to write test file");
+        
+        let output = run_gitx_command(test_repo.path(), &["--offline", "insert", "-m", "Fix first feature"])
+            .expect("Failed to run gitx insert");
+        
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("unstaged changes"), "insert wasn't refused: {}", stderr);
+        assert_eq!(test_repo.repo.head().unwrap().peel_to_commit().unwrap().id(), head_before);
+        assert_eq!(fs::read_to_string(test_repo.path().join("second.txt")).unwrap(), "work in progress");
+    }
+    
+    #[test]
+    fn test_gitx_diff_commits_staged_changes() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+
+        fs::write(test_repo.path().join("new_file.txt"), "test content").expect("Failed to write test file");
+        let output = Command::new("git")
+            .args(&["add", "new_file.txt"])
+            .current_dir(test_repo.path())
+            .output()
+            .expect("Failed to run git add");
+        assert!(output.status.success());
+
+        let output = run_gitx_command(test_repo.path(), &["diff", "-m", "Add new file"])
+            .expect("Failed to run gitx diff");
+
+        assert!(output.status.success(), "gitx diff -m failed: {}", String::from_utf8_lossy(&output.stderr));
+        let head = test_repo.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Add new file"));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Creating PR branch for: Add new file"), "diff didn't run for the new commit: {}", stdout);
+    }
+
+    #[test]
+    fn test_gitx_diff_message_requires_staged_changes() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        let head_before = test_repo.repo.head().unwrap().target();
+
+        let output = run_gitx_command(test_repo.path(), &["diff", "-m", "Nothing to commit"])
+            .expect("Failed to run gitx diff");
+
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Nothing is staged to commit"));
+        assert_eq!(test_repo.repo.head().unwrap().target(), head_before);
+    }
+
     #[test]
     fn test_gitx_status_passthrough() {
         let test_repo = TestRepo::new().expect("Failed to create test repo");
@@ -267,6 +636,28 @@ mod tests {
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
         assert!(stdout.contains("unstaged.txt"));
+        assert!(!stdout.contains("Stack:"));
+    }
+    
+    #[test]
+    fn test_gitx_status_stack_lists_undiffed_commit() {
+        let test_repo = TestRepo::new().expect("Failed to create test repo");
+        
+        let commit_id = test_repo
+            .add_commit("Add feature W", "feature W code")
+            .expect("Failed to add commit");
+        
+        let output = run_gitx_command(test_repo.path(), &["status", "--stack"])
+            .expect("Failed to run gitx status --stack");
+        
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout
+            .lines()
+            .find(|line| line.contains("Add feature W"))
+            .expect("Stack should list the new commit");
+        assert!(line.contains(&commit_id.to_string()[..7]));
+        assert!(line.contains("no PR"));
     }
     
     #[test]
@@ -0,0 +1,52 @@
+use assert_cmd::Command;
+use gitx::metadata::CommitMetadata;
+use predicates::prelude::*;
+
+mod test_utils;
+use test_utils::{run_git, TestRepo};
+
+#[test]
+fn test_gitx_land_dry_run_without_token() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("feature.txt", "feature", "Add feature");
+    
+    // Track the commit as an open PR
+    let sha = run_git(repo.path(), &["rev-parse", "HEAD"]);
+    let metadata = CommitMetadata::new_branch_created("gitx/test/add-feature".to_string(), sha.clone())
+        .with_pr_number(7);
+    let json = serde_json::to_string(&metadata).unwrap();
+    run_git(repo.path(), &["notes", "--ref=refs/notes/gitx-metadata", "add", "-m", &json, &sha]);
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(repo.path())
+        .env_remove("GITHUB_TOKEN")
+        .args(["land", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR #7"))
+        .stdout(predicate::str::contains("state unknown"))
+        .stdout(predicate::str::contains("Would delete remote branch: gitx/test/add-feature"))
+        .stderr(predicate::str::contains("GITHUB_TOKEN").not());
+}
+
+#[test]
+fn test_gitx_land_dry_run_keeps_remote_when_auto_cleanup_off() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("feature.txt", "feature", "Add feature");
+    run_git(repo.path(), &["config", "gitx.branch.autoCleanup", "false"]);
+    
+    let sha = run_git(repo.path(), &["rev-parse", "HEAD"]);
+    let metadata = CommitMetadata::new_branch_created("gitx/test/add-feature".to_string(), sha.clone())
+        .with_pr_number(7);
+    let json = serde_json::to_string(&metadata).unwrap();
+    run_git(repo.path(), &["notes", "--ref=refs/notes/gitx-metadata", "add", "-m", &json, &sha]);
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(repo.path())
+        .env_remove("GITHUB_TOKEN")
+        .args(["land", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would keep remote branch: gitx/test/add-feature"))
+        .stdout(predicate::str::contains("Would delete remote branch").not());
+}
@@ -0,0 +1,113 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod test_utils;
+use test_utils::{run_git, TestRepo};
+
+/// Create a repository with one commit pushed to a local bare "origin"
+fn repo_with_local_origin() -> (TestRepo, assert_fs::TempDir) {
+    let remote = assert_fs::TempDir::new().unwrap();
+    run_git(remote.path(), &["init", "--bare"]);
+    
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    let remote_url = format!("file://{}", remote.path().display());
+    run_git(repo.path(), &["remote", "add", "origin", &remote_url]);
+    run_git(repo.path(), &["push", "origin", "HEAD:refs/heads/main"]);
+    
+    (repo, remote)
+}
+
+#[test]
+fn test_gitx_push_and_fetch_notes() {
+    let (repo, remote) = repo_with_local_origin();
+    run_git(repo.path(), &["notes", "--ref=refs/notes/gitx-metadata", "add", "-m", "shared metadata", "HEAD"]);
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(repo.path())
+        .arg("push-notes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pushed gitx metadata"));
+    
+    // A second clone picks the metadata up with fetch-notes
+    let clone = assert_fs::TempDir::new().unwrap();
+    let remote_url = format!("file://{}", remote.path().display());
+    run_git(clone.path(), &["clone", "--branch", "main", &remote_url, "."]);
+    run_git(clone.path(), &["config", "user.name", "Other User"]);
+    run_git(clone.path(), &["config", "user.email", "other@example.com"]);
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(clone.path())
+        .arg("fetch-notes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fetched gitx metadata"));
+    
+    let note = run_git(clone.path(), &["notes", "--ref=refs/notes/gitx-metadata", "show", "HEAD"]);
+    assert_eq!(note.trim(), "shared metadata");
+}
+
+#[test]
+fn test_gitx_push_and_fetch_notes_with_configured_remote() {
+    let remote = assert_fs::TempDir::new().unwrap();
+    run_git(remote.path(), &["init", "--bare"]);
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    let remote_url = format!("file://{}", remote.path().display());
+    run_git(repo.path(), &["remote", "add", "upstream", &remote_url]);
+    run_git(repo.path(), &["push", "upstream", "HEAD:refs/heads/main"]);
+    run_git(repo.path(), &["config", "gitx.remote", "upstream"]);
+    run_git(repo.path(), &["notes", "--ref=refs/notes/gitx-metadata", "add", "-m", "shared metadata", "HEAD"]);
+    
+    // There is no origin at all, so every step has to go through upstream
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(repo.path())
+        .arg("push-notes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pushed gitx metadata to upstream"));
+    
+    let clone = assert_fs::TempDir::new().unwrap();
+    run_git(clone.path(), &["clone", "--origin", "upstream", "--branch", "main", &remote_url, "."]);
+    run_git(clone.path(), &["config", "user.name", "Other User"]);
+    run_git(clone.path(), &["config", "user.email", "other@example.com"]);
+    run_git(clone.path(), &["config", "gitx.remote", "upstream"]);
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(clone.path())
+        .arg("fetch-notes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fetched gitx metadata from upstream"));
+    
+    let note = run_git(clone.path(), &["notes", "--ref=refs/notes/gitx-metadata", "show", "HEAD"]);
+    assert_eq!(note.trim(), "shared metadata");
+    run_git(clone.path(), &["rev-parse", "--verify", "refs/notes/upstream/gitx-metadata"]);
+}
+
+#[test]
+fn test_gitx_fetch_notes_without_remote_metadata() {
+    let (repo, _remote) = repo_with_local_origin();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(repo.path())
+        .arg("fetch-notes")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Origin has no gitx metadata yet"));
+}
+
+#[test]
+fn test_gitx_init_share_notes_adds_refspec() {
+    let (repo, _remote) = repo_with_local_origin();
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd.current_dir(repo.path())
+        .args(["init", "--share-notes", "--yes"])
+        .assert()
+        .success();
+    
+    let refspecs = run_git(repo.path(), &["config", "--get-all", "remote.origin.fetch"]);
+    assert!(refspecs.contains("+refs/notes/gitx-metadata:refs/notes/origin/gitx-metadata"));
+}
@@ -0,0 +1,171 @@
+use assert_cmd::Command;
+use gitx::metadata::CommitMetadata;
+use std::process::Command as StdCommand;
+
+mod test_utils;
+use test_utils::{commit_tracked, TestRepo};
+
+#[test]
+fn test_gitx_prs_porcelain() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    
+    let first = commit_tracked(
+        &repo,
+        "one.txt",
+        "Add first feature",
+        CommitMetadata::new_branch_created("gitx/test/add-first-feature".to_string(), String::new()).with_pr_number(12),
+    );
+    let second = commit_tracked(
+        &repo,
+        "two.txt",
+        "Add second feature",
+        CommitMetadata::new_branch_created("gitx/test/add-second-feature".to_string(), String::new()),
+    );
+    
+    let output = Command::cargo_bin("gitx").unwrap()
+        .current_dir(repo.path())
+        .args(["prs", "--porcelain"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout.lines().map(|line| line.split('\t').collect()).collect();
+    
+    assert_eq!(rows, vec![
+        vec![first.as_str(), "pr-created", "12", "gitx/test/add-first-feature", "Add first feature"],
+        vec![second.as_str(), "branch-created", "", "gitx/test/add-second-feature", "Add second feature"],
+    ]);
+}
+
+/// Split a CSV line into fields, honouring quoted fields with doubled quotes
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+#[test]
+fn test_gitx_prs_csv() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    
+    let sha = commit_tracked(
+        &repo,
+        "one.txt",
+        "Add first feature",
+        CommitMetadata::new_branch_created("gitx/test/add-\"first\",feature".to_string(), String::new()).with_pr_number(12),
+    );
+    
+    let output = Command::cargo_bin("gitx").unwrap()
+        .current_dir(repo.path())
+        .args(["prs", "--csv"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<String>> = stdout.lines().map(parse_csv_line).collect();
+    
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0], ["commit", "branch", "pr_number", "status", "created_at", "incremental_count"]);
+    assert_eq!(rows[1][..4], [sha.as_str(), "gitx/test/add-\"first\",feature", "12", "pr-created"]);
+    assert!(chrono::DateTime::parse_from_rfc3339(&rows[1][4]).is_ok());
+    assert_eq!(rows[1][5], "0");
+}
+
+#[test]
+fn test_gitx_prs_in_bare_repository() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let init = StdCommand::new("git")
+        .args(["init", "--bare"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to run git");
+    assert!(init.status.success());
+    
+    let output = Command::cargo_bin("gitx").unwrap()
+        .current_dir(temp_dir.path())
+        .arg("prs")
+        .output()
+        .unwrap();
+    
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("gitx cannot operate in a bare repository"), "unexpected stderr: {}", stderr);
+}
+
+#[test]
+fn test_gitx_prs_filter_local() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    
+    commit_tracked(
+        &repo,
+        "one.txt",
+        "Add tracked PR",
+        CommitMetadata::new_branch_created("gitx/test/add-tracked-pr".to_string(), String::new()).with_pr_number(3),
+    );
+    let local = commit_tracked(
+        &repo,
+        "two.txt",
+        "Add local branch",
+        CommitMetadata::new_branch_created("gitx/test/add-local-branch".to_string(), String::new()),
+    );
+    
+    let output = Command::cargo_bin("gitx").unwrap()
+        .current_dir(repo.path())
+        .args(["prs", "--porcelain", "--filter", "local"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let shas: Vec<&str> = stdout.lines().map(|line| line.split('\t').next().unwrap()).collect();
+    assert_eq!(shas, vec![local.as_str()]);
+}
+
+#[test]
+fn test_gitx_prs_offline_shows_stored_metadata() {
+    let repo = TestRepo::with_gitx(); // Token configured, so online mode would call GitHub
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    
+    commit_tracked(
+        &repo,
+        "one.txt",
+        "Add offline feature",
+        CommitMetadata::new_branch_created("gitx/test/add-offline-feature".to_string(), String::new()).with_pr_number(4),
+    );
+    
+    for (args, env) in [(vec!["--offline", "prs"], None), (vec!["prs"], Some(("GITX_OFFLINE", "1")))] {
+        let mut cmd = Command::cargo_bin("gitx").unwrap();
+        cmd.current_dir(repo.path()).args(&args);
+        if let Some((key, value)) = env {
+            cmd.env(key, value);
+        }
+        
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stdout.contains("Offline"), "missing offline marker: {}", stdout);
+        assert!(stdout.contains("Add offline feature"));
+        assert!(stdout.contains("PR #4"));
+        assert!(!stderr.contains("Could not fetch GitHub PR statuses"), "contacted GitHub: {}", stderr);
+    }
+}
\ No newline at end of file
@@ -39,4 +39,47 @@ async fn test_simple_workflow_with_cli_and_mock() {
         .stdout(predicate::str::contains("(transient branch deleted locally)"))
         .stderr(predicate::str::is_empty()); // Assert no error logs
 
-}
\ No newline at end of file
+}
+
+/// `gitx diff --stdin` processes exactly the piped commits, bottom of the stack first
+#[tokio::test]
+async fn test_diff_processes_commits_read_from_stdin() {
+    let repo = TestRepo::with_gitx();
+    repo.set_git_config("gitx.github.token", "mock_token").unwrap();
+    let _remote_path = repo.setup_mock_remote();
+    
+    let mut shas = Vec::new();
+    for (file, message) in [("one.txt", "Add one"), ("two.txt", "Add two"), ("three.txt", "Add three")] {
+        repo.add_and_commit(file, message, message);
+        let output = std::process::Command::new("git")
+            .args(["rev-parse", "--short", "HEAD"])
+            .current_dir(&repo.temp_dir)
+            .output()
+            .unwrap();
+        shas.push(String::from_utf8_lossy(&output.stdout).trim().to_string());
+    }
+    
+    // Only the first and last commits are piped in; the middle one is left alone
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd
+        .current_dir(&repo.temp_dir)
+        .env("GITX_USE_MOCK_GITHUB", "1")
+        .args(["diff", "--stdin"])
+        .write_stdin(format!("{}\n\n{}\n", shas[2], shas[0]))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("(?s)Mock: Creating PR: gitx/mock-user/add-one.*Mock: Creating PR: gitx/mock-user/add-three").unwrap())
+        .stdout(predicate::str::contains("add-two").not())
+        .stdout(predicate::str::contains("Completed: 2 new branches, 0 incremental updates"));
+    
+    let mut cmd = Command::cargo_bin("gitx").unwrap();
+    cmd
+        .current_dir(&repo.temp_dir)
+        .env("GITX_USE_MOCK_GITHUB", "1")
+        .args(["diff", "--stdin"])
+        .write_stdin(format!("{}\ndeadbeef\n", shas[1]))
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Created GitHub PR").not())
+        .stderr(predicate::str::contains("Unknown commits or not reachable from HEAD: deadbeef"));
+}
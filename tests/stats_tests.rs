@@ -0,0 +1,64 @@
+use assert_cmd::Command;
+use gitx::metadata::{CommitMetadata, IncrementalCommitType};
+
+mod test_utils;
+use test_utils::{commit_tracked, TestRepo};
+
+#[test]
+fn test_gitx_stats_json_counts() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    
+    commit_tracked(
+        &repo,
+        "one.txt",
+        "Add merged feature",
+        CommitMetadata::new_branch_created("gitx/test/add-merged-feature".to_string(), String::new())
+            .with_pr_number(1)
+            .mark_merged(),
+    );
+    commit_tracked(
+        &repo,
+        "two.txt",
+        "Add open feature",
+        CommitMetadata::new_branch_created("gitx/test/add-open-feature".to_string(), String::new())
+            .with_pr_number(2)
+            .add_incremental_commit("abc123".to_string(), "Fix typo".to_string(), IncrementalCommitType::AmendedCommit),
+    );
+    commit_tracked(
+        &repo,
+        "three.txt",
+        "Add local feature",
+        CommitMetadata::new_branch_created("gitx/test/add-local-feature".to_string(), String::new()),
+    );
+    
+    let output = Command::cargo_bin("gitx").unwrap()
+        .current_dir(repo.path())
+        .args(["--offline", "stats", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["total"], 3);
+    assert_eq!(stats["open"], 1);
+    assert_eq!(stats["merged"], 1);
+    assert_eq!(stats["closed"], 0);
+    assert_eq!(stats["local"], 1);
+    assert!(stats["average_hours_to_merge"].is_number());
+    assert!((stats["average_incremental_updates"].as_f64().unwrap() - 1.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_gitx_stats_without_prs() {
+    let repo = TestRepo::with_git();
+    repo.add_and_commit("README.md", "# Test", "Initial commit");
+    
+    let output = Command::cargo_bin("gitx").unwrap()
+        .current_dir(repo.path())
+        .args(["--offline", "stats"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("No stacked PRs found"));
+}
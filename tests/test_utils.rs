@@ -305,6 +305,28 @@ impl TestRepo {
 
 
 
+/// Run a git command in `dir` and return its trimmed stdout
+pub fn run_git(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = StdCommand::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("Failed to run git");
+    
+    assert!(output.status.success(), "git {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Commit a file and attach gitx metadata to the new commit, returning its SHA
+pub fn commit_tracked(repo: &TestRepo, filename: &str, message: &str, metadata: gitx::metadata::CommitMetadata) -> String {
+    repo.add_and_commit(filename, message, message);
+    let sha = run_git(repo.path(), &["rev-parse", "HEAD"]);
+    
+    let json = serde_json::to_string(&metadata).unwrap();
+    run_git(repo.path(), &["notes", "--ref=refs/notes/gitx-metadata", "add", "-m", &json, &sha]);
+    sha
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;